@@ -19,13 +19,19 @@
     clippy::panic
 )]
 
+use std::{
+    error::Error,
+    fmt::{Display, Formatter, Result as FmtResult},
+    str::FromStr,
+};
+
 use colored::Color as Colored;
 use serde::{Deserialize, Serialize};
 
 pub use colored;
 
 /// Basic colors that things can be in the `holding` world.
-#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
 #[allow(missing_docs)]
 pub enum Color {
     Black,
@@ -52,3 +58,34 @@ impl From<Color> for Colored {
         }
     }
 }
+
+/// Returned when parsing a color that doesn't exist.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownColorError(pub String);
+
+impl Display for UnknownColorError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{} is not a known color", self.0)
+    }
+}
+
+impl Error for UnknownColorError {}
+
+/// Parses a color from its (case-insensitive) name.
+impl FromStr for Color {
+    type Err = UnknownColorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "black" => Ok(Color::Black),
+            "red" => Ok(Color::Red),
+            "green" => Ok(Color::Green),
+            "yellow" => Ok(Color::Yellow),
+            "blue" => Ok(Color::Blue),
+            "magenta" => Ok(Color::Magenta),
+            "cyan" => Ok(Color::Cyan),
+            "white" => Ok(Color::White),
+            _ => Err(UnknownColorError(s.to_string())),
+        }
+    }
+}
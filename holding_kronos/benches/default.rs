@@ -0,0 +1,84 @@
+//! Compares the allocations made by building the default calendar
+//! against those made by sharing one.
+//!
+//! Run with `cargo bench -p holding_kronos -- --nocapture` to see
+//! the number of allocations each call makes.
+
+#![feature(test)]
+
+extern crate test;
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use holding_kronos::{calendar::Calendar, datetime::DateTime};
+use test::{black_box, Bencher};
+
+/// Passes everything on to the system allocator,
+/// counting the allocations as they happen.
+struct Counting;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+const CALLS: usize = 1000;
+
+/// Counts the allocations made by calling `f` many times and
+/// prints them per call, before timing it with the bencher. The
+/// first call is not counted, so that the shared calendar is only
+/// measured once it has been built.
+fn count_allocations<T>(b: &mut Bencher, name: &str, f: impl Fn() -> T) -> usize {
+    black_box(f());
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    for _ in 0..CALLS {
+        black_box(f());
+    }
+    let count = ALLOCATIONS.load(Ordering::Relaxed) - before;
+    println!("{}: {} allocations per call", name, count / CALLS);
+
+    b.iter(|| black_box(f()));
+    count
+}
+
+#[bench]
+fn build_default(b: &mut Bencher) {
+    assert!(count_allocations(b, "build_default", Calendar::default) >= CALLS);
+}
+
+#[bench]
+fn shared_default(b: &mut Bencher) {
+    assert_eq!(count_allocations(b, "shared_default", Calendar::shared), 0);
+}
+
+#[bench]
+fn datetime_with_default(b: &mut Bencher) {
+    let count = count_allocations(b, "datetime_with_default", || {
+        let cal = Calendar::default();
+        DateTime::from_seconds(black_box(1_000_000), &cal).total_seconds()
+    });
+    assert!(count >= CALLS);
+}
+
+#[bench]
+fn datetime_with_shared(b: &mut Bencher) {
+    let count = count_allocations(b, "datetime_with_shared", || {
+        let cal = Calendar::shared();
+        DateTime::from_seconds(black_box(1_000_000), cal).total_seconds()
+    });
+    assert_eq!(count, 0);
+}
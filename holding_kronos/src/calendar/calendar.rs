@@ -1,4 +1,5 @@
-#[cfg(feature = "parse")]
+use std::{cmp::Reverse, convert::TryInto};
+
 use lazy_static::lazy_static;
 #[cfg(feature = "parse")]
 use regex::Regex;
@@ -10,42 +11,446 @@ use thiserror::Error;
 use crate::{
     calendar::{
         traits::{ConvertDate, ConvertTime, DayCycle, YearCycle},
-        Day, Month, Week, Year,
+        Day, Era, Eras, LeapRule, Month, Week, Year, YearZero,
     },
     datetime::traits::ShowDate,
     datetime::traits::ShowTime,
     datetime::InvalidDateTimeError,
     datetime::{
-        traits::{ModifyDate, ModifyDateTime},
-        DateTime, InvalidDateError, InvalidTimeError, InvalidWaitError, Time, TimeFormat,
-        WaitTarget,
+        traits::ModifyDateTime, DateTime, Duration, InvalidDateError, InvalidTimeError,
+        InvalidWaitError, RawDate, Time, TimeFormat, TimeOfDay, WaitTarget,
     },
+    util::div_rem,
 };
 
 use super::{traits::WeekCycle, week::WeekDay};
+#[cfg(feature = "parse")]
+use crate::datetime::RawDateTime;
 
 #[cfg(feature = "parse")]
 lazy_static! {
     static ref DATE: Regex = Regex::new(r"^(?P<y>\d+)-(?P<m>\d+)-(?P<d>\d+)$").expect("compiles");
-    static ref REL: Regex = Regex::new(r"(?P<val>\d+)(?P<suff>(mo|[ywdhms]))").expect("compiles");
+    static ref REL: Regex = Regex::new(r"^(\d+(mo|[ywdhms]))+$").expect("compiles");
+    static ref REL_PART: Regex =
+        Regex::new(r"(?P<val>\d+)(?P<suff>(mo|[ywdhms]))").expect("compiles");
     static ref TIME: Regex = Regex::new(r"^(?P<val>\d+)(?P<suff>(am|pm))$").expect("compiles");
+    static ref DAY_MONTH_YEAR: Regex =
+        Regex::new(r"^(?P<d>\d+)(st|nd|rd|th)?\s+(?P<m>[^\d,]+?)\s+(?P<y>\d+)$").expect("compiles");
+    static ref MONTH_DAY_YEAR: Regex =
+        Regex::new(r"^(?P<m>[^\d,]+?)\s+(?P<d>\d+)(st|nd|rd|th)?,?\s+(?P<y>\d+)$")
+            .expect("compiles");
+}
+
+lazy_static! {
+    static ref DEFAULT: Calendar = Calendar::default();
 }
 
 /// A calendar provides a frame of reference for the manipulation
 /// of `DateTime`. It defines what a day, week, or month is, and
 /// since those can change.
+///
+/// When deserializing, any missing field is taken from the default
+/// calendar, so calendars saved before a field existed still load.
 #[derive(Clone, Debug, Eq, PartialEq, Default)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct Calendar {
     year: Year,
     week: Week,
     day: Day,
+
+    /// The number of days the week is shifted by, so
+    /// that changes to the week can preserve old dates.
+    week_offset: u32,
+
+    /// Named cycles that run alongside the week,
+    /// such as a market day cycle.
+    cycles: Vec<(String, Week)>,
+
+    /// Custom names for each `TimeOfDay`, overriding the defaults.
+    period_names: [Option<String>; 8],
+
+    /// The eras that years can be counted from.
+    eras: Eras,
+
+    /// Which years are leap years, if any.
+    leap_rule: Option<LeapRule>,
+
+    /// Whether the year before year 1 is year 0.
+    year_zero: YearZero,
+
+    /// The pattern used to show dates to people, if
+    /// not the ISO-like form used for storage.
+    display_format: Option<String>,
 }
 
 impl Calendar {
     /// Creates a new Calendar given a number of months, week days, and eras.
     pub fn new(year: Year, week: Week, day: Day) -> Self {
-        Self { year, week, day }
+        Self {
+            year,
+            week,
+            day,
+            week_offset: 0,
+            cycles: vec![],
+            period_names: Default::default(),
+            eras: Eras::default(),
+            leap_rule: None,
+            year_zero: YearZero::default(),
+            display_format: None,
+        }
+    }
+
+    /// Gets the default calendar without building it again. This
+    /// is much cheaper than `Calendar::default`, which allocates
+    /// every month and week day, when the calendar is only read.
+    pub fn shared() -> &'static Calendar {
+        &DEFAULT
+    }
+
+    /// Shifts the week so that a given date falls on a given
+    /// (1-indexed) week day. This is useful when changing the
+    /// length of the week, to keep established dates in place.
+    pub fn reanchor_week(
+        &mut self,
+        known_date: RawDate,
+        desired_weekday: u32,
+    ) -> Result<(), InvalidDateError> {
+        let week_length = self.days_in_week();
+        if desired_weekday == 0 || desired_weekday > week_length {
+            return Err(InvalidDateError::WeekDayOutOfBounds(desired_weekday));
+        }
+
+        self.week_offset = 0;
+        let current = self.week_day_of(known_date);
+        self.week_offset = (desired_weekday + week_length - current) % week_length;
+        Ok(())
+    }
+
+    /// Gets the (1-indexed) week day of a given date.
+    pub(crate) fn week_day_of(&self, date: RawDate) -> u32 {
+        let week_length = i64::from(self.days_in_week());
+        let days_so_far = self.days_since_epoch(date) + i64::from(self.week_offset);
+        let week_day: u32 = days_so_far
+            .rem_euclid(week_length)
+            .try_into()
+            .expect("This is less than a u32");
+
+        week_day + 1
+    }
+
+    /// Adds a named cycle that runs alongside the week.
+    /// Any existing cycle with the same name is replaced.
+    pub fn add_cycle(&mut self, name: String, cycle: Week) {
+        self.cycles.retain(|(n, _)| *n != name);
+        self.cycles.push((name, cycle));
+    }
+
+    /// Gets the named cycles that run alongside the week.
+    pub fn cycles(&self) -> &[(String, Week)] {
+        &self.cycles
+    }
+
+    /// Gets a named cycle, if it exists.
+    pub fn cycle(&self, name: &str) -> Option<&Week> {
+        self.cycles.iter().find(|(n, _)| n == name).map(|(_, c)| c)
+    }
+
+    /// Gets the (1-indexed) position and name of a given
+    /// date in some named cycle, if that cycle exists.
+    pub(crate) fn cycle_day_of(&self, date: RawDate, name: &str) -> Option<(u32, &str)> {
+        let cycle = self.cycle(name)?;
+        let length = i64::from(cycle.days_in_week());
+        if length == 0 {
+            return None;
+        }
+
+        let index: u32 = self
+            .days_since_epoch(date)
+            .rem_euclid(length)
+            .try_into()
+            .expect("This is less than a u32");
+
+        Some((index + 1, &cycle.as_slice()[index as usize].name))
+    }
+
+    /// Gives a custom name to a period of the day, such as
+    /// calling `TimeOfDay::LateNight` "the witching hour".
+    pub fn set_period_name(&mut self, period: TimeOfDay, name: String) {
+        self.period_names[u8::from(period) as usize] = Some(name);
+    }
+
+    /// Gets the name of a period of the day, falling
+    /// back to the built-in name if none is set.
+    pub fn period_name(&self, period: TimeOfDay) -> &str {
+        self.period_names[u8::from(period) as usize]
+            .as_deref()
+            .unwrap_or_else(|| period.into())
+    }
+
+    /// Gets the eras of this calendar.
+    pub fn eras(&self) -> &Eras {
+        &self.eras
+    }
+
+    /// Adds an era to the calendar.
+    pub fn add_era(&mut self, era: Era) {
+        self.eras.push(era)
+    }
+
+    /// Gets the most specific era that a year belongs to. When eras
+    /// overlap, the one with the narrowest bounds wins, so an era with
+    /// both a start and an end beats an open-ended one. Among open-ended
+    /// eras, the one that started most recently (or ends soonest) wins.
+    pub fn era_for(&self, year: i64) -> Option<&Era> {
+        self.eras
+            .iter()
+            .filter(|era| era.contains(year))
+            .min_by_key(|era| {
+                let (start, end) = (era.start_year(), era.end_year());
+                let width = match (start, end) {
+                    (Some(start), Some(end)) => end.saturating_sub(start),
+                    _ => i64::MAX,
+                };
+                let open = start.is_none() as u8 + end.is_none() as u8;
+                (
+                    open,
+                    width,
+                    Reverse(start.unwrap_or(i64::MIN)),
+                    end.unwrap_or(i64::MAX),
+                )
+            })
+    }
+
+    /// Sets which years are leap years, or removes the rule.
+    pub fn set_leap_rule(&mut self, rule: Option<LeapRule>) {
+        self.leap_rule = rule;
+    }
+
+    /// Gets the rule for which years are leap years, if any.
+    pub fn leap_rule(&self) -> Option<&LeapRule> {
+        self.leap_rule.as_ref()
+    }
+
+    /// Whether a year is a leap year. This is always
+    /// false for calendars without a leap rule.
+    pub fn is_leap_year(&self, year: i64) -> bool {
+        matches!(self.leap_rule, Some(rule) if rule.is_leap_year(self.astronomical_year(year)))
+    }
+
+    /// Sets whether the year before year 1 is year 0. Dates are
+    /// stored by their year, so this changes the meaning of any
+    /// dates before year 1 that were made with this calendar.
+    pub fn set_year_zero(&mut self, year_zero: YearZero) {
+        self.year_zero = year_zero;
+    }
+
+    /// Gets whether the year before year 1 is year 0.
+    pub fn year_zero(&self) -> YearZero {
+        self.year_zero
+    }
+
+    /// Sets the pattern used to show dates to people, as understood
+    /// by `DateTime::format`, or goes back to the ISO-like form.
+    pub fn set_display_format(&mut self, pattern: Option<String>) {
+        self.display_format = pattern;
+    }
+
+    /// Gets the pattern used to show dates to people, if any.
+    pub fn display_format(&self) -> Option<&str> {
+        self.display_format.as_deref()
+    }
+
+    /// Converts a year in this calendar to the astronomical
+    /// numbering, where year 0 is the year before year 1.
+    pub fn astronomical_year(&self, year: i64) -> i64 {
+        match self.year_zero {
+            YearZero::Historical if year < 0 => year + 1,
+            _ => year,
+        }
+    }
+
+    /// Converts an astronomically numbered year to a year in this calendar.
+    pub fn year_from_astronomical(&self, year: i64) -> i64 {
+        self.checked_year_from_astronomical(year)
+            .expect("The year is too far from the epoch")
+    }
+
+    /// Converts an astronomically numbered year to a year in
+    /// this calendar, returning None if it does not fit in an i64.
+    pub(crate) fn checked_year_from_astronomical(&self, year: i64) -> Option<i64> {
+        match self.year_zero {
+            YearZero::Historical if year <= 0 => year.checked_sub(1),
+            _ => Some(year),
+        }
+    }
+
+    /// Gets the number of days between the epoch and a given date.
+    pub(crate) fn days_since_epoch(&self, date: RawDate) -> i64 {
+        self.checked_days_since_epoch(date)
+            .expect("The date is too far from the epoch")
+    }
+
+    /// Gets the number of days between the epoch and a given
+    /// date, returning None if it does not fit in an i64.
+    pub(crate) fn checked_days_since_epoch(&self, date: RawDate) -> Option<i64> {
+        let years = self.astronomical_year(date.year).checked_sub(1)?;
+        let leap_days = match self.active_leap_rule() {
+            Some(rule) => {
+                i64::from(rule.extra).checked_mul(years.div_euclid(i64::from(rule.cycle)))?
+            }
+            None => 0,
+        };
+
+        i64::from(self.days_in_year())
+            .checked_mul(years)?
+            .checked_add(leap_days)?
+            .checked_add(i64::from(self.day_of_year(date)))
+    }
+
+    /// Gets the date a given number of days after the epoch.
+    pub(crate) fn date_from_days(&self, days: i64) -> RawDate {
+        self.checked_date_from_days(days)
+            .expect("The date is too far from the epoch")
+    }
+
+    /// Gets the date a given number of days after the epoch,
+    /// returning None if the year does not fit in an i64.
+    pub(crate) fn checked_date_from_days(&self, days: i64) -> Option<RawDate> {
+        let days_in_year = i64::from(self.days_in_year());
+        let (years, day_of_year) = match self.active_leap_rule() {
+            Some(rule) => {
+                // the leap year is the last year of each cycle
+                let cycle = i64::from(rule.cycle);
+                let cycle_days = cycle * days_in_year + i64::from(rule.extra);
+                let rest = days.rem_euclid(cycle_days);
+                let year = (rest / days_in_year).min(cycle - 1);
+                (
+                    days.div_euclid(cycle_days)
+                        .checked_mul(cycle)?
+                        .checked_add(year)?,
+                    rest - year * days_in_year,
+                )
+            }
+            None => (days.div_euclid(days_in_year), days.rem_euclid(days_in_year)),
+        };
+
+        let year = self.checked_year_from_astronomical(years.checked_add(1)?)?;
+        let (month, day) = self.month_and_day(
+            year,
+            day_of_year.try_into().expect("This is less than a u32"),
+        );
+        Some(RawDate { year, month, day })
+    }
+
+    /// Gets the date some number of days after another, returning
+    /// None if the year does not fit in an i64.
+    ///
+    /// The years repeat every leap cycle, so the days are counted
+    /// from a year near the epoch in the same place in the cycle.
+    /// This works right up to the bounds, where counting from the
+    /// epoch itself would overflow.
+    pub(crate) fn checked_offset_days(&self, date: RawDate, days: i64) -> Option<RawDate> {
+        let period = self
+            .active_leap_rule()
+            .map_or(1, |rule| i64::from(rule.cycle));
+        let year = self.astronomical_year(date.year);
+        // at least 1, so that it is the same year in either numbering
+        let local = year.rem_euclid(period) + period;
+
+        let moved = self.checked_date_from_days(
+            self.checked_days_since_epoch(RawDate {
+                year: local,
+                ..date
+            })?
+            .checked_add(days)?,
+        )?;
+        let shift = self.astronomical_year(moved.year).checked_sub(local)?;
+        Some(RawDate {
+            year: self.checked_year_from_astronomical(year.checked_add(shift)?)?,
+            ..moved
+        })
+    }
+
+    /// Gets the number of days in a (1-indexed) month,
+    /// if the month exists in this calendar.
+    ///
+    /// This ignores leap days. See `Calendar::days_in_month_for`.
+    pub fn days_in_month(&self, month: u32) -> Option<u32> {
+        self.months()
+            .get(month.checked_sub(1)? as usize)
+            .map(|m| m.days)
+    }
+
+    /// Gets the number of days in a (1-indexed) month of a given
+    /// year, including any leap days, if the month exists.
+    pub fn days_in_month_for(&self, year: i64, month: u32) -> Option<u32> {
+        self.month_lengths(year).nth(month.checked_sub(1)? as usize)
+    }
+
+    /// Gets the (0-indexed) day of the year of a date,
+    /// counting the leap days in the months before it.
+    pub(crate) fn day_of_year(&self, date: RawDate) -> u32 {
+        self.month_lengths(date.year)
+            .take(date.month as usize)
+            .sum::<u32>()
+            + date.day
+    }
+
+    /// Splits a (0-indexed) day of a year into its
+    /// (0-indexed) month and day, counting leap days.
+    pub(crate) fn month_and_day(&self, year: i64, day_of_year: u32) -> (u32, u32) {
+        let mut rest = day_of_year;
+        self.month_lengths(year)
+            .enumerate()
+            .find_map(|(month, days)| {
+                if rest < days {
+                    Some((month as u32, rest))
+                } else {
+                    rest -= days;
+                    None
+                }
+            })
+            .expect("The day is within the year")
+    }
+
+    /// Gets the length of each month of a year, including leap days.
+    fn month_lengths(&self, year: i64) -> impl Iterator<Item = u32> + '_ {
+        let leap = self.active_leap_rule().filter(|_| self.is_leap_year(year));
+        self.months()
+            .iter()
+            .enumerate()
+            .map(move |(index, month)| match leap {
+                Some(rule) if rule.month as usize == index => month.days + rule.extra,
+                _ => month.days,
+            })
+    }
+
+    /// Gets the leap rule, if it ever adds any days.
+    fn active_leap_rule(&self) -> Option<LeapRule> {
+        self.leap_rule
+            .filter(|rule| rule.cycle != 0 && rule.extra != 0 && rule.month < self.months_in_year())
+    }
+
+    /// Gets the (1-indexed) week day that the first day of a
+    /// (1-indexed) month falls on.
+    pub fn first_weekday_of_month(&self, year: i64, month: u32) -> Result<u32, InvalidDateError> {
+        let date = RawDate::new(year, month, 1)?;
+        self.validate_date(&date.into_date(self))?;
+        Ok(self.week_day_of(date))
+    }
+
+    /// Gets the (1-indexed) week day of every day in a (1-indexed) month.
+    pub fn weekdays_in_month(&self, year: i64, month: u32) -> Result<Vec<u32>, InvalidDateError> {
+        let first = self.first_weekday_of_month(year, month)?;
+        let days = self
+            .days_in_month_for(year, month)
+            .ok_or(InvalidDateError::MonthOutOfBounds(month))?;
+        let week_length = self.days_in_week();
+
+        Ok((0..days)
+            .map(|day| (first - 1 + day) % week_length + 1)
+            .collect())
     }
 
     /// Validates a date against this calendar.
@@ -53,9 +458,11 @@ impl Calendar {
         let day = date.day();
         let month = date.month();
 
-        if month == 0 || month > self.months_in_year() {
+        if date.year() == 0 && self.year_zero == YearZero::Historical {
+            Err(InvalidDateError::YearOutOfBounds(0))
+        } else if month == 0 || month > self.months_in_year() {
             Err(InvalidDateError::MonthOutOfBounds(month))
-        } else if day == 0 || day > self.months()[month as usize - 1].days {
+        } else if day == 0 || day > self.days_in_month_for(date.year(), month).unwrap_or(0) {
             Err(InvalidDateError::DayOutOfBounds(day))
         } else {
             Ok(date)
@@ -89,12 +496,42 @@ impl Calendar {
         self.week.as_slice()
     }
 
+    /// Finds the (1-indexed) month with some name, ignoring case. A
+    /// name that is not exactly that of a month may be the start of
+    /// one, such as `jan`, as long as it is the start of only one.
+    #[cfg(feature = "parse")]
+    pub fn month_by_name(&self, name: &str) -> Result<u32, ParseDateTimeError> {
+        let name = name.trim().to_lowercase();
+        let find = |matches: &dyn Fn(&str) -> bool| {
+            self.months()
+                .iter()
+                .enumerate()
+                .filter(|(_, m)| matches(&m.name.to_lowercase()))
+                .map(|(i, _)| i as u32 + 1)
+                .collect::<Vec<_>>()
+        };
+
+        let mut found = find(&|month| month == name);
+        if found.is_empty() && name.chars().count() >= 3 {
+            found = find(&|month| month.starts_with(&name));
+        }
+
+        match found.as_slice() {
+            [month] => Ok(*month),
+            [] => Err(ParseDateTimeError::UnknownMonth),
+            _ => Err(ParseDateTimeError::AmbiguousMonth),
+        }
+    }
+
     /// Parses human times such as 1d8h43m into datetime objects
     /// according to the rules of the calendar.
     ///
     /// 1101-02-12 - some specific date
+    /// 1101-02-12T08:30:00Z - the same, at a time of day, as `Display` writes it
+    /// 3 January 1101, January 3rd, 1101 - the same, by month name
     /// 8am, 2pm - some specific time
     /// 1y32mo6d3s - relative from some time
+    /// -2d4h - the same, but going back in time, no further than the epoch
     /// long rest - exactly 8 hours
     /// short rest - exactly 4 hours
     #[cfg(feature = "parse")]
@@ -107,6 +544,19 @@ impl Calendar {
         T: Into<Option<DateTime<'a>>>,
     {
         let relative_to: Option<DateTime> = relative_to.into();
+        if let Ok(raw) = date_string.parse::<RawDateTime>() {
+            let date =
+                DateTime::from_date(raw.date.year, raw.date.month + 1, raw.date.day + 1, self)?;
+            let time = Time::from_hms(
+                raw.time.hour,
+                raw.time.minute,
+                raw.time.second,
+                self,
+                TimeFormat::Exact,
+            )?;
+            return Ok(DateTime { time, ..date });
+        }
+
         if let Some(captures) = DATE.captures(date_string) {
             return DateTime::from_date(
                 captures
@@ -132,7 +582,22 @@ impl Calendar {
             .map_err(Into::into);
         }
 
-        let mut relative = relative_to.ok_or(ParseDateTimeError::NoRelativeReferencePoint)?;
+        if let Some(captures) = DAY_MONTH_YEAR
+            .captures(date_string)
+            .or_else(|| MONTH_DAY_YEAR.captures(date_string))
+        {
+            let group = |name| captures.name(name).expect("This is in the regex").as_str();
+            let year = group("y")
+                .parse()
+                .map_err(|_| ParseDateTimeError::InvalidFormat)?;
+            let day = group("d")
+                .parse()
+                .map_err(|_| ParseDateTimeError::InvalidFormat)?;
+            let month = self.month_by_name(group("m"))?;
+            return DateTime::from_date(year, month, day, self).map_err(Into::into);
+        }
+
+        let relative = relative_to.ok_or(ParseDateTimeError::NoRelativeReferencePoint)?;
 
         if date_string.eq("long rest") {
             return Ok(relative.add_hours(8));
@@ -152,32 +617,17 @@ impl Calendar {
                 .map_err(Into::into);
         }
 
-        if REL.is_match(date_string) {
-            for captures in REL.captures_iter(date_string) {
-                let value: u32 = captures
-                    .name("val")
-                    .expect("This is in the regex")
-                    .as_str()
-                    .parse()
-                    .expect("This is a valid u32");
-
-                let suffix = captures
-                    .name("suff")
-                    .expect("This is in the regex")
-                    .as_str();
-
-                relative = match suffix {
-                    "y" => relative.add_years(value),
-                    "mo" => relative.add_months(value),
-                    "w" => relative.add_weeks(value),
-                    "d" => relative.add_days(value),
-                    "h" => relative.add_hours(value),
-                    "m" => relative.add_minutes(value),
-                    "s" => relative.add_seconds(value),
-                    _ => relative,
-                };
-            }
-            return Ok(relative);
+        let (backwards, magnitude) = match date_string.strip_prefix('-') {
+            Some(ago) => (true, ago),
+            None => (false, date_string),
+        };
+        if REL.is_match(magnitude) {
+            let duration = relative_duration(magnitude)?;
+            return Ok(if backwards {
+                relative.subtract_duration(duration)
+            } else {
+                relative.add_duration(duration)
+            });
         }
 
         if let Some(captures) = TIME.captures(date_string) {
@@ -200,13 +650,72 @@ impl Calendar {
 
             let time = Time::from_hms(hour, 0, 0, self, format)?;
 
-            return relative
-                .wait_until(WaitTarget::Time(time))
-                .map_err(Into::into);
+            let mut parsed = relative.wait_until(WaitTarget::Time(time))?;
+            parsed.time.display_format = Some(format);
+            return Ok(parsed);
         }
 
         Err(ParseDateTimeError::InvalidFormat)
     }
+
+    /// Parses a span of time using the relative grammar of
+    /// `Calendar::parse`, such as 2y4mo2h, long rest, or short rest.
+    ///
+    /// Since there is no reference point, months and years have no
+    /// fixed length. `Duration::to_seconds` resolves them using the
+    /// average month and year of this calendar.
+    #[cfg(feature = "parse")]
+    pub fn parse_duration(&self, duration_string: &str) -> Result<Duration, ParseDateTimeError> {
+        let hours = |hours| Duration {
+            hours,
+            ..Duration::default()
+        };
+
+        match duration_string {
+            "long rest" => return Ok(hours(8)),
+            "short rest" => return Ok(hours(4)),
+            _ => {}
+        }
+
+        if !REL.is_match(duration_string) {
+            return Err(ParseDateTimeError::InvalidFormat);
+        }
+
+        relative_duration(duration_string)
+    }
+}
+
+/// Sums up the parts of a relative expression such as 1y32mo6d3s,
+/// which must already match `REL`. Parts too large to count fail.
+#[cfg(feature = "parse")]
+fn relative_duration(string: &str) -> Result<Duration, ParseDateTimeError> {
+    let mut duration = Duration::default();
+    for captures in REL_PART.captures_iter(string) {
+        let value: u32 = captures
+            .name("val")
+            .expect("This is in the regex")
+            .as_str()
+            .parse()
+            .map_err(|_| ParseDateTimeError::InvalidFormat)?;
+
+        let component = match captures
+            .name("suff")
+            .expect("This is in the regex")
+            .as_str()
+        {
+            "y" => &mut duration.years,
+            "mo" => &mut duration.months,
+            "w" => &mut duration.weeks,
+            "d" => &mut duration.days,
+            "h" => &mut duration.hours,
+            "m" => &mut duration.minutes,
+            _ => &mut duration.seconds,
+        };
+        *component = component
+            .checked_add(value)
+            .ok_or(ParseDateTimeError::InvalidFormat)?;
+    }
+    Ok(duration)
 }
 
 impl YearCycle for Calendar {
@@ -214,6 +723,10 @@ impl YearCycle for Calendar {
         self.year.days_in_year()
     }
 
+    fn days_in_year_for(&self, year: i64) -> u32 {
+        self.month_lengths(year).sum()
+    }
+
     fn months_in_year(&self) -> u32 {
         self.year.months_in_year()
     }
@@ -271,19 +784,38 @@ impl ConvertDate for Calendar {
             }
         }
     }
+
+    fn seconds_to_years_months_days(&self, seconds: u32) -> (u32, u32, u32, u32) {
+        let (days, seconds) = self.seconds_to_days(seconds);
+        let (years, days) = div_rem(days, self.days_in_year());
+        let (months, days) = self.days_to_months(days);
+        (years, months, days, seconds)
+    }
 }
 
 #[cfg(feature = "parse")]
+/// The ways parsing a date or duration can fail.
 #[derive(Error, Debug, Copy, Clone)]
 pub enum ParseDateTimeError {
+    /// The date does not exist in the calendar.
     #[error("invalid date: {0}")]
     InvalidDate(#[from] InvalidDateError),
+    /// The time does not exist in the calendar.
     #[error("invalid time: {0}")]
     InvalidTime(#[from] InvalidTimeError),
+    /// The duration could not be waited.
     #[error("invalid wait: {0}")]
     InvalidWait(#[from] InvalidWaitError),
+    /// The text is not a date or a duration.
     #[error("invalid format")]
     InvalidFormat,
+    /// No month has the given name.
+    #[error("there is no month with that name")]
+    UnknownMonth,
+    /// More than one month has (or starts with) the given name.
+    #[error("more than one month has that name")]
+    AmbiguousMonth,
+    /// A duration was given without a date to count from.
     #[error("relative time given with no reference point")]
     NoRelativeReferencePoint,
 }
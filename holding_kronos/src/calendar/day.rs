@@ -3,9 +3,11 @@ use serde::{Deserialize, Serialize};
 
 use super::traits::DayCycle;
 
-/// Represents a day.
+/// Represents a day. Any missing units are
+/// taken from the 24 hour day when deserializing.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct Day {
     seconds_in_minute: u32,
     minutes_in_hour: u32,
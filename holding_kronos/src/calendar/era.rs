@@ -20,6 +20,11 @@ impl Eras {
     pub fn as_slice(&self) -> &[Era] {
         &self.0
     }
+
+    /// Adds an era.
+    pub fn push(&mut self, era: Era) {
+        self.0.push(era)
+    }
 }
 
 /// An era is a contiguous block of time in a calendar from which time can be referenced.
@@ -35,9 +40,11 @@ pub struct Era {
     pub name: String,
 
     /// When the era started. None implies it has always existed.
+    #[cfg_attr(feature = "serde", serde(default))]
     pub start_year: Option<i64>,
 
     /// When the era ended. None implies it hasn't ended.
+    #[cfg_attr(feature = "serde", serde(default))]
     pub end_year: Option<i64>,
 }
 
@@ -65,8 +72,17 @@ impl Era {
     pub fn end_year(&self) -> Option<i64> {
         self.end_year
     }
+
+    /// Whether a year falls within the era. Both
+    /// the start and end years are included.
+    pub fn contains(&self, year: i64) -> bool {
+        !matches!(self.start_year, Some(start) if start > year)
+            && !matches!(self.end_year, Some(end) if end < year)
+    }
 }
 
+/// The Common Era starts at year 0, which only exists
+/// in calendars that number their years astronomically.
 impl Default for Eras {
     fn default() -> Self {
         Self(vec![
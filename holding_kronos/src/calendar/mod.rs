@@ -1,6 +1,7 @@
 //! calendar: A general-purpose flexible fantasy calendar.
 //!
-//! For simplicity, it does not support leap-anything.
+//! Leap years are supported through a `LeapRule`, which adds
+//! days to a month every few years. There are no leap seconds.
 
 mod calendar;
 mod day;
@@ -11,7 +12,9 @@ mod week;
 mod year;
 
 pub use calendar::Calendar;
+#[cfg(feature = "parse")]
+pub use calendar::ParseDateTimeError;
 pub use day::Day;
 pub use era::{Era, Eras};
-pub use week::Week;
-pub use year::{Month, Year};
+pub use week::{DayKind, Week, WeekDay};
+pub use year::{LeapRule, Month, Year, YearZero};
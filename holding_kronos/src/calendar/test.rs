@@ -6,10 +6,13 @@ use proptest::prelude::*;
 use test_case::test_case;
 
 use crate::{
-    calendar::{traits::ConvertDate, Calendar},
+    calendar::{
+        traits::{ConvertDate, ConvertTime, DayCycle, WeekCycle, YearCycle},
+        Calendar, Day, DayKind, LeapRule, Month, ParseDateTimeError, Week, WeekDay, Year, YearZero,
+    },
     datetime::{
-        traits::{ModifyDateTime, ShowDate, ShowTime},
-        DateTime, Time, TimeFormat, WaitTarget,
+        traits::{ModifyDate, ModifyDateTime, ShowDate, ShowTime},
+        Date, DateTime, RawDate, Time, TimeFormat, WaitTarget,
     },
 };
 
@@ -30,6 +33,78 @@ pub fn parses_a_date(string: &str, year: i64, month: u32, day: u32) -> Result {
     Ok(())
 }
 
+#[test]
+pub fn parses_a_displayed_datetime() -> Result {
+    let cal = Calendar::shared();
+    let date = cal.parse("1101-02-12T08:30:15Z", None)?;
+
+    assert_eq!((date.year(), date.month(), date.day()), (1101, 2, 12));
+    assert_eq!((date.hour(), date.minute(), date.second()), (8, 30, 15));
+    assert_eq!(date.to_string(), "1101-02-12T08:30:15Z");
+    assert!(matches!(
+        cal.parse("1101-02-12T25:00:00Z", None),
+        Err(ParseDateTimeError::InvalidTime(_))
+    ));
+
+    Ok(())
+}
+
+#[test]
+pub fn parsed_time_keeps_its_format() -> Result {
+    let cal = Calendar::shared();
+    let date = cal.parse("8pm", DateTime::from_seconds(0, cal))?;
+
+    assert_eq!(date.hour(), 20);
+    assert_eq!(date.time.display_format, Some(TimeFormat::PM));
+    assert_eq!(date.time.to_string(), "8:00 PM");
+    assert_eq!(date.time, Time::from_hms(20, 0, 0, cal, TimeFormat::Exact)?);
+
+    Ok(())
+}
+
+#[test_case("8h", 8 * 3600)]
+#[test_case("2d", 2 * 86400)]
+#[test_case("1y", 365 * 86400)]
+#[test_case("1w2d3s", 9 * 86400 + 3)]
+#[test_case("1mo", 365 * 86400 / 12 ; "an average month")]
+#[test_case("long rest", 8 * 3600)]
+pub fn parses_a_duration(string: &str, seconds: u64) -> Result {
+    let cal = Calendar::default();
+    let duration = cal.parse_duration(string)?;
+
+    assert_eq!(duration.to_seconds(&cal), seconds);
+
+    Ok(())
+}
+
+#[test_case("2021-01-01" ; "a date")]
+#[test_case("8am" ; "a time")]
+#[test_case("junk 1d junk" ; "surrounded by junk")]
+#[test_case("1d junk" ; "trailing junk")]
+#[test_case("-1d" ; "negative")]
+#[test_case("99999999999h" ; "too many hours")]
+#[test_case("4294967295s1s" ; "overflowing sum")]
+pub fn rejects_a_duration(string: &str) {
+    let cal = Calendar::shared();
+    assert!(matches!(
+        cal.parse_duration(string),
+        Err(ParseDateTimeError::InvalidFormat)
+    ));
+}
+
+#[test_case("junk 1d junk" ; "surrounded by junk")]
+#[test_case("1d junk" ; "trailing junk")]
+#[test_case("--1d" ; "doubly negative")]
+#[test_case("99999999999h" ; "too many hours")]
+pub fn rejects_a_relative(string: &str) {
+    let cal = Calendar::shared();
+    let now = DateTime::from_seconds(0, cal);
+    assert!(matches!(
+        cal.parse(string, now),
+        Err(ParseDateTimeError::InvalidFormat)
+    ));
+}
+
 #[test_case(10, (0, 10))]
 #[test_case(40, (1, 9))]
 #[test_case(273, (9, 0))]
@@ -57,6 +132,8 @@ pub fn days_to_months(days: u32, expected: (u32, u32)) -> Result {
 #[test_case("20m", 1, 1, 1, 0, 20, 0 ; "minutes")]
 #[test_case("20s", 1, 1, 1, 0, 0, 20 ; "seconds")]
 #[test_case("2y4mo2h5m20s", 3, 5, 1, 2, 5, 20 ; "combination")]
+#[test_case("-1d", 1, 1, 1, 0, 0, 0 ; "back a day stops at the epoch")]
+#[test_case("-1mo2h", 1, 1, 1, 0, 0, 0 ; "back across midnight stops at the epoch")]
 pub fn parses_a_relative(
     string: &str,
     year: i64,
@@ -79,6 +156,49 @@ pub fn parses_a_relative(
     Ok(())
 }
 
+#[test_case("3 January 1101", 1101, 1, 3 ; "day month year")]
+#[test_case("3rd january 1101", 1101, 1, 3 ; "ordinal day")]
+#[test_case("February 14, 1101", 1101, 2, 14 ; "month day year")]
+#[test_case("Dec 25 1101", 1101, 12, 25 ; "abbreviated month")]
+pub fn parses_a_named_date(string: &str, year: i64, month: u32, day: u32) -> Result {
+    let cal = Calendar::shared();
+    let date = cal.parse(string, None)?;
+
+    assert_eq!((date.year(), date.month(), date.day()), (year, month, day));
+    assert_eq!(date.hour(), 0);
+
+    Ok(())
+}
+
+#[test]
+pub fn rejects_unknown_month_names() {
+    let cal = Calendar::shared();
+    assert!(matches!(
+        cal.parse("3 Brumaire 1101", None),
+        Err(ParseDateTimeError::UnknownMonth)
+    ));
+    assert!(matches!(
+        cal.parse("3 Ju 1101", None),
+        Err(ParseDateTimeError::UnknownMonth)
+    ));
+    assert!(matches!(
+        cal.parse("40 January 1101", None),
+        Err(ParseDateTimeError::InvalidDate(_))
+    ));
+
+    let month = |name: &str| Month {
+        name: name.to_string(),
+        days: 30,
+    };
+    let year = Year::new(vec![month("Harvest"), month("Harvestide")]);
+    let cal = Calendar::new(year, Week::default(), Day::default());
+    assert_eq!(cal.month_by_name("harvest").ok(), Some(1));
+    assert!(matches!(
+        cal.month_by_name("Harv"),
+        Err(ParseDateTimeError::AmbiguousMonth)
+    ));
+}
+
 #[test_case("10-09-wrong" ; "invalid day")]
 #[test_case("1-ouch-100" ; "invalid month")]
 #[test_case("1-1-100" ; "out of bounds day")]
@@ -126,6 +246,140 @@ pub fn months_to_seconds(months: u32, seconds: u32) -> Result {
     Ok(())
 }
 
+#[test_case(0, (0, 0))]
+#[test_case(86399, (0, 86399))]
+#[test_case(86400 * 3 + 20, (3, 20))]
+pub fn seconds_to_days(seconds: u32, expected: (u32, u32)) {
+    let cal = Calendar::default();
+    assert_eq!(cal.seconds_to_days(seconds), expected);
+}
+
+#[test]
+pub fn splits_seconds() {
+    let cal = Calendar::default();
+    let seconds = cal.years_to_seconds(1)
+        + cal.months_to_seconds(2)
+        + cal.days_to_seconds(3)
+        + cal.hours_to_seconds(4)
+        + cal.minutes_to_seconds(5)
+        + 6;
+
+    let (years, months, days, seconds) = cal.seconds_to_years_months_days(seconds);
+    assert_eq!((years, months, days), (1, 2, 3));
+    assert_eq!(cal.seconds_to_hours_minutes_seconds(seconds), (4, 5, 6));
+}
+
+#[test]
+pub fn reanchors_week() -> Result {
+    let cal = Calendar::default();
+    let festival = Date::from_ymd(1101, 3, 15, &cal)?;
+    let week_day = festival.week_day();
+
+    let week = (1..=10)
+        .map(|i| WeekDay::new(format!("Day {}", i)))
+        .collect();
+    let mut cal = Calendar::new(Year::default(), Week::new(week), Day::default());
+    cal.reanchor_week(festival.date, week_day)?;
+
+    let festival = Date::from_ymd(1101, 3, 15, &cal)?;
+    assert_eq!(festival.week_day(), week_day);
+    assert_eq!(festival.add_weeks(3).week_day(), week_day);
+    assert_ne!(festival.add_days(1).week_day(), week_day);
+
+    Ok(())
+}
+
+#[test]
+pub fn tags_day_kinds() -> Result {
+    let week = vec![
+        WeekDay::new("Toil".to_string()),
+        WeekDay::new("Labour".to_string()),
+        WeekDay::new("Graft".to_string()),
+        WeekDay::with_kind("Fair".to_string(), DayKind::Market),
+        WeekDay::with_kind("Still".to_string(), DayKind::Rest),
+    ];
+    let cal = Calendar::new(Year::default(), Week::new(week), Day::default());
+
+    let first = Date::from_ymd(1, 1, 1, &cal)?;
+    let fair = (0..5)
+        .map(|offset| first.add_days(offset))
+        .find(|d| d.day_kind() == &DayKind::Market)
+        .expect("a week has a market day");
+    assert_eq!(fair.week_day_name(), "Fair");
+    assert_eq!(fair.add_weeks(3).day_kind(), &DayKind::Market);
+    assert_eq!(fair.add_days(1).day_kind(), &DayKind::Rest);
+    assert_eq!(fair.add_days(2).day_kind(), &DayKind::Work);
+
+    let default = Calendar::default();
+    let monday = Date::from_ymd(1, 1, 1, &default)?;
+    assert_eq!(monday.day_kind(), &DayKind::Work);
+    assert_eq!(monday.add_days(5).day_kind(), &DayKind::Rest);
+
+    Ok(())
+}
+
+#[test]
+pub fn tracks_market_cycle() -> Result {
+    let mut cal = Calendar::default();
+    let market = (1..=10)
+        .map(|i| WeekDay::new(format!("Market {}", i)))
+        .collect();
+    cal.add_cycle("market".to_string(), Week::new(market));
+
+    let date = Date::from_ymd(1101, 3, 15, &cal)?;
+    let (position, name) = date.cycle_day("market").expect("cycle exists");
+    assert_eq!(name, format!("Market {}", position));
+
+    let later = date.add_days(7);
+    assert_eq!(later.week_day(), date.week_day());
+    assert_eq!(
+        later.cycle_day("market").expect("cycle exists").0,
+        (position + 7 - 1) % 10 + 1
+    );
+    assert_eq!(
+        date.add_days(10).cycle_day("market"),
+        Some((position, name))
+    );
+    assert_eq!(date.cycle_day("tides"), None);
+
+    Ok(())
+}
+
+#[test_case(1, 1, 31)]
+#[test_case(1, 2, 28)]
+#[test_case(1101, 3, 31)]
+pub fn lays_out_month(year: i64, month: u32, days: u32) -> Result {
+    let cal = Calendar::default();
+    let first = cal.first_weekday_of_month(year, month)?;
+    let week_days = cal.weekdays_in_month(year, month)?;
+
+    assert_eq!(cal.days_in_month(month), Some(days));
+    assert_eq!(week_days.len() as u32, days);
+    assert_eq!(week_days[0], first);
+    assert_eq!(first, Date::from_ymd(year, month, 1, &cal)?.week_day());
+    assert_eq!(
+        week_days[days as usize - 1],
+        Date::from_ymd(year, month, days, &cal)?.week_day()
+    );
+
+    Ok(())
+}
+
+#[test]
+pub fn rejects_invalid_month() {
+    let cal = Calendar::default();
+    assert_eq!(cal.days_in_month(0), None);
+    assert_eq!(cal.days_in_month(13), None);
+    assert!(cal.first_weekday_of_month(1, 13).is_err());
+}
+
+#[test]
+pub fn reanchor_rejects_invalid_week_day() {
+    let mut cal = Calendar::default();
+    assert!(cal.reanchor_week(RawDate::default(), 0).is_err());
+    assert!(cal.reanchor_week(RawDate::default(), 8).is_err());
+}
+
 proptest! {
     #[test]
     fn parses_exact_dates(s in "[0-9]{4}-([1-9]|10|11|12){1}-([1-9]|10|11|12){1}") {
@@ -153,3 +407,58 @@ fn parse_time_test(s: &str) {
     let datetime = DateTime::from_seconds(0, &cal);
     cal.parse(s, datetime);
 }
+
+#[test]
+pub fn shared_calendar_is_the_default() {
+    assert_eq!(Calendar::shared(), &Calendar::default());
+    assert!(std::ptr::eq(Calendar::shared(), Calendar::shared()));
+}
+
+#[test_case(4, true)]
+#[test_case(8, true)]
+#[test_case(0, true ; "year zero")]
+#[test_case(-4, true ; "before year zero")]
+#[test_case(1, false)]
+#[test_case(6, false)]
+#[test_case(-3, false ; "odd year before zero")]
+pub fn marks_leap_years(year: i64, expected: bool) -> Result {
+    let mut cal = Calendar::default();
+    assert!(!cal.is_leap_year(year));
+
+    cal.set_leap_rule(Some(LeapRule::new(4, 1, 1)));
+    assert_eq!(cal.is_leap_year(year), expected);
+
+    let date = DateTime::from_date(year, 3, 1, &cal)?;
+    assert_eq!(date.is_leap_year(), expected);
+    Ok(())
+}
+
+#[cfg(feature = "serde")]
+#[test]
+pub fn fills_in_missing_calendar_fields() -> Result {
+    let cal: Calendar = serde_yaml::from_str(
+        "year:\n\
+         - name: Thaw\n\
+         \x20 days: 90\n\
+         - name: Frost\n\
+         \x20 days: 90\n\
+         week:\n\
+         - name: Oneday\n\
+         - name: Twoday\n\
+         day:\n\
+         \x20 hours_in_day: 20\n\
+         eras:\n\
+         - name: Dawn\n",
+    )?;
+
+    assert_eq!(cal.days_in_year(), 180);
+    assert_eq!(cal.days_in_week(), 2);
+    assert_eq!(cal.hours_in_day(), 20);
+    assert_eq!(cal.minutes_in_hour(), 60);
+    assert_eq!(cal.leap_rule(), None);
+    assert_eq!(cal.year_zero(), YearZero::Astronomical);
+
+    let dawn = &cal.eras().as_slice()[0];
+    assert_eq!((dawn.start_year, dawn.end_year), (None, None));
+    Ok(())
+}
@@ -1,10 +1,17 @@
 //! Useful traits for representing arbitrary calendars.
 
+use crate::util::div_rem;
+
 /// Allows for inspecting information about a year cycle.
 pub trait YearCycle {
     /// Gets the days in a year for this calendar.
     fn days_in_year(&self) -> u32;
 
+    /// Gets the days in a given year, including any leap days.
+    fn days_in_year_for(&self, _year: i64) -> u32 {
+        self.days_in_year()
+    }
+
     /// Gets the number of months in a year for this calendar.
     fn months_in_year(&self) -> u32;
 }
@@ -40,6 +47,18 @@ pub trait DayCycle {
     fn minutes_in_day(&self) -> u32 {
         self.minutes_in_hour() * self.hours_in_day()
     }
+
+    /// Gets the total number of seconds in an hour, which
+    /// doesn't overflow for hours longer than `u32::MAX`.
+    fn seconds_in_hour_u64(&self) -> u64 {
+        u64::from(self.seconds_in_minute()) * u64::from(self.minutes_in_hour())
+    }
+
+    /// Gets the total number of seconds in a day, which
+    /// doesn't overflow for days longer than `u32::MAX`.
+    fn seconds_in_day_u64(&self) -> u64 {
+        self.seconds_in_hour_u64() * u64::from(self.hours_in_day())
+    }
 }
 
 impl<T> ConvertTime for T
@@ -57,6 +76,16 @@ where
     fn minutes_to_seconds(&self, minutes: u32) -> u32 {
         minutes * self.seconds_in_minute()
     }
+
+    fn seconds_to_days(&self, seconds: u32) -> (u32, u32) {
+        div_rem(seconds, self.seconds_in_day())
+    }
+
+    fn seconds_to_hours_minutes_seconds(&self, seconds: u32) -> (u32, u32, u32) {
+        let (hours, seconds) = div_rem(seconds, self.seconds_in_hour());
+        let (minutes, seconds) = div_rem(seconds, self.seconds_in_minute());
+        (hours, minutes, seconds)
+    }
 }
 
 /// Allows for conversion between various units of time.
@@ -69,6 +98,14 @@ pub trait ConvertTime {
     fn hours_to_seconds(&self, hours: u32) -> u32;
     /// Gets the number of seconds corresponding to a number of minutes.
     fn minutes_to_seconds(&self, minutes: u32) -> u32;
+
+    /// Gets the number of days from a number of seconds with remainder.
+    fn seconds_to_days(&self, seconds: u32) -> (u32, u32);
+
+    /// Splits a number of seconds into hours, minutes, and seconds.
+    ///
+    /// Hours are not wrapped at the end of the day.
+    fn seconds_to_hours_minutes_seconds(&self, seconds: u32) -> (u32, u32, u32);
 }
 
 /// Allows for version between various units of time.
@@ -95,4 +132,12 @@ pub trait ConvertDate {
 
     /// Gets the number of months from a number of days with remainder.
     fn days_to_months(&self, days: u32) -> (u32, u32);
+
+    /// Splits a number of seconds into years, months, and days,
+    /// with the remaining seconds in the final day.
+    ///
+    /// All the components are counts rather than dates, so are
+    /// 0-indexed. Months are consumed in calendar order from the
+    /// start of the year.
+    fn seconds_to_years_months_days(&self, seconds: u32) -> (u32, u32, u32, u32);
 }
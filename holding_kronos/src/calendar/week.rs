@@ -1,4 +1,7 @@
-use std::slice::Iter;
+use std::{
+    fmt::{Display, Formatter, Result as FmtResult},
+    slice::Iter,
+};
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
@@ -16,9 +19,60 @@ pub struct Week(Vec<WeekDay>);
 pub struct WeekDay {
     /// The name of this day.
     pub name: String,
+
+    /// What sort of day this is in the rhythm of the week.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub kind: DayKind,
+}
+
+/// The part a week day plays in the rhythm of the week,
+/// such as whether people work or rest on it.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum DayKind {
+    /// An ordinary working day.
+    #[default]
+    Work,
+    /// A day when the markets are open.
+    Market,
+    /// A day of rest.
+    Rest,
+    /// A holy day, when most things are closed.
+    Holy,
+    /// Some other sort of day, by name.
+    Custom(String),
+}
+
+impl Display for DayKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            DayKind::Work => write!(f, "work"),
+            DayKind::Market => write!(f, "market"),
+            DayKind::Rest => write!(f, "rest"),
+            DayKind::Holy => write!(f, "holy"),
+            DayKind::Custom(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+impl WeekDay {
+    /// Creates a new `WeekDay`, which is a work day.
+    pub fn new(name: String) -> Self {
+        Self::with_kind(name, DayKind::Work)
+    }
+
+    /// Creates a new `WeekDay` of some kind.
+    pub fn with_kind(name: String, kind: DayKind) -> Self {
+        Self { name, kind }
+    }
 }
 
 impl Week {
+    /// Creates a new `Week` from its days.
+    pub fn new(days: Vec<WeekDay>) -> Self {
+        Self(days)
+    }
+
     /// Iterate over the days in the week.
     pub fn iter(&self) -> Iter<'_, WeekDay> {
         self.0.iter()
@@ -39,27 +93,13 @@ impl WeekCycle for Week {
 impl Default for Week {
     fn default() -> Self {
         Self(vec![
-            WeekDay {
-                name: "Monday".to_string(),
-            },
-            WeekDay {
-                name: "Tuesday".to_string(),
-            },
-            WeekDay {
-                name: "Wednesday".to_string(),
-            },
-            WeekDay {
-                name: "Thursday".to_string(),
-            },
-            WeekDay {
-                name: "Friday".to_string(),
-            },
-            WeekDay {
-                name: "Saturday".to_string(),
-            },
-            WeekDay {
-                name: "Sunday".to_string(),
-            },
+            WeekDay::new("Monday".to_string()),
+            WeekDay::new("Tuesday".to_string()),
+            WeekDay::new("Wednesday".to_string()),
+            WeekDay::new("Thursday".to_string()),
+            WeekDay::new("Friday".to_string()),
+            WeekDay::with_kind("Saturday".to_string(), DayKind::Rest),
+            WeekDay::with_kind("Sunday".to_string(), DayKind::Rest),
         ])
     }
 }
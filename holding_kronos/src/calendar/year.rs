@@ -11,6 +11,11 @@ use super::traits::YearCycle;
 pub struct Year(Vec<Month>);
 
 impl Year {
+    /// Creates a year from its months, in order.
+    pub fn new(months: Vec<Month>) -> Self {
+        Self(months)
+    }
+
     /// Iterate over the months in the year.
     pub fn iter(&self) -> Iter<'_, Month> {
         self.0.iter()
@@ -98,3 +103,52 @@ pub struct Month {
     /// The days in the month.
     pub days: u32,
 }
+
+/// An intercalary period: every `cycle` years, counting
+/// from year 0, a month gains some extra days.
+///
+/// The extra days are counted everywhere dates are converted
+/// to and from seconds. Durations have no reference year,
+/// so `Duration` still measures a year without them.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct LeapRule {
+    /// The number of years between leap years.
+    pub cycle: u32,
+
+    /// The (0-indexed) month that gains the days.
+    pub month: u32,
+
+    /// The number of days gained.
+    pub extra: u32,
+}
+
+/// How the years before year 1 are numbered.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum YearZero {
+    /// The year before year 1 is year 0, and the years before
+    /// that are negative. This is the default.
+    #[default]
+    Astronomical,
+    /// There is no year 0, so the year before year 1 is year -1,
+    /// as when counting years BC and AD.
+    Historical,
+}
+
+impl LeapRule {
+    /// Creates a new `LeapRule`.
+    pub fn new(cycle: u32, month: u32, extra: u32) -> Self {
+        Self {
+            cycle,
+            month,
+            extra,
+        }
+    }
+
+    /// Whether a (astronomically numbered) year
+    /// is a leap year under this rule.
+    pub fn is_leap_year(&self, year: i64) -> bool {
+        self.cycle != 0 && year.rem_euclid(i64::from(self.cycle)) == 0
+    }
+}
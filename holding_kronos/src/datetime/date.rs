@@ -1,4 +1,9 @@
-use std::{convert::TryInto, fmt::Display};
+use std::{
+    convert::{TryFrom, TryInto},
+    fmt::Display,
+    ops::Add,
+    str::FromStr,
+};
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
@@ -6,28 +11,45 @@ use thiserror::Error;
 
 use crate::{
     calendar::{
-        traits::{ConvertDate, ConvertTime, WeekCycle, YearCycle},
-        Calendar,
+        traits::{DayCycle, WeekCycle, YearCycle},
+        Calendar, DayKind, Era,
+    },
+    datetime::{
+        parse_component,
+        traits::{ModifyDate, OverflowPolicy, ShowDate, ShowTime},
+        Duration, ParseRawError, Time,
     },
-    datetime::traits::{ModifyDate, ShowDate},
     util::div_rem,
 };
 
 /// A raw date object (useful for serialization).
+///
+/// The month and day fields are stored 0-indexed, so
+/// `RawDate { year: 1, month: 0, day: 0 }` is the first day
+/// of the first month. Prefer the `from_ordinal` and `from_index`
+/// constructors, which make the convention explicit.
 #[derive(Debug, Clone, Copy, Ord, PartialOrd, PartialEq, Eq, Default)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct RawDate {
     /// The year component
     pub year: i64,
-    /// The month component
+    /// The month component, 0-indexed.
     pub month: u32,
-    /// The day component
+    /// The day component, 0-indexed.
     pub day: u32,
 }
 
 impl RawDate {
     /// Create a new `RawDate` from a year-month-day tuple.
     ///
+    /// This is an alias for `RawDate::from_ordinal`.
+    pub fn new(year: i64, month: u32, day: u32) -> Result<Self, InvalidDateError> {
+        Self::from_ordinal(year, month, day)
+    }
+
+    /// Create a new `RawDate` from a 1-indexed year-month-day
+    /// tuple, the way dates are usually written.
+    ///
     /// This will return an `InvalidDateError` if the month or day are 0.
     ///
     /// # Arguments
@@ -37,20 +59,22 @@ impl RawDate {
     ///                This is 1-indexed.
     /// * `day`      - The number of days relative to the beginning of that month.
     ///                This is 1-indexed.
-    pub fn new(year: i64, month: u32, day: u32) -> Result<Self, InvalidDateError> {
+    pub fn from_ordinal(year: i64, month: u32, day: u32) -> Result<Self, InvalidDateError> {
         if month == 0 {
             Err(InvalidDateError::MonthOutOfBounds(month))
         } else if day == 0 {
             Err(InvalidDateError::DayOutOfBounds(day))
         } else {
-            Ok(Self {
-                year,
-                month: month - 1,
-                day: day - 1,
-            })
+            Ok(Self::from_index(year, month - 1, day - 1))
         }
     }
 
+    /// Create a new `RawDate` from a 0-indexed year-month-day
+    /// tuple, matching the fields of the struct.
+    pub fn from_index(year: i64, month: u32, day: u32) -> Self {
+        Self { year, month, day }
+    }
+
     /// Convert this `RawDate` into a `Date` object.
     pub fn into_date(self, cal: &Calendar) -> Date {
         Date {
@@ -68,12 +92,49 @@ impl Display for RawDate {
             f,
             "{:0>4}-{:0>2}-{:0>2}",
             self.year,
-            self.month + 1,
-            self.day + 1
+            u64::from(self.month) + 1,
+            u64::from(self.day) + 1
         )
     }
 }
 
+/// Reads a date back from the form `Display` writes it in,
+/// YYYY-MM-DD. There is no calendar, so any numbers are allowed
+/// as long as the month and day are 1-indexed.
+impl FromStr for RawDate {
+    type Err = ParseRawError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.rsplitn(3, '-');
+        match (parts.next(), parts.next(), parts.next()) {
+            (Some(day), Some(month), Some(year)) => Ok(Self {
+                year: parse_year(year)?,
+                month: parse_index("month", month)?,
+                day: parse_index("day", day)?,
+            }),
+            _ => Err(ParseRawError::Malformed(s.to_string(), "YYYY-MM-DD")),
+        }
+    }
+}
+
+/// Reads the year as `Display` pads it, where a negative
+/// year may have zeros in front of its sign, such as 00-5.
+fn parse_year(text: &str) -> Result<i64, ParseRawError> {
+    match text.trim_start_matches('0') {
+        "" if !text.is_empty() => Ok(0),
+        digits => parse_component("year", digits),
+    }
+}
+
+/// Reads a 1-indexed component, returning its 0-based index.
+fn parse_index(name: &'static str, text: &str) -> Result<u32, ParseRawError> {
+    let ordinal: u64 = parse_component(name, text)?;
+    ordinal
+        .checked_sub(1)
+        .and_then(|index| u32::try_from(index).ok())
+        .ok_or_else(|| ParseRawError::OutOfBounds(name, text.to_string()))
+}
+
 /// Represents dates in arbitrary calendars.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Date<'a> {
@@ -123,50 +184,263 @@ impl<'a> Date<'a> {
         Ok(date)
     }
 
+    /// Create a new `Date` from a year and a (1-indexed) day of that
+    /// year, so that `date.days() + 1` gives back the day of the year.
+    /// In leap years, the leap days are counted too.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use holding_kronos::{calendar::Calendar, datetime::{traits::ShowDate, Date}};
+    /// #
+    /// let cal = Calendar::default();
+    /// let date = Date::from_ordinal_day(2020, 32, &cal).unwrap();
+    /// assert_eq!((date.month(), date.day()), (2, 1));
+    /// assert_eq!(date.days() + 1, 32);
+    /// ```
+    pub fn from_ordinal_day(
+        year: i64,
+        day_of_year: u32,
+        calendar: &'a Calendar,
+    ) -> Result<Self, InvalidDateError> {
+        if day_of_year == 0 || day_of_year > calendar.days_in_year_for(year) {
+            return Err(InvalidDateError::DayOutOfBounds(day_of_year));
+        }
+
+        let (month, day) = calendar.month_and_day(year, day_of_year - 1);
+        Self::from_ymd(year, month + 1, day + 1, calendar)
+    }
+
     /// Create a new `Date` representing the number of seconds
     /// relative to the year 0001-01-01.
     ///
     /// # Arguments
     ///
-    /// * `seconds` - The number of seconds relative to midnight.
-    /// * `calendar` - The calendar this `Time` is relative to.
+    /// * `seconds` - The number of seconds relative to the epoch. Negative
+    ///   values are before it, rounding down to the day they fall in.
+    /// * `calendar` - The calendar this `Date` is relative to.
     ///
     /// # Examples
     ///
     /// ```rust
     /// # use holding_kronos::{
     /// #    calendar::Calendar,
-    /// #    datetime::Date,
+    /// #    datetime::{Date, traits::ShowDate},
     /// # };
     /// #
     /// let cal = Calendar::default();
     /// let date = Date::from_seconds(60, &cal);
+    /// assert_eq!(Date::from_seconds(-1, &cal).year(), 0);
     /// ```
     pub fn from_seconds(seconds: i64, calendar: &'a Calendar) -> Self {
-        let total_days = seconds / i64::from(calendar.days_to_seconds(1));
-        let (years, year_days) = div_rem(total_days, i64::from(calendar.days_in_year()));
-        let (month, day) = calendar.days_to_months(year_days as u32);
-        Date::from_ymd(years + 1, month + 1, day + 1, calendar)
-            .expect("If this is out of bounds it is a programmer error")
-    }
-
-    // given a number of days into the year, gets the month component
-    // and the year component.
-    fn calculate_month_and_day(&self, days: u32) -> (u32, u32) {
-        let mut months = self.calendar.months().iter().cycle().enumerate();
-        let mut delta_days = days;
-        loop {
-            let (delta_months, month) = months.next().expect("This is on a cycle");
-            let month_days = month.days;
-            if month_days > delta_days {
-                return (delta_months as u32 + 1, delta_days + 1);
-            } else {
-                delta_days -= month_days;
+        let total_days = i128::from(seconds)
+            .div_euclid(i128::from(calendar.seconds_in_day_u64()))
+            .try_into()
+            .expect("There are fewer days than seconds");
+        Date {
+            date: calendar.date_from_days(total_days),
+            calendar,
+        }
+    }
+
+    /// Gets the (1-indexed) position and day name of this date in
+    /// a named cycle of the calendar, such as a market week.
+    ///
+    /// Returns None if the calendar has no cycle with that name.
+    pub fn cycle_day(&self, name: &str) -> Option<(u32, &'a str)> {
+        self.calendar.cycle_day_of(self.date, name)
+    }
+
+    /// Gets the most specific era this date falls
+    /// in, if any, as chosen by `Calendar::era_for`.
+    pub fn era(&self) -> Option<&'a Era> {
+        self.calendar.era_for(self.date.year)
+    }
+
+    /// Gets the number of years since the start of the era this date
+    /// falls in, so the year an era starts is year 0 of that era. This
+    /// way, years in the default Common Era match the absolute year.
+    ///
+    /// Returns None if the date is in no era, or the era has no start.
+    pub fn era_year(&self) -> Option<i64> {
+        let start = self.calendar.astronomical_year(self.era()?.start_year()?);
+        Some(self.calendar.astronomical_year(self.date.year) - start)
+    }
+
+    /// Gets the kind of day this is in the rhythm of the week,
+    /// such as a market day or a day of rest.
+    pub fn day_kind(&self) -> &'a DayKind {
+        &self.calendar.week_days()[self.week_day() as usize - 1].kind
+    }
+
+    /// Gets the number of whole days from this date until another,
+    /// which is negative if the other date comes first.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use holding_kronos::{calendar::Calendar, datetime::Date};
+    /// #
+    /// let cal = Calendar::default();
+    /// let new_year = Date::from_ymd(2020, 12, 31, &cal).unwrap();
+    /// let after = Date::from_ymd(2021, 1, 2, &cal).unwrap();
+    /// assert_eq!(new_year.days_until(&after), 2);
+    /// assert_eq!(after.days_until(&new_year), -2);
+    /// ```
+    pub fn days_until(&self, other: &Date) -> i64 {
+        other.calendar.days_since_epoch(other.date) - self.calendar.days_since_epoch(self.date)
+    }
+
+    /// Moves to a (0-indexed) day of this month, dealing with
+    /// days past the end of the month according to the policy.
+    fn with_day(self, day: u32, policy: OverflowPolicy) -> Self {
+        let last = self
+            .calendar
+            .days_in_month_for(self.date.year, self.date.month + 1)
+            .unwrap_or(1)
+            .saturating_sub(1);
+        let date = Date {
+            date: RawDate {
+                day: day.min(last),
+                ..self.date
+            },
+            ..self
+        };
+
+        match policy {
+            OverflowPolicy::Rollover if day > last => date.add_days(day - last),
+            _ => date,
+        }
+    }
+
+    /// Stops a date that was moved back past the epoch at the epoch,
+    /// or where it started if it was already before the epoch. A date
+    /// that couldn't be moved at all also stops there.
+    fn stop_at_epoch(self, moved: Option<Self>) -> Self {
+        let floor = self.date.min(RawDate::from_index(1, 0, 0));
+        match moved {
+            Some(moved) if moved.date >= floor => moved,
+            _ => Date {
+                date: floor,
+                calendar: self.calendar,
+            },
+        }
+    }
+
+    /// Gets the year a number of years away from this one,
+    /// skipping year 0 if the calendar has none.
+    fn checked_offset_year(&self, years: i64) -> Option<i64> {
+        let year = self
+            .calendar
+            .astronomical_year(self.date.year)
+            .checked_add(years)?;
+        self.calendar.checked_year_from_astronomical(year)
+    }
+
+    /// Gets the year and month a number of months after this date,
+    /// returning None if the year would overflow.
+    fn checked_offset_month(&self, months: u32) -> Option<(i64, u32)> {
+        let (years, month) = div_rem(
+            u64::from(self.date.month) + u64::from(months),
+            self.calendar.months().len() as u64,
+        );
+        let month = month
+            .try_into()
+            .expect("This is less than the months in a year");
+        Some((self.checked_offset_year(years.try_into().ok()?)?, month))
+    }
+
+    fn offset_year(&self, years: i64) -> i64 {
+        self.checked_offset_year(years)
+            .expect("The year is too far from the epoch")
+    }
+
+    /// Formats the date according to a pattern, where
+    ///
+    /// - `%Y` is the year
+    /// - `%m` is the (1-indexed) month, and `%0m` is the same padded to two digits
+    /// - `%d` is the (1-indexed) day, and `%0d` is the same padded to two digits
+    /// - `%o` is the day as an ordinal, such as 2nd
+    /// - `%B` is the name of the month
+    /// - `%A` is the name of the week day
+    /// - `%E` is the name of the era
+    /// - `%e` is the year within the era
+    /// - `%%` is a literal `%`
+    ///
+    /// Era tokens are left empty when the date is in no era.
+    /// Any other token is left as it is.
+    pub fn format(&self, pattern: &str) -> String {
+        self.format_with_time(pattern, None)
+    }
+
+    /// Formats the date according to a pattern, also filling
+    /// in the time tokens if there is a time to fill them with.
+    pub(crate) fn format_with_time(&self, pattern: &str, time: Option<Time>) -> String {
+        let mut out = String::with_capacity(pattern.len());
+        let mut chars = pattern.chars().peekable();
+        while let Some(c) = chars.next() {
+            if c != '%' {
+                out.push(c);
+                continue;
+            }
+
+            let token = chars.next();
+            if let (Some(unit @ ('H' | 'M' | 'S' | 'I' | 'p' | 'P')), Some(time)) = (token, time) {
+                match unit {
+                    'H' => out.push_str(&format!("{:0>2}", time.hour())),
+                    'M' => out.push_str(&format!("{:0>2}", time.minute())),
+                    'S' => out.push_str(&format!("{:0>2}", time.second())),
+                    'I' => out.push_str(&format!("{:0>2}", time.clock_12h().0)),
+                    'p' => out.push_str(time.clock_12h().1),
+                    _ => out.push_str(self.calendar.period_name(time.time_of_day())),
+                }
+                continue;
+            }
+
+            match token {
+                Some('Y') => out.push_str(&self.year().to_string()),
+                Some('m') => out.push_str(&self.month().to_string()),
+                Some('d') => out.push_str(&self.day().to_string()),
+                Some('0') if matches!(chars.peek(), Some('m' | 'd')) => {
+                    let value = match chars.next() {
+                        Some('m') => self.month(),
+                        _ => self.day(),
+                    };
+                    out.push_str(&format!("{:0>2}", value));
+                }
+                Some('o') => out.push_str(&ordinal(self.day())),
+                Some('B') => out.push_str(self.month_name()),
+                Some('A') => out.push_str(self.week_day_name()),
+                Some('E') => out.push_str(self.era().map_or("", Era::name)),
+                Some('e') => {
+                    if let Some(year) = self.era_year() {
+                        out.push_str(&year.to_string())
+                    }
+                }
+                Some('%') => out.push('%'),
+                Some(other) => {
+                    out.push('%');
+                    out.push(other);
+                }
+                None => out.push('%'),
             }
         }
+        out
     }
 }
 
+/// Writes a number as an English ordinal, such as 1st or 12th.
+fn ordinal(n: u32) -> String {
+    let suffix = match (n % 10, n % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{}{}", n, suffix)
+}
+
 impl PartialOrd for Date<'_> {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
         if self.calendar == other.calendar {
@@ -191,29 +465,17 @@ impl<'a> ShowDate for Date<'a> {
     }
 
     fn days(&self) -> u32 {
-        self.calendar
-            .months()
-            .iter()
-            .take(self.date.month as usize)
-            .map(|m| m.days)
-            .sum::<u32>()
-            + self.date.day
+        self.calendar.day_of_year(self.date)
     }
 
     fn week_day(&self) -> u32 {
-        let week_length: i64 = self
-            .calendar
-            .week_days()
-            .len()
-            .try_into()
-            .expect("This always fits inside an i64");
-        let year_length = i64::from(self.calendar.days_in_year());
-        let days_so_far = year_length * (self.date.year - 1) + i64::from(self.days());
-        let week_day: u32 = (days_so_far % week_length)
-            .try_into()
-            .expect("This should be positive");
+        self.calendar.week_day_of(self.date)
+    }
 
-        week_day + 1
+    fn week_of_month(&self) -> u32 {
+        let first = RawDate::from_index(self.date.year, self.date.month, 0);
+        let offset = self.calendar.week_day_of(first) - 1;
+        (self.date.day + offset) / self.calendar.days_in_week() + 1
     }
 
     fn week_day_name(&self) -> &str {
@@ -224,6 +486,10 @@ impl<'a> ShowDate for Date<'a> {
         &self.calendar.months()[self.month() as usize - 1].name
     }
 
+    fn is_leap_year(&self) -> bool {
+        self.calendar.is_leap_year(self.date.year)
+    }
+
     fn week(&self) -> u32 {
         let days = self.year().abs() as u64 * u64::from(self.calendar.days_in_year())
             + u64::from(self.days());
@@ -239,22 +505,26 @@ impl<'a> ModifyDate for Date<'a> {
         Date {
             calendar: self.calendar,
             date: RawDate {
-                year: self.date.year + i64::from(years),
+                year: self.offset_year(i64::from(years)),
                 ..self.date
             },
         }
+        .with_day(self.date.day, OverflowPolicy::Clamp)
     }
 
-    fn add_months(self, months: u32) -> Self::Item {
-        let (year, month) = div_rem(months, self.calendar.months().len() as u32);
+    fn add_months_with(self, months: u32, policy: OverflowPolicy) -> Self::Item {
+        let (year, month) = self
+            .checked_offset_month(months)
+            .expect("The year is too far from the epoch");
         Date {
             date: RawDate {
-                year: self.date.year + i64::from(year),
-                month: self.date.month + month,
-                day: self.date.day,
+                year,
+                month,
+                day: 0,
             },
             calendar: self.calendar,
         }
+        .with_day(self.date.day, policy)
     }
 
     fn add_weeks(self, weeks: u32) -> Self::Item {
@@ -262,22 +532,178 @@ impl<'a> ModifyDate for Date<'a> {
     }
 
     fn add_days(self, days: u32) -> Self::Item {
-        if days == 0 {
-            return self;
+        let days = self.calendar.days_since_epoch(self.date) + i64::from(days);
+        Date {
+            date: self.calendar.date_from_days(days),
+            calendar: self.calendar,
         }
+    }
 
-        // get the number of years and days into the year
-        let (delta_year, delta_days) = div_rem(self.days() + days, self.calendar.days_in_year());
-        let year = self.year() + i64::from(delta_year);
+    fn sub_years(self, years: u32) -> Self::Item {
+        Date {
+            calendar: self.calendar,
+            date: RawDate {
+                year: self.offset_year(-i64::from(years)),
+                ..self.date
+            },
+        }
+        .with_day(self.date.day, OverflowPolicy::Clamp)
+    }
+
+    fn sub_months(self, months: u32) -> Self::Item {
+        let months_in_year = i64::from(self.calendar.months_in_year());
+        let total = self.calendar.astronomical_year(self.date.year) * months_in_year
+            + i64::from(self.date.month)
+            - i64::from(months);
+        Date {
+            date: RawDate {
+                year: self
+                    .calendar
+                    .year_from_astronomical(total.div_euclid(months_in_year)),
+                month: total
+                    .rem_euclid(months_in_year)
+                    .try_into()
+                    .expect("This is less than a u32"),
+                day: 0,
+            },
+            calendar: self.calendar,
+        }
+        .with_day(self.date.day, OverflowPolicy::Clamp)
+    }
 
-        // get month and day component
-        let (month, day) = self.calculate_month_and_day(delta_days);
+    fn sub_weeks(self, weeks: u32) -> Self::Item {
+        self.sub_days(weeks * self.calendar.days_in_week())
+    }
 
+    fn sub_days(self, days: u32) -> Self::Item {
+        let days = self.calendar.days_since_epoch(self.date) - i64::from(days);
         Date {
-            date: RawDate::new(year, month, day).expect("Programmer error"),
+            date: self.calendar.date_from_days(days),
             calendar: self.calendar,
         }
     }
+
+    fn subtract_years(self, years: u32) -> Self::Item {
+        self.stop_at_epoch(self.checked_sub_years(years))
+    }
+
+    fn subtract_months(self, months: u32) -> Self::Item {
+        self.stop_at_epoch(self.checked_sub_months(months))
+    }
+
+    fn subtract_weeks(self, weeks: u32) -> Self::Item {
+        self.stop_at_epoch(self.checked_sub_weeks(weeks))
+    }
+
+    fn subtract_days(self, days: u32) -> Self::Item {
+        self.stop_at_epoch(self.checked_sub_days(days))
+    }
+
+    fn checked_add_years(self, years: u32) -> Option<Self::Item> {
+        Some(
+            Date {
+                calendar: self.calendar,
+                date: RawDate {
+                    year: self.checked_offset_year(i64::from(years))?,
+                    ..self.date
+                },
+            }
+            .with_day(self.date.day, OverflowPolicy::Clamp),
+        )
+    }
+
+    fn checked_add_months(self, months: u32) -> Option<Self::Item> {
+        let (year, month) = self.checked_offset_month(months)?;
+        Some(
+            Date {
+                date: RawDate {
+                    year,
+                    month,
+                    day: 0,
+                },
+                calendar: self.calendar,
+            }
+            .with_day(self.date.day, OverflowPolicy::Clamp),
+        )
+    }
+
+    fn checked_add_weeks(self, weeks: u32) -> Option<Self::Item> {
+        self.checked_add_days(weeks.checked_mul(self.calendar.days_in_week())?)
+    }
+
+    fn checked_add_days(self, days: u32) -> Option<Self::Item> {
+        Some(Date {
+            date: self
+                .calendar
+                .checked_offset_days(self.date, i64::from(days))?,
+            calendar: self.calendar,
+        })
+    }
+
+    fn checked_sub_years(self, years: u32) -> Option<Self::Item> {
+        Some(
+            Date {
+                calendar: self.calendar,
+                date: RawDate {
+                    year: self.checked_offset_year(-i64::from(years))?,
+                    ..self.date
+                },
+            }
+            .with_day(self.date.day, OverflowPolicy::Clamp),
+        )
+    }
+
+    fn checked_sub_months(self, months: u32) -> Option<Self::Item> {
+        let months_in_year = self.calendar.months_in_year();
+        let (years, months) = div_rem(months, months_in_year);
+        // borrow a year if going back past the start of this one
+        let (years, month) = match self.date.month.checked_sub(months) {
+            Some(month) => (i64::from(years), month),
+            None => (
+                i64::from(years) + 1,
+                self.date.month + months_in_year - months,
+            ),
+        };
+        Some(
+            Date {
+                date: RawDate {
+                    year: self.checked_offset_year(-years)?,
+                    month,
+                    day: 0,
+                },
+                calendar: self.calendar,
+            }
+            .with_day(self.date.day, OverflowPolicy::Clamp),
+        )
+    }
+
+    fn checked_sub_weeks(self, weeks: u32) -> Option<Self::Item> {
+        self.checked_sub_days(weeks.checked_mul(self.calendar.days_in_week())?)
+    }
+
+    fn checked_sub_days(self, days: u32) -> Option<Self::Item> {
+        Some(Date {
+            date: self
+                .calendar
+                .checked_offset_days(self.date, -i64::from(days))?,
+            calendar: self.calendar,
+        })
+    }
+}
+
+/// Adds a duration to a date. The hours, minutes, and seconds only
+/// count once they add up to whole days, so adding less than a day
+/// leaves the date as it is. Add to a `DateTime` to keep the rest.
+impl<'a> Add<Duration> for Date<'a> {
+    type Output = Date<'a>;
+
+    fn add(self, duration: Duration) -> Self::Output {
+        let (days, _) = duration.clock_days(self.calendar);
+        self.add_years(duration.years)
+            .add_months(duration.months)
+            .add_weeks(duration.weeks)
+            .add_days(duration.days.saturating_add(days))
+    }
 }
 
 impl From<Date<'_>> for RawDate {
@@ -296,10 +722,14 @@ impl Display for Date<'_> {
 #[derive(Error, Debug, Copy, Clone)]
 #[allow(missing_docs)]
 pub enum InvalidDateError {
+    #[error("year {0} is out of bounds")]
+    YearOutOfBounds(i64),
     #[error("month {0} is out of bounds")]
     MonthOutOfBounds(u32),
     #[error("day {0} is out of bounds")]
     DayOutOfBounds(u32),
+    #[error("week day {0} is out of bounds")]
+    WeekDayOutOfBounds(u32),
 }
 
 #[cfg(test)]
@@ -309,13 +739,216 @@ mod test {
 
     use crate::{
         calendar::traits::YearCycle,
-        calendar::Calendar,
+        calendar::{Calendar, Era, LeapRule, YearZero},
         datetime::{
-            traits::{ModifyDate, ShowDate},
-            Date,
+            traits::{ModifyDate, OverflowPolicy, ShowDate},
+            Date, Duration,
         },
     };
 
+    use super::{InvalidDateError, RawDate};
+
+    #[test]
+    pub fn ordinal_and_index_agree() {
+        let first = RawDate::from_ordinal(3, 1, 1).expect("valid date");
+        assert_eq!(first, RawDate::from_index(3, 0, 0));
+        assert_eq!((first.month, first.day), (0, 0));
+        assert_eq!(first.to_string(), "0003-01-01");
+        assert_eq!(
+            RawDate::new(3, 2, 5).ok(),
+            RawDate::from_ordinal(3, 2, 5).ok()
+        );
+        assert_eq!(RawDate::from_index(3, 1, 4).to_string(), "0003-02-05");
+    }
+
+    #[test]
+    pub fn counts_years_within_era() {
+        let mut cal = Calendar::default();
+        cal.add_era(Era::new("the Third Age".to_string(), Some(1000), None));
+
+        let date = Date::from_ymd(1005, 3, 1, &cal).expect("valid date");
+        assert_eq!(date.era().map(Era::name), Some("the Third Age"));
+        assert_eq!(date.era_year(), Some(5));
+        assert_eq!(date.format("Year %e of %E"), "Year 5 of the Third Age");
+
+        let before = Date::from_ymd(999, 3, 1, &cal).expect("valid date");
+        assert_eq!(before.era().map(Era::name), Some("Common Era"));
+        assert_eq!(before.era_year(), Some(999));
+    }
+
+    #[test]
+    pub fn picks_the_narrowest_era() {
+        let mut cal = Calendar::default();
+        cal.add_era(Era::new("the Third Age".to_string(), Some(1000), None));
+        cal.add_era(Era::new(
+            "the Long Peace".to_string(),
+            Some(900),
+            Some(1100),
+        ));
+        cal.add_era(Era::new("the Siege".to_string(), Some(1010), Some(1012)));
+
+        let era = |year| cal.era_for(year).map(Era::name);
+        assert_eq!(era(1011), Some("the Siege"));
+        assert_eq!(era(1050), Some("the Long Peace"));
+        assert_eq!(era(1200), Some("the Third Age"));
+        assert_eq!(era(950), Some("the Long Peace"));
+        assert_eq!(era(-5), Some("Before Common Era"));
+
+        let date = Date::from_ymd(1012, 1, 1, &cal).expect("valid date");
+        assert_eq!(date.era().map(Era::name), Some("the Siege"));
+    }
+
+    #[test_case("%Y-%m-%d", "20-2-5" ; "numeric")]
+    #[test_case("%d %B, %E", "5 February, Common Era" ; "names")]
+    #[test_case("100%% %q", "100% %q" ; "escapes")]
+    #[test_case("%Y-%0m-%0d", "20-02-05" ; "padded")]
+    #[test_case("%A the %o of %B, %Y", "Saturday the 5th of February, 20" ; "ordinal")]
+    #[test_case("%0q %H", "%0q %H" ; "no time")]
+    pub fn formats_date(pattern: &str, expected: &str) {
+        let cal = Calendar::default();
+        let date = Date::from_ymd(20, 2, 5, &cal).expect("valid date");
+        assert_eq!(date.format(pattern), expected);
+    }
+
+    #[test]
+    pub fn no_era_year_before_any_era() {
+        let cal = Calendar::default();
+        let date = Date::from_ymd(-5, 1, 1, &cal).expect("valid date");
+        assert_eq!(date.era().map(Era::name), Some("Before Common Era"));
+        assert_eq!(date.era_year(), None);
+        assert_eq!(date.format("%e %E"), " Before Common Era");
+    }
+
+    #[test_case(YearZero::Astronomical, 0, "Common Era" ; "astronomical")]
+    #[test_case(YearZero::Historical, -1, "Before Common Era" ; "historical")]
+    pub fn year_before_year_one(year_zero: YearZero, expected: i64, era: &str) {
+        let mut cal = Calendar::default();
+        cal.set_year_zero(year_zero);
+        let first = Date::from_ymd(1, 1, 1, &cal).expect("valid date");
+
+        let before = first.sub_days(1);
+        assert_eq!(before.year(), expected);
+        assert_eq!(first.sub_months(1).year(), expected);
+        assert_eq!(first.sub_years(1).year(), expected);
+        assert_eq!(Date::from_seconds(-1, &cal), before);
+        assert_eq!(before.add_days(1), first);
+        assert_eq!(before.add_years(1).year(), 1);
+        assert_eq!(before.era().map(Era::name), Some(era));
+        assert_eq!(
+            Date::from_ymd(0, 1, 1, &cal).is_ok(),
+            year_zero == YearZero::Astronomical
+        );
+    }
+
+    #[test]
+    pub fn historical_era_years_skip_year_zero() {
+        let mut cal = Calendar::default();
+        cal.set_year_zero(YearZero::Historical);
+        cal.add_era(Era::new("the Long Night".to_string(), Some(-3), Some(-1)));
+
+        let date = Date::from_ymd(-1, 1, 1, &cal).expect("valid date");
+        assert_eq!(date.era_year(), Some(2));
+    }
+
+    #[test]
+    pub fn adds_the_most_months() {
+        let cal = Calendar::default();
+        let date = Date::from_ymd(1, 12, 1, &cal).expect("valid date");
+        let expected = Date::from_ymd(357_913_943, 3, 1, &cal).expect("valid date");
+        assert_eq!(date.add_months(u32::MAX), expected);
+        assert_eq!(date.checked_add_months(u32::MAX), Some(expected));
+    }
+
+    #[test]
+    pub fn checked_arithmetic_stops_at_the_bounds() {
+        let cal = Calendar::default();
+        let late = Date::from_ymd(i64::MAX - 1, 12, 31, &cal).expect("valid date");
+        assert_eq!(late.checked_add_years(1).map(|d| d.year()), Some(i64::MAX));
+        assert_eq!(late.checked_add_years(2), None);
+        assert_eq!(late.checked_add_months(13), None);
+        assert_eq!(late.checked_add_days(366), None);
+        assert_eq!(late.checked_add_weeks(u32::MAX), None);
+
+        assert_eq!(
+            late.checked_add_days(1),
+            Some(Date::from_ymd(i64::MAX, 1, 1, &cal).expect("valid date"))
+        );
+
+        let early = Date::from_ymd(i64::MIN + 1, 1, 1, &cal).expect("valid date");
+        let last = Date::from_ymd(i64::MIN, 12, 31, &cal).expect("valid date");
+        assert_eq!(early.checked_sub_years(2), None);
+        assert_eq!(early.checked_sub_months(1).map(|d| d.month()), Some(12));
+        assert_eq!(
+            early.checked_sub_months(1).map(|d| d.year()),
+            Some(i64::MIN)
+        );
+        assert_eq!(early.checked_sub_months(13), None);
+        assert_eq!(early.checked_sub_days(1), Some(last));
+        assert_eq!(early.checked_sub_days(366), None);
+
+        let mut leap = Calendar::default();
+        leap.set_leap_rule(Some(LeapRule {
+            cycle: 4,
+            month: 1,
+            extra: 1,
+        }));
+        let early = Date::from_ymd(i64::MIN + 1, 1, 1, &leap).expect("valid date");
+        let first = Date::from_ymd(i64::MIN, 1, 1, &leap).expect("valid date");
+        assert!(leap.is_leap_year(i64::MIN));
+        assert_eq!(early.checked_sub_days(366), Some(first));
+        assert_eq!(early.checked_sub_days(367), None);
+        let march = Date::from_ymd(i64::MIN + 4, 3, 1, &leap).expect("valid date");
+        let leap_day = Date::from_ymd(i64::MIN + 4, 2, 29, &leap).expect("a leap year");
+        assert_eq!(march.checked_sub_days(1), Some(leap_day));
+        assert_eq!(leap_day.checked_add_days(1), Some(march));
+
+        let date = Date::from_ymd(2020, 3, 1, &cal).expect("valid date");
+        assert_eq!(date.checked_add_days(40), Some(date.add_days(40)));
+        assert_eq!(date.checked_sub_months(14), Some(date.sub_months(14)));
+        assert_eq!(date.checked_sub_weeks(3), Some(date.sub_weeks(3)));
+    }
+
+    #[test_case((1, 3, 10), 2, (1, 1, 10) ; "within range")]
+    #[test_case((1, 3, 10), 3, (1, 1, 1) ; "stops at the epoch")]
+    #[test_case((2, 1, 1), 1200, (1, 1, 1) ; "many months")]
+    #[test_case((-5, 6, 1), 12, (-5, 6, 1) ; "already before the epoch")]
+    pub fn subtracts_months_to_the_epoch(
+        from: (i64, u32, u32),
+        months: u32,
+        expected: (i64, u32, u32),
+    ) {
+        let cal = Calendar::default();
+        let date = |(y, m, d)| Date::from_ymd(y, m, d, &cal).expect("valid date");
+        assert_eq!(date(from).subtract_months(months), date(expected));
+    }
+
+    #[test_case(0, 1 ; "month zero")]
+    #[test_case(1, 0 ; "day zero")]
+    pub fn ordinal_rejects_zero(month: u32, day: u32) {
+        assert!(RawDate::from_ordinal(1, month, day).is_err());
+    }
+
+    #[test_case((1, 3, 1), (1, 3, 15), 14 ; "same year")]
+    #[test_case((1, 12, 30), (2, 1, 2), 3 ; "across a year boundary")]
+    #[test_case((2, 1, 2), (1, 12, 30), -3 ; "reversed")]
+    #[test_case((-1, 1, 1), (1, 1, 1), 730 ; "from a negative year")]
+    pub fn counts_days_until(from: (i64, u32, u32), to: (i64, u32, u32), expected: i64) {
+        let cal = Calendar::default();
+        let date = |(y, m, d)| Date::from_ymd(y, m, d, &cal).expect("valid date");
+        assert_eq!(date(from).days_until(&date(to)), expected);
+    }
+
+    #[test_case(1, 1, 1 ; "first of a month starting on monday")]
+    #[test_case(1, 8, 2 ; "eighth of a month starting on monday")]
+    #[test_case(1, 7, 1 ; "end of the first full week")]
+    #[test_case(2, 4, 1 ; "end of a partial first week")]
+    #[test_case(2, 5, 2 ; "start of the second week")]
+    pub fn week_of_month(month: u32, day: u32, week: u32) {
+        let cal = Calendar::default();
+        let date = Date::from_ymd(1, month, day, &cal).expect("valid date");
+        assert_eq!(date.week_of_month(), week);
+    }
+
     #[test_case(1, 1, 1, 2)]
     #[test_case(5, 1, 1, 6)]
     #[test_case(50, 1, 2, 20)]
@@ -329,7 +962,80 @@ mod test {
         );
     }
 
+    #[test_case(1101, 12, 1102, 1 ; "rolls over the year")]
+    #[test_case(1101, 1, 1100, 12 ; "rolls back the year")]
+    pub fn steps_months(year: i64, month: u32, next_year: i64, next_month: u32) {
+        let cal = Calendar::default();
+        let date = Date::from_ymd(year, month, 15, &cal).expect("valid date");
+        let stepped = if next_year > year {
+            date.succ_month()
+        } else {
+            date.pred_month()
+        };
+        assert_eq!(
+            stepped,
+            Date::from_ymd(next_year, next_month, 15, &cal).expect("valid date")
+        );
+    }
+
     proptest! {
+        #[test]
+        fn steps_return_to_origin(year in 1i64..3000, month in 1u32..=12, day in 1u32..=28) {
+            let cal = Calendar::default();
+            let date = Date::from_ymd(year, month, day, &cal).expect("valid date");
+
+            assert_eq!(date.succ_day().pred_day(), date);
+            assert_eq!(date.pred_day().succ_day(), date);
+            assert_eq!(date.succ_month().pred_month(), date);
+            assert_eq!(date.pred_month().succ_month(), date);
+            assert_eq!(date.succ_year().pred_year(), date);
+            assert_eq!(date.pred_year().succ_year(), date);
+            assert_eq!(date.add_weeks(3).sub_weeks(3), date);
+        }
+
+        #[test]
+        fn leap_dates_round_trip(seconds in -100_000_000_000i64..100_000_000_000) {
+            let mut cal = Calendar::default();
+            cal.set_leap_rule(Some(LeapRule::new(4, 1, 1)));
+
+            let date = Date::from_seconds(seconds, &cal);
+            assert!(cal.validate_date(&date).is_ok());
+            let days = cal.days_since_epoch(date.date);
+            assert_eq!(days, seconds.div_euclid(86400));
+            assert_eq!(cal.date_from_days(days), date.date);
+            assert_eq!(date.days(), cal.day_of_year(date.date));
+            assert!(date.days() < cal.days_in_year_for(date.year()));
+        }
+
+        #[test]
+        fn leap_days_are_counted(year in -3000i64..3000, month in 1u32..=12, day in 1u32..=31, n in 0u32..100000) {
+            let mut cal = Calendar::default();
+            cal.set_leap_rule(Some(LeapRule::new(4, 1, 1)));
+            prop_assume!(day <= cal.days_in_month_for(year, month).expect("valid month"));
+            let date = Date::from_ymd(year, month, day, &cal).expect("valid date");
+
+            assert_eq!(Date::from_seconds(cal.days_since_epoch(date.date) * 86400, &cal), date);
+            assert_eq!(date.add_days(n).sub_days(n), date);
+            assert_eq!(date.days_until(&date.add_days(n)), i64::from(n));
+            assert_eq!(date.succ_day().days_until(&date), -1);
+        }
+
+        #[test]
+        fn subtract_days_undoes_add_days(year in 1i64..3000, month in 1u32..=12, day in 1u32..=28, n in 0u32..100000) {
+            let cal = Calendar::default();
+            let date = Date::from_ymd(year, month, day, &cal).expect("valid date");
+
+            assert_eq!(date.add_days(n).subtract_days(n), date);
+        }
+
+        #[test]
+        fn sub_days_undoes_add_days(year in -3000i64..3000, month in 1u32..=12, day in 1u32..=28, n in 0u32..100000) {
+            let cal = Calendar::default();
+            let date = Date::from_ymd(year, month, day, &cal).expect("valid date");
+
+            assert_eq!(date.add_days(n).sub_days(n), date);
+        }
+
         #[test]
         fn days(days in 0u32..10000) {
             let cal = Calendar::default();
@@ -356,6 +1062,78 @@ mod test {
         );
     }
 
+    #[test_case(OverflowPolicy::Clamp, false, (1, 2, 28) ; "clamps")]
+    #[test_case(OverflowPolicy::Rollover, false, (1, 3, 3) ; "rolls over")]
+    #[test_case(OverflowPolicy::Clamp, true, (1, 2, 29) ; "clamps in a leap year")]
+    #[test_case(OverflowPolicy::Rollover, true, (1, 3, 2) ; "rolls over in a leap year")]
+    pub fn add_month_overflow(policy: OverflowPolicy, leap: bool, expected: (i64, u32, u32)) {
+        let mut cal = Calendar::default();
+        if leap {
+            cal.set_leap_rule(Some(LeapRule::new(1, 1, 1)));
+        }
+        let date = Date::from_ymd(1, 1, 31, &cal).expect("valid date");
+        let (year, month, day) = expected;
+
+        let next = date.add_months_with(1, policy);
+        assert_eq!(
+            next,
+            Date::from_ymd(year, month, day, &cal).expect("valid date")
+        );
+        assert_eq!(
+            date.add_months(1),
+            date.add_months_with(1, OverflowPolicy::Clamp)
+        );
+    }
+
+    #[test]
+    pub fn counts_leap_days() {
+        let mut cal = Calendar::default();
+        cal.set_leap_rule(Some(LeapRule::new(4, 1, 1)));
+
+        assert_eq!(cal.days_in_year_for(4), 366);
+        assert_eq!(cal.days_in_year_for(5), 365);
+        assert_eq!(cal.days_in_month_for(4, 2), Some(29));
+        assert_eq!(cal.days_in_month_for(5, 2), Some(28));
+        assert!(Date::from_ymd(5, 2, 29, &cal).is_err());
+
+        let leap_day = Date::from_ymd(4, 2, 29, &cal).expect("year 4 is a leap year");
+        assert_eq!(
+            Date::from_ymd(4, 2, 28, &cal)
+                .expect("valid date")
+                .succ_day(),
+            leap_day
+        );
+        assert_eq!(
+            leap_day.succ_day(),
+            Date::from_ymd(4, 3, 1, &cal).expect("valid date")
+        );
+        assert_eq!(leap_day.days(), 59);
+        assert_eq!(
+            Date::from_ordinal_day(4, 366, &cal)
+                .expect("valid day")
+                .month(),
+            12
+        );
+        assert!(Date::from_ordinal_day(5, 366, &cal).is_err());
+        assert_eq!(
+            leap_day.add_years(1),
+            Date::from_ymd(5, 2, 28, &cal).expect("valid date")
+        );
+
+        let first = Date::from_ymd(5, 1, 1, &cal).expect("valid date");
+        assert_eq!(
+            Date::from_ymd(1, 1, 1, &cal)
+                .expect("valid date")
+                .days_until(&first),
+            365 * 4 + 1
+        );
+        assert_eq!(Date::from_seconds(86400 * (365 * 4 + 1), &cal), first);
+        assert_eq!(
+            Date::from_seconds(-86400 * 366, &cal),
+            Date::from_ymd(0, 1, 1, &cal).expect("year 0 is a leap year")
+        );
+    }
+
     #[test_case(1, 2, 1, 1)]
     #[test_case(5, 6, 1, 1)]
     #[test_case(120, 121, 1, 1)]
@@ -378,4 +1156,52 @@ mod test {
             Date::from_ymd(year, month, day, &cal).unwrap().date
         );
     }
+
+    #[test_case(1, 1, 1)]
+    #[test_case(32, 2, 1)]
+    #[test_case(365, 12, 31)]
+    pub fn from_ordinal_day(day_of_year: u32, month: u32, day: u32) {
+        let cal = Calendar::default();
+        let date = Date::from_ordinal_day(1101, day_of_year, &cal).expect("valid date");
+
+        assert_eq!(
+            date,
+            Date::from_ymd(1101, month, day, &cal).expect("valid date")
+        );
+        assert_eq!(date.days() + 1, day_of_year);
+    }
+
+    #[test_case(0)]
+    #[test_case(366)]
+    pub fn ordinal_day_out_of_bounds(day_of_year: u32) {
+        let cal = Calendar::default();
+        assert!(matches!(
+            Date::from_ordinal_day(1101, day_of_year, &cal),
+            Err(InvalidDateError::DayOutOfBounds(d)) if d == day_of_year
+        ));
+    }
+
+    #[test]
+    pub fn adds_duration() {
+        let cal = Calendar::default();
+        let date = Date::from_ymd(1, 1, 31, &cal).expect("valid date");
+        let hours = |hours| Duration {
+            hours,
+            ..Duration::default()
+        };
+
+        assert_eq!(
+            date + hours(25),
+            Date::from_ymd(1, 2, 1, &cal).expect("valid date")
+        );
+        assert_eq!(date + hours(23), date);
+        assert_eq!(
+            date + Duration {
+                months: 1,
+                days: 1,
+                ..Duration::default()
+            },
+            Date::from_ymd(1, 3, 1, &cal).expect("valid date")
+        );
+    }
 }
@@ -1,18 +1,28 @@
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::{
+    cmp::Ordering,
+    convert::TryInto,
+    fmt::{Display, Formatter, Result as FmtResult},
+    ops::Add,
+    str::FromStr,
+};
 
 use thiserror::Error;
 
 use crate::{
-    calendar::{traits::ConvertTime, Calendar},
+    calendar::{
+        traits::{ConvertDate, ConvertTime, DayCycle},
+        Calendar, Era,
+    },
     datetime::{
         date::{Date, InvalidDateError},
         time::{InvalidTimeError, Time},
-        traits::{ModifyDate, ModifyDateTime, ModifyTime, ShowDate, ShowTime},
-        InvalidWaitError, TimeFormat, WaitTarget,
+        traits::{ModifyDate, ModifyDateTime, ModifyTime, OverflowPolicy, ShowDate, ShowTime},
+        Duration, InvalidWaitError, ParseRawError, TimeFormat, WaitTarget,
     },
+    units::Seconds,
 };
 
 use super::{date::RawDate, time::RawTime};
@@ -20,6 +30,12 @@ use super::{date::RawDate, time::RawTime};
 #[derive(Copy, Clone, Eq, PartialEq, Debug, PartialOrd, Ord)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 /// A datetime without the calendar (for serialization.)
+///
+/// Ordering is strictly lexicographic on the stored integers:
+/// year, month, day, hour, minute, then second. Since there
+/// is no calendar, values from different calendars can still
+/// be compared, though the result is meaningless. Within a
+/// single calendar it agrees with `DateTime::total_seconds`.
 pub struct RawDateTime {
     /// The date component.
     pub date: RawDate,
@@ -37,6 +53,33 @@ impl RawDateTime {
     }
 }
 
+/// A datetime stored as the number of seconds since the epoch.
+///
+/// This serializes as a single integer, making it the most compact
+/// form for long logs. Like `RawDateTime`, it has no calendar, and
+/// only means anything alongside the one it was made with.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(transparent))]
+pub struct EpochSeconds(pub i64);
+
+impl EpochSeconds {
+    /// Creates a new `EpochSeconds` from a raw datetime and its calendar.
+    pub fn from_raw(raw: RawDateTime, cal: &Calendar) -> Self {
+        raw.into_datetime(cal).into()
+    }
+
+    /// Convert this `EpochSeconds` into a `DateTime` object.
+    pub fn into_datetime(self, cal: &Calendar) -> DateTime<'_> {
+        DateTime::from_seconds(self.0, cal)
+    }
+}
+
+impl From<DateTime<'_>> for EpochSeconds {
+    fn from(val: DateTime<'_>) -> Self {
+        Self(val.total_seconds())
+    }
+}
+
 /// Allows you to do calendar-aware manipulation
 /// of the `DateTime`.
 #[derive(Copy, Clone, Eq, PartialEq, Debug, PartialOrd)]
@@ -82,11 +125,13 @@ impl<'a> DateTime<'a> {
         })
     }
 
-    /// Create a new `DateTime` representing the number of seconds.
+    /// Create a new `DateTime` representing the number of seconds
+    /// relative to the epoch, 0001-01-01 at midnight.
     ///
     /// # Arguments
     ///
-    /// * `seconds` - The number of seconds relative to midnight.
+    /// * `seconds` - The number of seconds relative to the epoch. Negative
+    ///   values are before it, so -1 is the last second of the day before.
     /// * `calendar` - The calendar this `Time` is relative to.
     ///
     /// # Examples
@@ -102,12 +147,11 @@ impl<'a> DateTime<'a> {
     /// # assert_eq!(time_1.hour(), 1);
     /// # assert_eq!(time_2.hour(), 2);
     /// ```
-    ///
-    /// todo(arlyon): Get working with negative values.
     pub fn from_seconds(seconds: i64, calendar: &'a Calendar) -> Self {
+        let time = i128::from(seconds).rem_euclid(i128::from(calendar.seconds_in_day_u64()));
         Self {
             date: Date::from_seconds(seconds, calendar),
-            time: Time::from_seconds(seconds as u32, calendar),
+            time: Time::from_seconds_u64(time.try_into().expect("less than a day"), calendar),
         }
     }
 
@@ -116,9 +160,178 @@ impl<'a> DateTime<'a> {
         self.date.calendar
     }
 
-    /// Gets the seconds this `DateTime` represents modulo some other.
-    pub fn seconds_modulo(&self, other: u32) -> u32 {
-        self.seconds() % other
+    /// Gets the total number of seconds since the epoch.
+    ///
+    /// This is the inverse of `DateTime::from_seconds`.
+    pub fn total_seconds(&self) -> i64 {
+        let calendar = self.calendar();
+        let day: i64 = calendar
+            .seconds_in_day_u64()
+            .try_into()
+            .expect("A day fits in an i64");
+        let time: i64 = self
+            .time
+            .seconds_u64()
+            .try_into()
+            .expect("This is less than a day");
+        calendar.days_since_epoch(self.date.date) * day + time
+    }
+
+    /// Moves forward by a duration, applying its components
+    /// from largest to smallest: years, months, weeks, days,
+    /// then hours, minutes, and seconds.
+    pub fn add_duration(&self, duration: Duration) -> DateTime<'a> {
+        *self + duration
+    }
+
+    /// Moves back by a duration, applying its components
+    /// from largest to smallest, as in `add_duration`.
+    pub fn sub_duration(&self, duration: Duration) -> DateTime<'a> {
+        let moved = self
+            .sub_years(duration.years)
+            .sub_months(duration.months)
+            .sub_weeks(duration.weeks)
+            .sub_days(duration.days);
+        let moved = ModifyDateTime::sub_hours(&moved, duration.hours);
+        let moved = ModifyDateTime::sub_minutes(&moved, duration.minutes);
+        ModifyDateTime::sub_seconds(&moved, duration.seconds)
+    }
+
+    /// Moves back by a duration as `sub_duration` does, but stops
+    /// at the epoch, 0001-01-01 at midnight, rather than going back
+    /// past it. A `DateTime` already before the epoch stays put.
+    pub fn subtract_duration(&self, duration: Duration) -> DateTime<'a> {
+        self.stop_at_epoch(Some(self.sub_duration(duration)))
+    }
+
+    /// Stops a `DateTime` that was moved back past the epoch at the
+    /// epoch, or where it started if it was already before the epoch.
+    fn stop_at_epoch(self, moved: Option<Self>) -> Self {
+        let epoch = DateTime::from_seconds(0, self.calendar());
+        let floor = if self.total_seconds() < 0 {
+            self
+        } else {
+            epoch
+        };
+        match moved {
+            Some(moved) if moved.total_seconds() >= floor.total_seconds() => moved,
+            _ => floor,
+        }
+    }
+
+    /// Compares two `DateTime`s by the number of seconds since the
+    /// epoch, regardless of which calendars they are presented in.
+    ///
+    /// Unlike `PartialOrd`, which refuses to compare across calendars,
+    /// this assumes every calendar shares the same epoch and second.
+    pub fn cmp_absolute(&self, other: &DateTime) -> Ordering {
+        self.total_seconds().cmp(&other.total_seconds())
+    }
+
+    /// Gets the number of seconds from `other` to this `DateTime`,
+    /// which is negative if `other` is later. Swapping the two
+    /// flips the sign.
+    ///
+    /// Both must share a calendar, otherwise they can't be
+    /// compared and a `DifferentCalendarError` is returned.
+    pub fn seconds_since(&self, other: &DateTime) -> Result<i64, DifferentCalendarError> {
+        if self.calendar() != other.calendar() {
+            return Err(DifferentCalendarError);
+        }
+        Ok(self.total_seconds() - other.total_seconds())
+    }
+
+    /// Gets how far apart this `DateTime` and `other` are, which
+    /// is the same whichever of the two is later. Use `seconds_since`
+    /// to tell which comes first.
+    ///
+    /// Both must share a calendar, otherwise they can't be
+    /// compared and a `DifferentCalendarError` is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use holding_kronos::{calendar::Calendar, datetime::DateTime};
+    /// #
+    /// let cal = Calendar::default();
+    /// let a = DateTime::from_seconds(0, &cal);
+    /// let b = DateTime::from_seconds(86400 + 3600, &cal);
+    /// assert_eq!(b.duration_since(&a).unwrap().to_string(), "1d1h");
+    /// assert_eq!(a.duration_since(&b).unwrap(), b.duration_since(&a).unwrap());
+    /// ```
+    pub fn duration_since(&self, other: &DateTime) -> Result<Duration, DifferentCalendarError> {
+        let seconds = self.seconds_since(other)?.unsigned_abs();
+        let year = u64::from(self.calendar().years_to_seconds(1)).max(1);
+        let years = (seconds / year).try_into().unwrap_or(u32::MAX);
+        let rest = (seconds % year)
+            .try_into()
+            .expect("Less than a year fits in the calendar's seconds");
+
+        Ok(Duration::from_seconds(rest, self.calendar()).with_years(years))
+    }
+
+    /// Gets the most specific era this `DateTime` falls in, if any.
+    pub fn era(&self) -> Option<&'a Era> {
+        self.date.era()
+    }
+
+    /// Gets the name of the period of the day this `DateTime`
+    /// falls in, using the calendar's custom names if it has any.
+    pub fn time_of_day_name(&self) -> &'a str {
+        self.calendar().period_name(self.time_of_day())
+    }
+
+    /// Formats the date and time according to a pattern. This
+    /// understands the same tokens as `Date::format`, as well as
+    ///
+    /// - `%H` is the hour
+    /// - `%I` is the hour on a 12 hour clock
+    /// - `%p` is AM or PM
+    /// - `%M` is the minute
+    /// - `%S` is the second
+    /// - `%P` is the period of the day, such as in the afternoon
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use holding_kronos::{calendar::Calendar, datetime::DateTime};
+    /// #
+    /// let cal = Calendar::default();
+    /// let time = DateTime::from_seconds(3600 * 13 + 60 * 5, &cal);
+    /// assert_eq!(time.format("%H:%M on %A"), "13:05 on Monday");
+    /// assert_eq!(time.format("%I:%M %p, %A the %o"), "01:05 PM, Monday the 1st");
+    /// ```
+    pub fn format(&self, pattern: &str) -> String {
+        self.date.format_with_time(pattern, Some(self.time))
+    }
+
+    /// Shows the date and time to people, using the display format
+    /// of the calendar if it has one. Unlike `Display`, the output
+    /// may not be understood by the parser.
+    pub fn to_display_string(&self) -> String {
+        match self.calendar().display_format() {
+            Some(pattern) => self.format(pattern),
+            None => self.to_string(),
+        }
+    }
+
+    /// Gets the seconds since the epoch modulo some other.
+    pub fn seconds_modulo(&self, other: Seconds) -> Seconds {
+        let seconds = self.total_seconds().rem_euclid(i64::from(other.0));
+        Seconds(seconds.try_into().expect("This is less than a u32"))
+    }
+
+    /// Restricts this `DateTime` to the range between `min` and `max`.
+    ///
+    /// All three must share a calendar, otherwise they can't be
+    /// compared and a `DifferentCalendarError` is returned. If `min`
+    /// is after `max`, `min` is returned.
+    pub fn clamp(
+        self,
+        min: DateTime<'a>,
+        max: DateTime<'a>,
+    ) -> Result<DateTime<'a>, DifferentCalendarError> {
+        self::max(self::min(self, max)?, min)
     }
 
     /// Progress time forward to a given target.
@@ -185,6 +398,39 @@ impl<'a> ModifyTime for DateTime<'a> {
             days,
         )
     }
+
+    fn sub_hours(&self, hours: u32) -> (Self::Item, u32) {
+        let (time, days) = self.time.sub_hours(hours);
+        (
+            DateTime {
+                date: self.date,
+                time,
+            },
+            days,
+        )
+    }
+
+    fn sub_minutes(&self, minutes: u32) -> (Self::Item, u32) {
+        let (time, days) = self.time.sub_minutes(minutes);
+        (
+            DateTime {
+                date: self.date,
+                time,
+            },
+            days,
+        )
+    }
+
+    fn sub_seconds(&self, seconds: u32) -> (Self::Item, u32) {
+        let (time, days) = self.time.sub_seconds(seconds);
+        (
+            DateTime {
+                date: self.date,
+                time,
+            },
+            days,
+        )
+    }
 }
 
 impl<'a> ModifyDate for DateTime<'a> {
@@ -197,9 +443,9 @@ impl<'a> ModifyDate for DateTime<'a> {
         }
     }
 
-    fn add_months(self, months: u32) -> Self::Item {
+    fn add_months_with(self, months: u32, policy: OverflowPolicy) -> Self::Item {
         DateTime {
-            date: self.date.add_months(months),
+            date: self.date.add_months_with(months, policy),
             time: self.time,
         }
     }
@@ -217,6 +463,106 @@ impl<'a> ModifyDate for DateTime<'a> {
             time: self.time,
         }
     }
+
+    fn sub_years(self, years: u32) -> Self::Item {
+        DateTime {
+            date: self.date.sub_years(years),
+            time: self.time,
+        }
+    }
+
+    fn sub_months(self, months: u32) -> Self::Item {
+        DateTime {
+            date: self.date.sub_months(months),
+            time: self.time,
+        }
+    }
+
+    fn sub_weeks(self, weeks: u32) -> Self::Item {
+        DateTime {
+            date: self.date.sub_weeks(weeks),
+            time: self.time,
+        }
+    }
+
+    fn sub_days(self, days: u32) -> Self::Item {
+        DateTime {
+            date: self.date.sub_days(days),
+            time: self.time,
+        }
+    }
+
+    fn checked_add_years(self, years: u32) -> Option<Self::Item> {
+        Some(DateTime {
+            date: self.date.checked_add_years(years)?,
+            time: self.time,
+        })
+    }
+
+    fn checked_add_months(self, months: u32) -> Option<Self::Item> {
+        Some(DateTime {
+            date: self.date.checked_add_months(months)?,
+            time: self.time,
+        })
+    }
+
+    fn checked_add_weeks(self, weeks: u32) -> Option<Self::Item> {
+        Some(DateTime {
+            date: self.date.checked_add_weeks(weeks)?,
+            time: self.time,
+        })
+    }
+
+    fn checked_add_days(self, days: u32) -> Option<Self::Item> {
+        Some(DateTime {
+            date: self.date.checked_add_days(days)?,
+            time: self.time,
+        })
+    }
+
+    fn checked_sub_years(self, years: u32) -> Option<Self::Item> {
+        Some(DateTime {
+            date: self.date.checked_sub_years(years)?,
+            time: self.time,
+        })
+    }
+
+    fn checked_sub_months(self, months: u32) -> Option<Self::Item> {
+        Some(DateTime {
+            date: self.date.checked_sub_months(months)?,
+            time: self.time,
+        })
+    }
+
+    fn checked_sub_weeks(self, weeks: u32) -> Option<Self::Item> {
+        Some(DateTime {
+            date: self.date.checked_sub_weeks(weeks)?,
+            time: self.time,
+        })
+    }
+
+    fn checked_sub_days(self, days: u32) -> Option<Self::Item> {
+        Some(DateTime {
+            date: self.date.checked_sub_days(days)?,
+            time: self.time,
+        })
+    }
+
+    fn subtract_years(self, years: u32) -> Self::Item {
+        self.stop_at_epoch(self.checked_sub_years(years))
+    }
+
+    fn subtract_months(self, months: u32) -> Self::Item {
+        self.stop_at_epoch(self.checked_sub_months(months))
+    }
+
+    fn subtract_weeks(self, weeks: u32) -> Self::Item {
+        self.stop_at_epoch(self.checked_sub_weeks(weeks))
+    }
+
+    fn subtract_days(self, days: u32) -> Self::Item {
+        self.stop_at_epoch(self.checked_sub_days(days))
+    }
 }
 
 // todo(arlyon) can this be impl automatically?
@@ -236,6 +582,48 @@ impl<'a> ModifyDateTime for DateTime<'a> {
         let (time, days) = ModifyTime::add_hours(self, hours);
         time.add_days(days)
     }
+
+    fn sub_seconds(&self, seconds: u32) -> Self::Item {
+        let (time, days) = ModifyTime::sub_seconds(self, seconds);
+        time.sub_days(days)
+    }
+
+    fn sub_minutes(&self, minutes: u32) -> Self::Item {
+        let (time, days) = ModifyTime::sub_minutes(self, minutes);
+        time.sub_days(days)
+    }
+
+    fn sub_hours(&self, hours: u32) -> Self::Item {
+        let (time, days) = ModifyTime::sub_hours(self, hours);
+        time.sub_days(days)
+    }
+}
+
+/// Adds a duration to a date and time, carrying the
+/// hours, minutes, and seconds over into the date.
+impl<'a> Add<Duration> for DateTime<'a> {
+    type Output = DateTime<'a>;
+
+    fn add(self, duration: Duration) -> Self::Output {
+        let date = self.date
+            + Duration {
+                hours: 0,
+                minutes: 0,
+                seconds: 0,
+                ..duration
+            };
+        let (time, days) = self.time
+            + Duration {
+                hours: duration.hours,
+                minutes: duration.minutes,
+                seconds: duration.seconds,
+                ..Duration::default()
+            };
+        DateTime {
+            date: date.add_days(days),
+            time,
+        }
+    }
 }
 
 impl ShowDate for DateTime<'_> {
@@ -267,9 +655,17 @@ impl ShowDate for DateTime<'_> {
         self.date.week_day_name()
     }
 
+    fn week_of_month(&self) -> u32 {
+        self.date.week_of_month()
+    }
+
     fn days(&self) -> u32 {
         self.date.days()
     }
+
+    fn is_leap_year(&self) -> bool {
+        self.date.is_leap_year()
+    }
 }
 
 impl ShowTime for DateTime<'_> {
@@ -285,8 +681,8 @@ impl ShowTime for DateTime<'_> {
         self.time.second()
     }
 
-    fn seconds(&self) -> u32 {
-        self.time.seconds()
+    fn seconds_u64(&self) -> u64 {
+        self.time.seconds_u64()
     }
 
     fn time_of_day(self) -> super::TimeOfDay {
@@ -300,6 +696,34 @@ impl Display for RawDateTime {
     }
 }
 
+/// Reads a datetime back from the form `Display` writes it in,
+/// YYYY-MM-DDThh:mm:ssZ. Unlike `Calendar::parse`, nothing is
+/// checked against a calendar and no relative forms are allowed.
+///
+/// # Examples
+///
+/// ```rust
+/// # use holding_kronos::datetime::RawDateTime;
+/// #
+/// let time: RawDateTime = "1011-02-03T04:05:06Z".parse().unwrap();
+/// assert_eq!((time.date.year, time.date.month, time.time.second), (1011, 1, 6));
+/// assert_eq!(time.to_string(), "1011-02-03T04:05:06Z");
+/// ```
+impl FromStr for RawDateTime {
+    type Err = ParseRawError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (date, time) = s
+            .strip_suffix('Z')
+            .and_then(|s| s.split_once('T'))
+            .ok_or_else(|| ParseRawError::Malformed(s.to_string(), "YYYY-MM-DDThh:mm:ssZ"))?;
+        Ok(Self {
+            date: date.parse()?,
+            time: time.parse()?,
+        })
+    }
+}
+
 impl Display for DateTime<'_> {
     fn fmt(&self, f: &mut Formatter) -> FmtResult {
         let x: RawDateTime = (*self).into();
@@ -316,6 +740,34 @@ impl From<DateTime<'_>> for RawDateTime {
     }
 }
 
+/// Gets the earlier of two `DateTime`s.
+///
+/// Both must share a calendar, otherwise they can't be
+/// compared and a `DifferentCalendarError` is returned.
+pub fn min<'a>(a: DateTime<'a>, b: DateTime<'a>) -> Result<DateTime<'a>, DifferentCalendarError> {
+    match a.partial_cmp(&b).ok_or(DifferentCalendarError)? {
+        Ordering::Greater => Ok(b),
+        _ => Ok(a),
+    }
+}
+
+/// Gets the later of two `DateTime`s.
+///
+/// Both must share a calendar, otherwise they can't be
+/// compared and a `DifferentCalendarError` is returned.
+pub fn max<'a>(a: DateTime<'a>, b: DateTime<'a>) -> Result<DateTime<'a>, DifferentCalendarError> {
+    match a.partial_cmp(&b).ok_or(DifferentCalendarError)? {
+        Ordering::Less => Ok(b),
+        _ => Ok(a),
+    }
+}
+
+/// The two objects belong to different calendars, and
+/// so can't be compared.
+#[derive(Error, Debug, Copy, Clone)]
+#[error("the datetimes belong to different calendars")]
+pub struct DifferentCalendarError;
+
 /// Possible invalid datetime states.
 #[derive(Error, Debug, Copy, Clone)]
 #[allow(missing_docs)]
@@ -328,17 +780,17 @@ pub enum InvalidDateTimeError {
 
 #[cfg(test)]
 mod test {
-    use std::{error::Error, result};
+    use std::{cmp::Ordering, error::Error, result};
 
     use proptest::prelude::*;
     use test_case::test_case;
 
     use crate::{
         calendar::traits::DayCycle,
-        calendar::{traits::YearCycle, Calendar},
+        calendar::{traits::YearCycle, Calendar, Day, Week, Year},
         datetime::{
-            traits::{ShowDate, ShowTime},
-            DateTime, RawDate, RawTime,
+            traits::{ModifyDate, ModifyDateTime, ShowDate, ShowTime},
+            DateTime, Duration, ParseRawError, RawDate, RawDateTime, RawTime, TimeOfDay,
         },
     };
 
@@ -348,6 +800,10 @@ mod test {
     #[test_case(3600, 1, 1, 1, 1, 0, 0)]
     #[test_case(60, 1, 1, 1, 0, 1, 0)]
     #[test_case(86400, 1, 1, 2, 0, 0, 0)]
+    #[test_case(-1, 0, 12, 31, 23, 59, 59 ; "one second before the epoch")]
+    #[test_case(-60, 0, 12, 31, 23, 59, 0 ; "one minute before the epoch")]
+    #[test_case(-86400, 0, 12, 31, 0, 0, 0 ; "one day before the epoch")]
+    #[test_case(-86401, 0, 12, 30, 23, 59, 59 ; "just over a day before the epoch")]
     pub fn datetime_from_seconds(
         seconds: i64,
         year: i64,
@@ -389,6 +845,61 @@ mod test {
             assert_eq!(date.minute(), minutes);
             assert_eq!(date.second(), seconds);
         }
+
+        #[test]
+        fn total_seconds_round_trips(seconds in 0i64..2_000_000_000) {
+            let cal = Calendar::default();
+            assert_eq!(DateTime::from_seconds(seconds, &cal).total_seconds(), seconds);
+        }
+
+        #[test]
+        fn raw_ordering_matches_seconds(a in 0i64..2_000_000_000, b in 0i64..2_000_000_000) {
+            let cal = Calendar::default();
+            let a = DateTime::from_seconds(a, &cal);
+            let b = DateTime::from_seconds(b, &cal);
+            let (raw_a, raw_b): (RawDateTime, RawDateTime) = (a.into(), b.into());
+
+            assert_eq!(raw_a.cmp(&raw_b), a.total_seconds().cmp(&b.total_seconds()));
+        }
+
+        #[test]
+        fn raw_display_round_trips(
+            year in any::<i64>(),
+            month in any::<u32>(),
+            day in any::<u32>(),
+            hour in any::<u32>(),
+            minute in any::<u32>(),
+            second in any::<u32>(),
+        ) {
+            let time = RawDateTime {
+                date: RawDate::from_index(year, month, day),
+                time: RawTime { hour, minute, second },
+            };
+            assert_eq!(time.to_string().parse::<RawDateTime>(), Ok(time));
+        }
+    }
+
+    #[test_case("0020-02-05T09:08:07Z", 20, 1, 4 ; "padded")]
+    #[test_case("12345-01-01T00:00:00Z", 12345, 0, 0 ; "long year")]
+    #[test_case("00-5-12-31T00:00:00Z", -5, 11, 30 ; "negative year")]
+    #[test_case("-1234-01-01T00:00:00Z", -1234, 0, 0 ; "long negative year")]
+    #[test_case("0000-01-01T00:00:00Z", 0, 0, 0 ; "year zero")]
+    pub fn parses_raw_datetime(text: &str, year: i64, month: u32, day: u32) -> Result {
+        let time: RawDateTime = text.parse()?;
+        assert_eq!(time.date, RawDate::from_index(year, month, day));
+        assert_eq!(time.to_string(), text);
+        Ok(())
+    }
+
+    #[test_case("1011-02-03 04:05:06", ParseRawError::Malformed("1011-02-03 04:05:06".to_string(), "YYYY-MM-DDThh:mm:ssZ") ; "no separator")]
+    #[test_case("1011-02T04:05:06Z", ParseRawError::Malformed("1011-02".to_string(), "YYYY-MM-DD") ; "missing day")]
+    #[test_case("1011-02-03T04:05Z", ParseRawError::Malformed("04:05".to_string(), "hh:mm:ss") ; "missing second")]
+    #[test_case("1011-xx-03T04:05:06Z", ParseRawError::InvalidComponent("month", "xx".to_string()) ; "not a number")]
+    #[test_case("1011-00-03T04:05:06Z", ParseRawError::OutOfBounds("month", "00".to_string()) ; "month zero")]
+    #[test_case("1011-02-4294967297T04:05:06Z", ParseRawError::OutOfBounds("day", "4294967297".to_string()) ; "day too large")]
+    #[test_case("1011-02-03T4294967296:05:06Z", ParseRawError::OutOfBounds("hour", "4294967296".to_string()) ; "hour too large")]
+    pub fn rejects_malformed_raw_datetime(text: &str, expected: ParseRawError) {
+        assert_eq!(text.parse::<RawDateTime>(), Err(expected));
     }
 
     #[test_case(0, "Monday", 1)]
@@ -405,6 +916,133 @@ mod test {
         Ok(())
     }
 
+    #[test_case(0, 2 ; "below")]
+    #[test_case(3, 3 ; "inside")]
+    #[test_case(9, 5 ; "above")]
+    pub fn clamps(day: i64, expected: i64) -> Result {
+        let cal = Calendar::default();
+        let min = DateTime::from_seconds(2 * 86400, &cal);
+        let max = DateTime::from_seconds(5 * 86400, &cal);
+        let date = DateTime::from_seconds(day * 86400, &cal).clamp(min, max)?;
+
+        assert_eq!(date, DateTime::from_seconds(expected * 86400, &cal));
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn uses_calendar_display_format() {
+        let mut cal = Calendar::default();
+        let time: RawDateTime = DateTime::from_seconds(86400 + 9 * 3600, &cal).into();
+        assert_eq!(
+            time.into_datetime(&cal).to_display_string(),
+            "0001-01-02T09:00:00Z"
+        );
+
+        cal.set_display_format(Some("%A, %d %B, Year %Y at %H:%M".to_string()));
+        assert_eq!(
+            time.into_datetime(&cal).to_display_string(),
+            "Tuesday, 2 January, Year 1 at 09:00"
+        );
+        assert_eq!(time.into_datetime(&cal).to_string(), "0001-01-02T09:00:00Z");
+    }
+
+    #[test_case(9 * 3600, "09:00 AM, in the morning" ; "morning")]
+    #[test_case(12 * 3600 + 30 * 60, "12:30 PM, in the afternoon" ; "noon")]
+    #[test_case(23 * 3600 + 59 * 60 + 7, "11:59 PM, at night" ; "late")]
+    #[test_case(0, "12:00 AM, late in the night" ; "midnight")]
+    pub fn formats_time_tokens(seconds: i64, expected: &str) {
+        let cal = Calendar::default();
+        let time = DateTime::from_seconds(seconds, &cal);
+        assert_eq!(time.format("%I:%M %p, %P"), expected);
+    }
+
+    #[test]
+    pub fn subtracting_stops_at_the_epoch() -> Result {
+        let cal = Calendar::default();
+        let epoch = DateTime::from_seconds(0, &cal);
+        let start = DateTime::from_date(2, 1, 10, &cal)?.add_hours(3);
+
+        assert_eq!(
+            start.subtract_years(1),
+            DateTime::from_date(1, 1, 10, &cal)?.add_hours(3)
+        );
+        assert_eq!(start.subtract_years(2), epoch);
+        assert_eq!(start.subtract_weeks(60), epoch);
+        assert_eq!(start.subtract_days(u32::MAX), epoch);
+        assert_eq!(
+            start.subtract_duration(Duration::default().with_years(1).with_days(9).with_hours(4)),
+            epoch
+        );
+
+        let before = DateTime::from_date(-3, 1, 1, &cal)?;
+        assert_eq!(before.subtract_days(1), before);
+        Ok(())
+    }
+
+    #[test]
+    pub fn custom_period_names() {
+        let mut cal = Calendar::default();
+        cal.set_period_name(TimeOfDay::LateNight, "the witching hour".to_string());
+
+        let midnight = DateTime::from_seconds(0, &cal);
+        let noon = DateTime::from_seconds(12 * 3600, &cal);
+
+        assert_eq!(midnight.time_of_day_name(), "the witching hour");
+        assert_eq!(noon.time_of_day_name(), "in the afternoon");
+    }
+
+    #[test_case(200_000, 200_000, Ordering::Equal)]
+    #[test_case(100_000, 200_000, Ordering::Less)]
+    #[test_case(300_000, 200_000, Ordering::Greater)]
+    pub fn compares_across_calendars(a: i64, b: i64, expected: Ordering) {
+        let cal = Calendar::default();
+        let other = Calendar::new(Year::default(), Week::default(), Day::new(100, 50, 20));
+        let a = DateTime::from_seconds(a, &cal);
+        let b = DateTime::from_seconds(b, &other);
+
+        assert_ne!(a.hour(), b.hour());
+        assert_eq!(a.partial_cmp(&b), None);
+        assert_eq!(a.cmp_absolute(&b), expected);
+    }
+
+    #[test]
+    pub fn clamp_different_calendars() {
+        let cal = Calendar::default();
+        let other = Calendar::new(Year::default(), Week::default(), Day::new(10, 10, 10));
+        let min = DateTime::from_seconds(0, &cal);
+        let max = DateTime::from_seconds(0, &other);
+
+        assert!(DateTime::from_seconds(0, &cal).clamp(min, max).is_err());
+    }
+
+    #[test_case(0, 0, 0, "0s" ; "same instant")]
+    #[test_case(86400 + 3600, 0, 90000, "1d1h" ; "later")]
+    #[test_case(0, 86400 * 365 + 61, -31_536_061, "1y1m1s" ; "earlier")]
+    #[test_case(-86400 * 400, 86400 * 400, -69_120_000, "2y2mo11d" ; "across the epoch")]
+    pub fn measures_time_between(a: i64, b: i64, seconds: i64, duration: &str) -> Result {
+        let cal = Calendar::default();
+        let a = DateTime::from_seconds(a, &cal);
+        let b = DateTime::from_seconds(b, &cal);
+
+        assert_eq!(a.seconds_since(&b)?, seconds);
+        assert_eq!(b.seconds_since(&a)?, -seconds);
+        assert_eq!(a.duration_since(&b)?.to_string(), duration);
+        assert_eq!(a.duration_since(&b)?, b.duration_since(&a)?);
+        Ok(())
+    }
+
+    #[test]
+    pub fn no_duration_between_calendars() {
+        let cal = Calendar::default();
+        let other = Calendar::new(Year::default(), Week::default(), Day::new(10, 10, 10));
+        let a = DateTime::from_seconds(0, &cal);
+        let b = DateTime::from_seconds(0, &other);
+
+        assert!(a.seconds_since(&b).is_err());
+        assert!(a.duration_since(&b).is_err());
+    }
+
     #[test_case(86400 * 1,  1, 2, "January")]
     #[test_case(86400 * 40, 2, 10, "February")]
     #[test_case(86400 * 95, 4, 6, "April")]
@@ -421,4 +1059,73 @@ mod test {
 
         Ok(())
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    pub fn serializes_as_total_seconds() {
+        use crate::datetime::EpochSeconds;
+
+        let cal = Calendar::default();
+        let raw = RawDateTime {
+            date: RawDate::new(1101, 2, 12).expect("valid date"),
+            time: RawTime {
+                hour: 13,
+                minute: 4,
+                second: 59,
+            },
+        };
+        let seconds = EpochSeconds::from_raw(raw, &cal);
+
+        let yaml = serde_yaml::to_string(&seconds).expect("can serialize");
+        assert_eq!(yaml.trim_start_matches("---").trim(), seconds.0.to_string());
+
+        let back: EpochSeconds = serde_yaml::from_str(&yaml).expect("can deserialize");
+        assert_eq!(RawDateTime::from(back.into_datetime(&cal)), raw);
+    }
+
+    #[test]
+    pub fn adds_duration() -> Result {
+        let cal = Calendar::default();
+        let start = DateTime::from_seconds(3600 * 20, &cal);
+        let later = start
+            + Duration {
+                days: 1,
+                hours: 5,
+                minutes: 30,
+                ..Duration::default()
+            };
+
+        assert_eq!(later.date.date, RawDate::new(1, 1, 3)?);
+        assert_eq!((later.hour(), later.minute()), (1, 30));
+        assert_eq!(
+            later.total_seconds() - start.total_seconds(),
+            86400 + 3600 * 5 + 60 * 30
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn subtracts_duration() -> Result {
+        let cal = Calendar::default();
+        let start = DateTime::from_date(1101, 3, 1, &cal)?;
+        let duration = Duration::default()
+            .with_years(1)
+            .with_months(1)
+            .with_days(1)
+            .with_hours(2);
+
+        let earlier = start.sub_duration(duration);
+        assert_eq!(earlier.date.date, RawDate::new(1100, 1, 30)?);
+        assert_eq!(earlier.hour(), 22);
+        assert_eq!(
+            earlier
+                .add_duration(Duration::default().with_hours(2))
+                .date
+                .date,
+            RawDate::new(1100, 1, 31)?
+        );
+
+        Ok(())
+    }
 }
@@ -0,0 +1,218 @@
+use std::{
+    convert::TryInto,
+    fmt::{Display, Formatter, Result as FmtResult},
+};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::calendar::{
+    traits::{ConvertDate, ConvertTime, DayCycle, WeekCycle, YearCycle},
+    Calendar,
+};
+
+/// A span of time, broken down into calendar units.
+///
+/// Since months are irregular, a duration only
+/// has a fixed length relative to some calendar.
+///
+/// Durations can be built up a unit at a time:
+///
+/// ```
+/// # use holding_kronos::datetime::Duration;
+/// let duration = Duration::default().with_months(2).with_hours(3);
+/// assert_eq!(duration.to_string(), "2mo3h");
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct Duration {
+    /// The years component.
+    pub years: u32,
+    /// The months component.
+    pub months: u32,
+    /// The weeks component.
+    pub weeks: u32,
+    /// The days component.
+    pub days: u32,
+    /// The hours component.
+    pub hours: u32,
+    /// The minutes component.
+    pub minutes: u32,
+    /// The seconds component.
+    pub seconds: u32,
+}
+
+impl Duration {
+    /// Breaks a number of seconds down into the units of a
+    /// calendar. Months are consumed in calendar order from
+    /// the start of the year, and weeks are left as days.
+    pub fn from_seconds(seconds: u32, calendar: &Calendar) -> Self {
+        let (years, months, days, seconds) = calendar.seconds_to_years_months_days(seconds);
+        let (hours, minutes, seconds) = calendar.seconds_to_hours_minutes_seconds(seconds);
+
+        Self {
+            years,
+            months,
+            weeks: 0,
+            days,
+            hours,
+            minutes,
+            seconds,
+        }
+    }
+
+    /// Breaks a number of seconds down like `from_seconds`, counting
+    /// whole years first so that longer spans fit. Returns None if
+    /// there are too many years to count.
+    pub fn checked_from_seconds(seconds: u64, calendar: &Calendar) -> Option<Self> {
+        let year = u64::from(calendar.years_to_seconds(1)).max(1);
+        let years = (seconds / year).try_into().ok()?;
+        let rest = (seconds % year)
+            .try_into()
+            .expect("Less than a year fits in the calendar's seconds");
+        Some(Self::from_seconds(rest, calendar).with_years(years))
+    }
+
+    /// Sets the years component.
+    pub fn with_years(self, years: u32) -> Self {
+        Self { years, ..self }
+    }
+
+    /// Sets the months component.
+    pub fn with_months(self, months: u32) -> Self {
+        Self { months, ..self }
+    }
+
+    /// Sets the weeks component.
+    pub fn with_weeks(self, weeks: u32) -> Self {
+        Self { weeks, ..self }
+    }
+
+    /// Sets the days component.
+    pub fn with_days(self, days: u32) -> Self {
+        Self { days, ..self }
+    }
+
+    /// Sets the hours component.
+    pub fn with_hours(self, hours: u32) -> Self {
+        Self { hours, ..self }
+    }
+
+    /// Sets the minutes component.
+    pub fn with_minutes(self, minutes: u32) -> Self {
+        Self { minutes, ..self }
+    }
+
+    /// Sets the seconds component.
+    pub fn with_seconds(self, seconds: u32) -> Self {
+        Self { seconds, ..self }
+    }
+
+    /// Gets the length of the duration in seconds. Years and months
+    /// have no fixed length, so a year counts as the days in a year
+    /// of the calendar and a month as the average month.
+    pub fn to_seconds(&self, calendar: &Calendar) -> u64 {
+        let day = calendar.seconds_in_day_u64();
+        let year = u64::from(calendar.days_in_year()) * day;
+        let month = year / u64::from(calendar.months_in_year().max(1));
+
+        u64::from(self.years) * year
+            + u64::from(self.months) * month
+            + u64::from(self.weeks) * u64::from(calendar.days_in_week()) * day
+            + u64::from(self.days) * day
+            + self.clock_seconds(calendar)
+    }
+
+    /// Gets the length of the hours, minutes, and
+    /// seconds of the duration in seconds.
+    pub(crate) fn clock_seconds(&self, calendar: &Calendar) -> u64 {
+        u64::from(self.hours) * calendar.seconds_in_hour_u64()
+            + u64::from(self.minutes) * u64::from(calendar.seconds_in_minute())
+            + u64::from(self.seconds)
+    }
+
+    /// Splits the hours, minutes, and seconds of the duration
+    /// into whole days and the seconds left over.
+    pub(crate) fn clock_days(&self, calendar: &Calendar) -> (u32, u32) {
+        let day = calendar.seconds_in_day_u64();
+        let seconds = self.clock_seconds(calendar);
+        let days = (seconds / day).try_into().unwrap_or(u32::MAX);
+        let rest = (seconds % day).try_into().expect("This is less than a day");
+        (days, rest)
+    }
+}
+
+/// Prints the duration in the relative format
+/// understood by the parser, such as 2y4mo2h.
+impl Display for Duration {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        let units = [
+            (self.years, "y"),
+            (self.months, "mo"),
+            (self.weeks, "w"),
+            (self.days, "d"),
+            (self.hours, "h"),
+            (self.minutes, "m"),
+            (self.seconds, "s"),
+        ];
+
+        if units.iter().all(|(value, _)| *value == 0) {
+            return write!(f, "0s");
+        }
+
+        for (value, suffix) in units.iter().filter(|(value, _)| *value != 0) {
+            write!(f, "{}{}", value, suffix)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use test_case::test_case;
+
+    use crate::calendar::Calendar;
+
+    use super::Duration;
+
+    #[test_case(0, "0s")]
+    #[test_case(90, "1m30s")]
+    #[test_case(86400 + 7200, "1d2h")]
+    #[test_case(86400 * 40, "1mo9d")]
+    #[test_case(86400 * 365 + 1, "1y1s")]
+    pub fn displays_seconds(seconds: u32, expected: &str) {
+        let cal = Calendar::default();
+        assert_eq!(Duration::from_seconds(seconds, &cal).to_string(), expected);
+    }
+
+    #[test]
+    pub fn counts_years_beyond_u32_seconds() {
+        let cal = Calendar::default();
+        let year = 86400 * 365;
+        let duration = Duration::checked_from_seconds(year * 200 + 90, &cal).expect("years fit");
+        assert_eq!(duration.to_string(), "200y1m30s");
+        assert_eq!(duration.to_seconds(&cal), year * 200 + 90);
+        assert_eq!(Duration::checked_from_seconds(u64::MAX, &cal), None);
+    }
+
+    #[test]
+    pub fn builds_up_units() {
+        let duration = Duration::default()
+            .with_years(2)
+            .with_months(4)
+            .with_hours(2)
+            .with_minutes(5)
+            .with_seconds(20);
+
+        assert_eq!(duration.to_string(), "2y4mo2h5m20s");
+        assert_eq!(duration.with_years(0).to_string(), "4mo2h5m20s");
+        assert_eq!(
+            Calendar::default()
+                .parse_duration(&duration.to_string())
+                .expect("valid duration"),
+            duration
+        );
+    }
+}
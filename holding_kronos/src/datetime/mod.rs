@@ -1,19 +1,29 @@
 //! calendar: General-purpose flexible dates and times in arbitrary calendars.
 //!
-//! For simplicity, it does not support leap-anything.
+//! Leap days follow the `LeapRule` of the calendar, if it has one.
 
-use std::fmt::Debug;
+use std::{
+    fmt::Debug,
+    num::{IntErrorKind, ParseIntError},
+    str::FromStr,
+};
 
 use thiserror::Error;
 
 mod date;
 mod datetime;
+mod duration;
+mod schedule;
 mod time;
 mod time_of_day;
 pub mod traits;
 
 pub use date::{Date, InvalidDateError, RawDate};
-pub use datetime::{DateTime, InvalidDateTimeError, RawDateTime};
+pub use datetime::{
+    max, min, DateTime, DifferentCalendarError, EpochSeconds, InvalidDateTimeError, RawDateTime,
+};
+pub use duration::Duration;
+pub use schedule::Schedule;
 pub use time::{InvalidTimeError, RawTime, Time, TimeFormat};
 pub use time_of_day::TimeOfDay;
 
@@ -41,3 +51,32 @@ pub enum InvalidWaitError {
     #[error("the wait target {0} is before current {1}")]
     BackwardsWait(RawTime, RawTime),
 }
+
+/// Possible errors when reading a `RawDateTime`, `RawDate`,
+/// or `RawTime` back from the string it displays as.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[allow(missing_docs)]
+pub enum ParseRawError {
+    #[error("{0:?} is not of the form {1}")]
+    Malformed(String, &'static str),
+
+    #[error("the {0} {1:?} is not a number")]
+    InvalidComponent(&'static str, String),
+
+    #[error("the {0} {1:?} is out of bounds")]
+    OutOfBounds(&'static str, String),
+}
+
+/// Parses one numeric component of a raw date or time,
+/// naming it in the error if it can't be read.
+fn parse_component<T>(name: &'static str, text: &str) -> Result<T, ParseRawError>
+where
+    T: FromStr<Err = ParseIntError>,
+{
+    text.parse().map_err(|e: ParseIntError| match e.kind() {
+        IntErrorKind::PosOverflow | IntErrorKind::NegOverflow => {
+            ParseRawError::OutOfBounds(name, text.to_string())
+        }
+        _ => ParseRawError::InvalidComponent(name, text.to_string()),
+    })
+}
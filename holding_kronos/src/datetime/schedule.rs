@@ -0,0 +1,129 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    calendar::Calendar,
+    datetime::{
+        traits::{ModifyDate, ModifyDateTime},
+        DateTime, Duration, RawDateTime,
+    },
+};
+
+/// A recurring event, such as a daily patrol or a yearly festival.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Schedule {
+    /// The first occurrence.
+    pub start: RawDateTime,
+    /// The time between occurrences.
+    pub every: Duration,
+}
+
+impl Schedule {
+    /// Creates a new `Schedule`.
+    pub fn new(start: RawDateTime, every: Duration) -> Self {
+        Self { start, every }
+    }
+
+    /// Gets the nth occurrence, where the start is the 0th.
+    ///
+    /// Each occurrence is counted from the start rather than
+    /// the previous one, so that they never drift.
+    pub fn occurrence<'a>(&self, calendar: &'a Calendar, n: u32) -> DateTime<'a> {
+        let every = self.every;
+        self.start
+            .into_datetime(calendar)
+            .add_years(every.years.saturating_mul(n))
+            .add_months(every.months.saturating_mul(n))
+            .add_weeks(every.weeks.saturating_mul(n))
+            .add_days(every.days.saturating_mul(n))
+            .add_hours(every.hours.saturating_mul(n))
+            .add_minutes(every.minutes.saturating_mul(n))
+            .add_seconds(every.seconds.saturating_mul(n))
+    }
+
+    /// Gets the occurrences after `from`, up to and including `to`.
+    ///
+    /// Since `from` is excluded, the occurrences in consecutive
+    /// ranges never overlap.
+    pub fn occurrences_between<'a>(
+        &self,
+        calendar: &'a Calendar,
+        from: DateTime,
+        to: DateTime,
+    ) -> Vec<DateTime<'a>> {
+        let (from, to) = (from.total_seconds(), to.total_seconds());
+        let start = self.start.into_datetime(calendar).total_seconds();
+        let length = self.every.to_seconds(calendar);
+
+        if length == 0 {
+            return if start > from && start <= to {
+                vec![self.start.into_datetime(calendar)]
+            } else {
+                vec![]
+            };
+        }
+
+        // skip ahead to just before the range. months are counted at
+        // their average length, which is never out by a whole interval
+        let skip = (from - start).max(0) as u64 / length;
+        let first = skip.saturating_sub(1).min(u64::from(u32::MAX)) as u32;
+
+        (first..)
+            .map(|n| self.occurrence(calendar, n))
+            .take_while(|o| o.total_seconds() <= to)
+            .filter(|o| o.total_seconds() > from)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use test_case::test_case;
+
+    use crate::{
+        calendar::Calendar,
+        datetime::{traits::ShowDate, DateTime, Duration},
+    };
+
+    use super::Schedule;
+
+    fn weekly() -> Duration {
+        Duration {
+            weeks: 1,
+            ..Duration::default()
+        }
+    }
+
+    #[test_case(0, 21, 3 ; "three weeks")]
+    #[test_case(0, 20, 2 ; "just short of three weeks")]
+    #[test_case(7, 14, 1 ; "excludes the start of the range")]
+    #[test_case(700, 707, 1 ; "far from the start")]
+    pub fn counts_occurrences(from: i64, to: i64, expected: usize) {
+        let cal = Calendar::default();
+        let day = |days: i64| DateTime::from_seconds(days * 86400, &cal);
+        let schedule = Schedule::new(day(0).into(), weekly());
+
+        let occurrences = schedule.occurrences_between(&cal, day(from), day(to));
+        assert_eq!(occurrences.len(), expected);
+        assert!(occurrences
+            .iter()
+            .all(|o| o.total_seconds() % (7 * 86400) == 0));
+    }
+
+    #[test]
+    pub fn monthly_counts_from_start() {
+        let cal = Calendar::default();
+        let start = cal.parse("1-01-15", None).expect("valid date");
+        let schedule = Schedule::new(
+            start.into(),
+            Duration {
+                months: 1,
+                ..Duration::default()
+            },
+        );
+
+        let later = schedule.occurrence(&cal, 14);
+        assert_eq!((later.year(), later.month(), later.day()), (2, 3, 15));
+    }
+}
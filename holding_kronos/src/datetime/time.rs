@@ -1,17 +1,15 @@
-use std::fmt::Display;
+use std::{convert::TryInto, fmt::Display, ops::Add, str::FromStr};
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 use crate::{
-    calendar::{
-        traits::{ConvertTime, DayCycle},
-        Calendar,
-    },
+    calendar::{traits::DayCycle, Calendar},
     datetime::{
+        parse_component,
         traits::{ModifyTime, ShowTime},
-        TimeOfDay,
+        Duration, ParseRawError, TimeOfDay,
     },
     util::div_rem,
 };
@@ -34,25 +32,42 @@ impl RawTime {
         Time {
             time: self,
             calendar: cal,
+            display_format: None,
         }
     }
 }
 
 /// Represents times.
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[derive(Debug, Clone, Copy)]
 pub struct Time<'a> {
     /// The raw time object.
     pub time: RawTime,
     /// The calendar this `Time` uses.
     pub calendar: &'a Calendar,
+    /// The format the time was written in, if it was given on a 12
+    /// hour clock. This only affects how the time is displayed, and
+    /// is ignored when comparing times.
+    pub display_format: Option<TimeFormat>,
+}
+
+impl PartialEq for Time<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.time == other.time && self.calendar == other.calendar
+    }
 }
 
+impl Eq for Time<'_> {}
+
 impl<'a> ModifyTime for Time<'a> {
     type Item = Time<'a>;
 
     fn add_seconds(&self, seconds: u32) -> (Self::Item, u32) {
-        let (days, seconds) = div_rem(self.seconds() + seconds, self.calendar.days_to_seconds(1));
-        (Time::from_seconds(seconds, self.calendar), days)
+        let total = self.seconds_u64() + u64::from(seconds);
+        let (days, seconds) = div_rem(total, self.calendar.seconds_in_day_u64());
+        (
+            Time::from_seconds_u64(seconds, self.calendar),
+            days.try_into().unwrap_or(u32::MAX),
+        )
     }
 
     fn add_minutes(&self, minutes: u32) -> (Self::Item, u32) {
@@ -62,6 +77,42 @@ impl<'a> ModifyTime for Time<'a> {
     fn add_hours(&self, hours: u32) -> (Self::Item, u32) {
         self.add_minutes(hours * self.calendar.minutes_in_hour())
     }
+
+    fn sub_seconds(&self, seconds: u32) -> (Self::Item, u32) {
+        let day = i128::from(self.calendar.seconds_in_day_u64());
+        let total = i128::from(self.seconds_u64()) - i128::from(seconds);
+        let days = (-total.div_euclid(day))
+            .try_into()
+            .expect("At most a u32 of days are borrowed");
+        let seconds = total
+            .rem_euclid(day)
+            .try_into()
+            .expect("This is less than a day");
+        (Time::from_seconds_u64(seconds, self.calendar), days)
+    }
+
+    fn sub_minutes(&self, minutes: u32) -> (Self::Item, u32) {
+        self.sub_seconds(minutes * self.calendar.seconds_in_minute())
+    }
+
+    fn sub_hours(&self, hours: u32) -> (Self::Item, u32) {
+        self.sub_minutes(hours * self.calendar.minutes_in_hour())
+    }
+}
+
+/// Adds a duration to a time, wrapping around within the day.
+/// Like `ModifyTime`, this also gives the number of days carried,
+/// which includes the years, months, weeks, and days of the
+/// duration at the lengths used by `Duration::to_seconds`.
+impl<'a> Add<Duration> for Time<'a> {
+    type Output = (Time<'a>, u32);
+
+    fn add(self, duration: Duration) -> Self::Output {
+        let day = self.calendar.seconds_in_day_u64();
+        let total = self.seconds_u64() + duration.to_seconds(self.calendar);
+        let days = (total / day).try_into().unwrap_or(u32::MAX);
+        (Time::from_seconds_u64(total % day, self.calendar), days)
+    }
 }
 
 impl<'a> ShowTime for Time<'a> {
@@ -77,16 +128,22 @@ impl<'a> ShowTime for Time<'a> {
         self.time.second
     }
 
-    fn seconds(&self) -> u32 {
-        self.calendar.hours_to_seconds(self.time.hour)
-            + self.calendar.minutes_to_seconds(self.time.minute)
-            + self.time.second
+    fn seconds_u64(&self) -> u64 {
+        let minute = u64::from(self.calendar.seconds_in_minute());
+        let hour = minute * u64::from(self.calendar.minutes_in_hour());
+        u64::from(self.time.hour) * hour
+            + u64::from(self.time.minute) * minute
+            + u64::from(self.time.second)
     }
 
     /// Get the `TimeOfDay`
+    ///
+    /// # Panics
+    ///
+    /// If the calendar has no hours in a day.
     fn time_of_day(self) -> TimeOfDay {
-        TimeOfDay::from_time(self.time.hour, self.calendar.hours_in_day())
-            .expect("If this fails it is programmer error")
+        TimeOfDay::from_time_clamped(self.time.hour, self.calendar.hours_in_day())
+            .expect("A calendar day has at least one hour")
     }
 }
 
@@ -110,9 +167,32 @@ impl Display for RawTime {
     }
 }
 
+/// Reads a time back from the form `Display` writes it in,
+/// hh:mm:ss. There is no calendar, so any numbers are allowed.
+impl FromStr for RawTime {
+    type Err = ParseRawError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split(':');
+        match (parts.next(), parts.next(), parts.next(), parts.next()) {
+            (Some(hour), Some(minute), Some(second), None) => Ok(Self {
+                hour: parse_component("hour", hour)?,
+                minute: parse_component("minute", minute)?,
+                second: parse_component("second", second)?,
+            }),
+            _ => Err(ParseRawError::Malformed(s.to_string(), "hh:mm:ss")),
+        }
+    }
+}
+
+/// Prints the time on a 12 hour clock if that is how it
+/// was written, and on a 24 hour clock otherwise.
 impl Display for Time<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        self.time.fmt(f)
+        match self.display_format {
+            Some(TimeFormat::AM) | Some(TimeFormat::PM) => write!(f, "{}", self.format_12h()),
+            _ => self.time.fmt(f),
+        }
     }
 }
 
@@ -184,10 +264,68 @@ impl<'a> Time<'a> {
                     second,
                 },
                 calendar,
+                display_format: match format {
+                    TimeFormat::Exact => None,
+                    format => Some(format),
+                },
             })
         }
     }
 
+    /// Formats the time on a 12 hour clock, such as 8:00 PM. In
+    /// calendars with longer or shorter days, the clock goes round
+    /// once for each half of the day.
+    pub fn format_12h(&self) -> String {
+        let (hour, period) = self.clock_12h();
+        format!("{}:{:0>2} {}", hour, self.time.minute, period)
+    }
+
+    /// Gets the hour on a 12 hour clock, along with AM or PM.
+    pub(crate) fn clock_12h(&self) -> (u32, &'static str) {
+        let half = (self.calendar.hours_in_day() / 2).max(1);
+        let (hour, period) = if self.time.hour < half {
+            (self.time.hour, "AM")
+        } else {
+            (self.time.hour - half, "PM")
+        };
+
+        (if hour == 0 { half } else { hour }, period)
+    }
+
+    /// Create a new `Time` from an hour-minute-second tuple, wrapping
+    /// any components that are out of bounds into the next unit.
+    ///
+    /// Unlike `from_hms`, this never fails. It returns the time along
+    /// with the number of whole days that were carried over.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use holding_kronos::datetime::{traits::ShowTime, Time};
+    /// # use holding_kronos::calendar::Calendar;
+    /// let cal = Calendar::default();
+    /// let (time, days) = Time::from_hms_wrapping(25, 0, 0, &cal);
+    ///
+    /// assert_eq!(time.hour(), 1);
+    /// assert_eq!(days, 1);
+    /// ```
+    pub fn from_hms_wrapping(
+        hour: u32,
+        minute: u32,
+        second: u32,
+        calendar: &'a Calendar,
+    ) -> (Self, u32) {
+        let seconds = u64::from(hour) * calendar.seconds_in_hour_u64()
+            + u64::from(minute) * u64::from(calendar.seconds_in_minute())
+            + u64::from(second);
+        let (days, seconds) = div_rem(seconds, calendar.seconds_in_day_u64());
+
+        (
+            Self::from_seconds_u64(seconds, calendar),
+            days.try_into().unwrap_or(u32::MAX),
+        )
+    }
+
     /// Create a new `Time` representing the number of seconds.
     ///
     /// Note that this will perform a modulo operator if the seconds
@@ -223,13 +361,26 @@ impl<'a> Time<'a> {
     /// # }
     /// ```
     pub fn from_seconds(seconds: u32, calendar: &'a Calendar) -> Self {
-        let (hour, seconds) = div_rem(seconds, calendar.seconds_in_hour());
-        let (minute, second) = div_rem(seconds, calendar.seconds_in_minute());
+        Self::from_seconds_u64(u64::from(seconds), calendar)
+    }
+
+    /// Create a new `Time` representing the number of seconds, like
+    /// `from_seconds`, but for calendars with days longer than
+    /// `u32::MAX` seconds.
+    pub fn from_seconds_u64(seconds: u64, calendar: &'a Calendar) -> Self {
+        let (hour, seconds) = div_rem(seconds, calendar.seconds_in_hour_u64());
+        let (minute, second) = div_rem(seconds, u64::from(calendar.seconds_in_minute()));
+        let hour = hour % u64::from(calendar.hours_in_day());
 
         Self::from_hms(
-            hour % calendar.hours_in_day(),
-            minute,
-            second,
+            hour.try_into()
+                .expect("This is less than the hours in a day"),
+            minute
+                .try_into()
+                .expect("This is less than the minutes in an hour"),
+            second
+                .try_into()
+                .expect("This is less than the seconds in a minute"),
             calendar,
             TimeFormat::Exact,
         )
@@ -244,7 +395,7 @@ impl From<Time<'_>> for RawTime {
 }
 
 /// Allow to specify a time relative to another.
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub enum TimeFormat {
     /// Military Time
     Exact,
@@ -276,7 +427,13 @@ pub enum InvalidTimeError {
 mod test {
     use test_case::test_case;
 
-    use crate::{calendar::Calendar, datetime::traits::ModifyTime};
+    use crate::{
+        calendar::{Calendar, Day, Week, Year},
+        datetime::{
+            traits::{ModifyTime, ShowTime},
+            DateTime, Duration,
+        },
+    };
 
     use super::{Time, TimeFormat};
 
@@ -293,4 +450,112 @@ mod test {
         );
         assert_eq!(days_expected, days);
     }
+
+    #[test]
+    pub fn large_units_do_not_overflow() {
+        let cal = Calendar::new(
+            Year::default(),
+            Week::default(),
+            Day::new(100_000, 100_000, 10),
+        );
+        let last = Time::from_hms(9, 99_999, 99_999, &cal, TimeFormat::Exact).expect("valid time");
+
+        let hms = |time: Time| (time.hour(), time.minute(), time.second());
+
+        assert_eq!(last.seconds_u64(), 100_000u64.pow(2) * 10 - 1);
+        assert_eq!(
+            Time::from_hms(0, 1, 1, &cal, TimeFormat::Exact)
+                .expect("valid time")
+                .seconds(),
+            100_001
+        );
+        assert_eq!(
+            hms(Time::from_seconds_u64(last.seconds_u64(), &cal)),
+            hms(last)
+        );
+
+        let (time, days) = Time::from_hms_wrapping(10, 0, 1, &cal);
+        assert_eq!((hms(time), days), ((0, 0, 1), 1));
+
+        let (time, days) = last
+            + Duration {
+                seconds: 2,
+                ..Duration::default()
+            };
+        assert_eq!((hms(time), days), ((0, 0, 1), 1));
+
+        let (time, days) = Time::from_seconds(0, &cal).sub_seconds(1);
+        assert_eq!((hms(time), days), (hms(last), 1));
+
+        let seconds = 100_000i64.pow(2) * 10 * 3 + 100_000i64.pow(2) * 5;
+        let datetime = DateTime::from_seconds(seconds, &cal);
+        assert_eq!(datetime.hour(), 5);
+        assert_eq!(datetime.total_seconds(), seconds);
+        assert_eq!(hms(DateTime::from_seconds(-1, &cal).time), hms(last));
+    }
+
+    #[test_case(0, "12:00 AM")]
+    #[test_case(9, "9:00 AM")]
+    #[test_case(12, "12:00 PM")]
+    #[test_case(23, "11:00 PM")]
+    pub fn formats_12h(hour: u32, expected: &str) {
+        let cal = Calendar::default();
+        let time = Time::from_hms(hour, 0, 0, &cal, TimeFormat::Exact).expect("valid time");
+        assert_eq!(time.format_12h(), expected);
+    }
+
+    #[test_case(25, 0, 0, (1, 0, 0), 1 ; "past midnight")]
+    #[test_case(23, 59, 60, (0, 0, 0), 1 ; "carries seconds")]
+    #[test_case(12, 90, 0, (13, 30, 0), 0 ; "carries minutes")]
+    #[test_case(48, 0, 0, (0, 0, 0), 2 ; "whole days")]
+    pub fn wraps_hms(hour: u32, minute: u32, second: u32, hms: (u32, u32, u32), carry: u32) {
+        let cal = Calendar::default();
+        let (time, days) = Time::from_hms_wrapping(hour, minute, second, &cal);
+
+        assert_eq!(
+            time.time,
+            Time::from_hms(hms.0, hms.1, hms.2, &cal, TimeFormat::Exact)
+                .expect("valid time")
+                .time
+        );
+        assert_eq!(days, carry);
+        assert!(Time::from_hms(hour, minute, second, &cal, TimeFormat::Exact).is_err());
+    }
+
+    #[test]
+    pub fn adds_duration() {
+        let cal = Calendar::default();
+        let time = Time::from_hms(6, 30, 0, &cal, TimeFormat::Exact).expect("valid time");
+        let (later, days) = time
+            + Duration {
+                hours: 25,
+                ..Duration::default()
+            };
+
+        assert_eq!(
+            later.time,
+            Time::from_hms(7, 30, 0, &cal, TimeFormat::Exact)
+                .expect("valid time")
+                .time
+        );
+        assert_eq!(days, 1);
+    }
+
+    #[test]
+    pub fn sub_borrows_days() {
+        let cal = Calendar::default();
+        let time = Time::from_hms(6, 30, 0, &cal, TimeFormat::Exact).expect("valid time");
+
+        let (earlier, days) = time.sub_minutes(30);
+        assert_eq!((earlier.hour(), earlier.minute(), days), (6, 0, 0));
+
+        let (earlier, days) = time.sub_hours(7);
+        assert_eq!((earlier.hour(), earlier.minute(), days), (23, 30, 1));
+
+        let (earlier, days) = time.sub_hours(55);
+        assert_eq!((earlier.hour(), earlier.minute(), days), (23, 30, 3));
+
+        let (earlier, days) = time.sub_seconds(0);
+        assert_eq!((earlier.time, days), (time.time, 0));
+    }
 }
@@ -1,14 +1,16 @@
 use std::convert::TryFrom;
 
 use num_enum::{IntoPrimitive, TryFromPrimitive};
-use strum::Display;
+use strum::{Display, IntoStaticStr};
 
 use crate::{
-    calendar::traits::DayCycle,
-    datetime::{traits::ShowTime, DateTime},
+    calendar::{traits::DayCycle, Calendar},
+    datetime::{traits::ShowTime, DateTime, InvalidTimeError},
 };
 
-#[derive(IntoPrimitive, TryFromPrimitive, Clone, Debug, Eq, PartialEq, Copy, Display)]
+#[derive(
+    IntoPrimitive, TryFromPrimitive, Clone, Debug, Eq, PartialEq, Copy, Display, IntoStaticStr,
+)]
 #[repr(u8)]
 #[allow(missing_docs)]
 pub enum TimeOfDay {
@@ -39,6 +41,33 @@ impl TimeOfDay {
         Self::try_from(index).ok()
     }
 
+    /// Lists the periods of the day in order, along with the hour
+    /// each starts at and the hour it ends before, using the same
+    /// binning as `TimeOfDay::from_time`. The ranges cover every
+    /// hour of the day exactly once. On days with fewer than eight
+    /// hours some periods have no hours, and these are skipped.
+    pub fn periods(calendar: &Calendar) -> impl Iterator<Item = (TimeOfDay, u32, u32)> {
+        let hours = u64::from(calendar.hours_in_day());
+        // the first hour h with h * 8 / hours >= i
+        let start = move |i: u64| {
+            u32::try_from((i * hours).div_ceil(8)).expect("This is at most the hours in a day")
+        };
+        (0..8u8).filter_map(move |i| {
+            let period = Self::try_from(i).expect("There are eight periods");
+            let (from, to) = (start(u64::from(i)), start(u64::from(i) + 1));
+            (from < to).then_some((period, from, to))
+        })
+    }
+
+    /// Gets the time of day for a given hour, treating hours past
+    /// the end of the day as the last hour.
+    ///
+    /// Returns None only if there are no hours in the day.
+    pub(crate) fn from_time_clamped(curr_hour: u32, max_hour: u32) -> Option<Self> {
+        let last = max_hour.checked_sub(1)?;
+        Self::from_time(curr_hour.min(last), max_hour)
+    }
+
     /// Checks if a given `TimeOfDay` is during day or night.
     pub fn is_day(self) -> bool {
         match self {
@@ -54,16 +83,25 @@ impl TimeOfDay {
     }
 }
 
-impl<'a> From<DateTime<'a>> for TimeOfDay {
-    fn from(dt: DateTime<'a>) -> Self {
-        Self::from_time(dt.hour(), dt.calendar().hours_in_day())
-            .expect("If this is not in range it is programmer error")
+/// Gets the time of day of a `DateTime`. This only fails
+/// if the calendar of the datetime has no hours in a day.
+impl<'a> TryFrom<DateTime<'a>> for TimeOfDay {
+    type Error = InvalidTimeError;
+
+    fn try_from(dt: DateTime<'a>) -> Result<Self, Self::Error> {
+        Self::from_time_clamped(dt.hour(), dt.calendar().hours_in_day())
+            .ok_or_else(|| InvalidTimeError::HourOutOfBounds(dt.hour()))
     }
 }
 
 #[cfg(test)]
 mod test {
-    use crate::datetime::time_of_day::TimeOfDay;
+    use std::convert::TryFrom;
+
+    use crate::{
+        calendar::{Calendar, Day, Week, Year},
+        datetime::{time_of_day::TimeOfDay, traits::ShowTime, DateTime},
+    };
     use test_case::test_case;
 
     #[test_case(0, 8, TimeOfDay::LateNight)]
@@ -77,4 +115,70 @@ mod test {
     pub fn get_time(curr_hour: u32, max_hour: u32, time_of_day: TimeOfDay) {
         assert_eq!(TimeOfDay::from_time(curr_hour, max_hour), Some(time_of_day));
     }
+
+    #[test]
+    pub fn periods_tile_the_day() {
+        let cal = Calendar::default();
+        let periods: Vec<_> = TimeOfDay::periods(&cal).collect();
+        assert_eq!(periods.len(), 8);
+        assert_eq!(periods[0], (TimeOfDay::LateNight, 0, 3));
+        assert_eq!(periods[7], (TimeOfDay::Night, 21, 24));
+
+        let mut next = 0;
+        for (period, start, end) in periods {
+            assert_eq!(start, next, "{:?} starts where the last one ended", period);
+            assert!(start < end);
+            for hour in start..end {
+                assert_eq!(TimeOfDay::from_time(hour, 24), Some(period));
+            }
+            next = end;
+        }
+        assert_eq!(next, 24);
+    }
+
+    #[test_case(5)]
+    #[test_case(13)]
+    #[test_case(1000)]
+    pub fn periods_agree_with_from_time(hours_in_day: u32) {
+        let cal = Calendar::new(
+            Year::default(),
+            Week::default(),
+            Day::new(60, 60, hours_in_day),
+        );
+        let hours: Vec<_> = TimeOfDay::periods(&cal)
+            .flat_map(|(period, start, end)| (start..end).map(move |hour| (hour, period)))
+            .collect();
+
+        assert_eq!(hours.len(), hours_in_day as usize);
+        for (expected, (hour, period)) in hours.into_iter().enumerate() {
+            assert_eq!(hour, expected as u32);
+            assert_eq!(TimeOfDay::from_time(hour, hours_in_day), Some(period));
+        }
+    }
+
+    #[test_case(8, 8, Some(TimeOfDay::Night) ; "last hour")]
+    #[test_case(9, 8, Some(TimeOfDay::Night) ; "past the last hour")]
+    #[test_case(3, 0, None ; "no hours")]
+    pub fn clamps_time(curr_hour: u32, max_hour: u32, expected: Option<TimeOfDay>) {
+        assert_eq!(TimeOfDay::from_time_clamped(curr_hour, max_hour), expected);
+    }
+
+    #[test_case(1)]
+    #[test_case(3)]
+    #[test_case(7)]
+    #[test_case(13)]
+    #[test_case(25)]
+    #[test_case(1000)]
+    pub fn try_from_succeeds(hours_in_day: u32) {
+        let cal = Calendar::new(
+            Year::default(),
+            Week::default(),
+            Day::new(60, 60, hours_in_day),
+        );
+        let day = i64::from(hours_in_day) * 3600;
+        for seconds in (0..day).step_by(1800) {
+            let dt = DateTime::from_seconds(seconds, &cal);
+            assert!(TimeOfDay::try_from(dt).is_ok(), "hour {}", dt.hour());
+        }
+    }
 }
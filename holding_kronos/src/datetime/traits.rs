@@ -1,5 +1,7 @@
 //! Useful traits for viewing / modifying dates and times.
 
+use std::convert::TryInto;
+
 use super::TimeOfDay;
 
 /// Allows modifying time-like objects.
@@ -24,6 +26,61 @@ pub trait ModifyTime {
     /// Returns the resulting object and a remainder
     /// for the number of days.
     fn add_seconds(&self, seconds: u32) -> (Self::Item, u32);
+
+    /// Subtract a number of hours from a time object.
+    ///
+    /// Returns the resulting object and the number
+    /// of days borrowed to go back past midnight.
+    fn sub_hours(&self, hours: u32) -> (Self::Item, u32);
+
+    /// Subtract a number of minutes from a time object.
+    ///
+    /// Returns the resulting object and the number
+    /// of days borrowed to go back past midnight.
+    fn sub_minutes(&self, minutes: u32) -> (Self::Item, u32);
+
+    /// Subtract a number of seconds from a time object.
+    ///
+    /// Returns the resulting object and the number
+    /// of days borrowed to go back past midnight.
+    fn sub_seconds(&self, seconds: u32) -> (Self::Item, u32);
+
+    /// Subtract a number of hours from a time object, as `sub_hours`.
+    ///
+    /// A time has no date, so there is no epoch to stop at. Take
+    /// the days borrowed off the date with `ModifyDate::subtract_days`.
+    fn subtract_hours(&self, hours: u32) -> (Self::Item, u32) {
+        self.sub_hours(hours)
+    }
+
+    /// Subtract a number of minutes from a time object, as `sub_minutes`.
+    ///
+    /// A time has no date, so there is no epoch to stop at. Take
+    /// the days borrowed off the date with `ModifyDate::subtract_days`.
+    fn subtract_minutes(&self, minutes: u32) -> (Self::Item, u32) {
+        self.sub_minutes(minutes)
+    }
+
+    /// Subtract a number of seconds from a time object, as `sub_seconds`.
+    ///
+    /// A time has no date, so there is no epoch to stop at. Take
+    /// the days borrowed off the date with `ModifyDate::subtract_days`.
+    fn subtract_seconds(&self, seconds: u32) -> (Self::Item, u32) {
+        self.sub_seconds(seconds)
+    }
+}
+
+/// What to do when adding months lands on a day
+/// past the end of the month, such as Feb 31.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum OverflowPolicy {
+    /// Stop at the last day of the month, so Jan 31 + 1 month
+    /// is Feb 28. This is what `add_months` does.
+    #[default]
+    Clamp,
+    /// Carry the extra days into the next month,
+    /// so Jan 31 + 1 month is Mar 3.
+    Rollover,
 }
 
 /// Allows modifying date-like objects.
@@ -34,14 +91,134 @@ pub trait ModifyDate {
     /// Add a number of years to a date object.
     fn add_years(self, years: u32) -> Self::Item;
 
-    /// Add a number of months to a date object.
-    fn add_months(self, months: u32) -> Self::Item;
+    /// Add a number of months to a date object. If the day
+    /// is past the end of the new month, it is clamped to the
+    /// last day, so that Jan 31 + 1 month is Feb 28.
+    fn add_months(self, months: u32) -> Self::Item
+    where
+        Self: Sized,
+    {
+        self.add_months_with(months, OverflowPolicy::Clamp)
+    }
+
+    /// Add a number of months to a date object, deciding what
+    /// happens to a day past the end of the new month.
+    fn add_months_with(self, months: u32, policy: OverflowPolicy) -> Self::Item;
 
     /// Add a number of weeks to a date object.
     fn add_weeks(self, weeks: u32) -> Self::Item;
 
     /// Add a number of days to a date object.
     fn add_days(self, days: u32) -> Self::Item;
+
+    /// Subtract a number of years from a date object.
+    fn sub_years(self, years: u32) -> Self::Item;
+
+    /// Subtract a number of months from a date object.
+    fn sub_months(self, months: u32) -> Self::Item;
+
+    /// Subtract a number of weeks from a date object.
+    fn sub_weeks(self, weeks: u32) -> Self::Item;
+
+    /// Subtract a number of days from a date object.
+    fn sub_days(self, days: u32) -> Self::Item;
+
+    /// Subtract a number of years from a date object, stopping at
+    /// the epoch, 0001-01-01, rather than going back past it. A date
+    /// that is already before the epoch is left where it is.
+    fn subtract_years(self, years: u32) -> Self::Item;
+
+    /// Subtract a number of months from a date object, stopping at
+    /// the epoch as `subtract_years` does.
+    fn subtract_months(self, months: u32) -> Self::Item;
+
+    /// Subtract a number of weeks from a date object, stopping at
+    /// the epoch as `subtract_years` does.
+    fn subtract_weeks(self, weeks: u32) -> Self::Item;
+
+    /// Subtract a number of days from a date object, stopping at
+    /// the epoch as `subtract_years` does.
+    fn subtract_days(self, days: u32) -> Self::Item;
+
+    /// Add a number of years to a date object,
+    /// returning None if the year would overflow.
+    fn checked_add_years(self, years: u32) -> Option<Self::Item>;
+
+    /// Add a number of months to a date object,
+    /// returning None if the year would overflow.
+    fn checked_add_months(self, months: u32) -> Option<Self::Item>;
+
+    /// Add a number of weeks to a date object,
+    /// returning None if the year would overflow.
+    fn checked_add_weeks(self, weeks: u32) -> Option<Self::Item>;
+
+    /// Add a number of days to a date object,
+    /// returning None if the year would overflow.
+    fn checked_add_days(self, days: u32) -> Option<Self::Item>;
+
+    /// Subtract a number of years from a date object,
+    /// returning None if the year would overflow.
+    fn checked_sub_years(self, years: u32) -> Option<Self::Item>;
+
+    /// Subtract a number of months from a date object,
+    /// returning None if the year would overflow.
+    fn checked_sub_months(self, months: u32) -> Option<Self::Item>;
+
+    /// Subtract a number of weeks from a date object,
+    /// returning None if the year would overflow.
+    fn checked_sub_weeks(self, weeks: u32) -> Option<Self::Item>;
+
+    /// Subtract a number of days from a date object,
+    /// returning None if the year would overflow.
+    fn checked_sub_days(self, days: u32) -> Option<Self::Item>;
+
+    /// Gets the following day.
+    fn succ_day(self) -> Self::Item
+    where
+        Self: Sized,
+    {
+        self.add_days(1)
+    }
+
+    /// Gets the preceding day.
+    fn pred_day(self) -> Self::Item
+    where
+        Self: Sized,
+    {
+        self.sub_days(1)
+    }
+
+    /// Gets the same day in the following month.
+    fn succ_month(self) -> Self::Item
+    where
+        Self: Sized,
+    {
+        self.add_months(1)
+    }
+
+    /// Gets the same day in the preceding month.
+    fn pred_month(self) -> Self::Item
+    where
+        Self: Sized,
+    {
+        self.sub_months(1)
+    }
+
+    /// Gets the same day in the following year.
+    fn succ_year(self) -> Self::Item
+    where
+        Self: Sized,
+    {
+        self.add_years(1)
+    }
+
+    /// Gets the same day in the preceding year.
+    fn pred_year(self) -> Self::Item
+    where
+        Self: Sized,
+    {
+        self.sub_years(1)
+    }
 }
 
 /// The `ModifyDateTime` trait provides a number of
@@ -79,6 +256,15 @@ pub trait ModifyDateTime {
 
     /// Get a new `Self::Item` with a number of seconds added.
     fn add_seconds(&self, seconds: u32) -> Self::Item;
+
+    /// Get a new `Self::Item` with a number of hours subtracted.
+    fn sub_hours(&self, hours: u32) -> Self::Item;
+
+    /// Get a new `Self::Item` with a number of minutes subtracted.
+    fn sub_minutes(&self, minutes: u32) -> Self::Item;
+
+    /// Get a new `Self::Item` with a number of seconds subtracted.
+    fn sub_seconds(&self, seconds: u32) -> Self::Item;
 }
 
 /// The `ShowTime` trait provides a number of
@@ -94,7 +280,17 @@ pub trait ShowTime {
     fn second(&self) -> u32;
 
     /// The total number of seconds this time represents.
-    fn seconds(&self) -> u32;
+    ///
+    /// This is limited to `u32::MAX`, which a calendar with more
+    /// seconds in a day than that can go past late in the day.
+    /// Such times saturate instead; use `seconds_u64` for them.
+    fn seconds(&self) -> u32 {
+        self.seconds_u64().try_into().unwrap_or(u32::MAX)
+    }
+
+    /// The total number of seconds this time represents,
+    /// which never overflows, however long the day.
+    fn seconds_u64(&self) -> u64;
 
     /// Get the `TimeOfDay` that corresponds to this time.
     fn time_of_day(self) -> TimeOfDay;
@@ -122,6 +318,11 @@ pub trait ShowDate {
     /// The week component is 1-indexed.
     fn week(&self) -> u32;
 
+    /// Gets the week within the month that a date falls in,
+    /// following the week boundaries of the calendar. The first
+    /// (possibly partial) week of the month is week 1.
+    fn week_of_month(&self) -> u32;
+
     /// Gets the day of the week for a given date.
     ///
     /// This requires the era because the number of days
@@ -145,4 +346,7 @@ pub trait ShowDate {
     ///
     /// The day component is 1-indexed.
     fn days(&self) -> u32;
+
+    /// Whether the date falls in a leap year.
+    fn is_leap_year(&self) -> bool;
 }
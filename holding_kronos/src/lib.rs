@@ -28,4 +28,5 @@
 
 pub mod calendar;
 pub mod datetime;
+pub mod units;
 mod util;
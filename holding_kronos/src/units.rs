@@ -0,0 +1,119 @@
+//! units: Lightweight wrappers to keep units of time apart.
+//!
+//! Durations are easy to mix up when they are all bare
+//! integers. These types make the unit part of the signature
+//! and only allow conversion through a calendar.
+
+use std::ops::{Add, Mul, Rem, Sub};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::calendar::traits::{ConvertTime, DayCycle};
+
+/// A number of seconds.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(transparent))]
+pub struct Seconds(pub u32);
+
+/// A number of days.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(transparent))]
+pub struct Days(pub u32);
+
+impl Seconds {
+    /// Gets the number of whole days in this duration according to a calendar.
+    pub fn to_days<T: DayCycle>(self, calendar: &T) -> Days {
+        Days(self.0 / calendar.seconds_in_day())
+    }
+}
+
+impl Days {
+    /// Gets the number of seconds in this many days according to a calendar.
+    pub fn to_seconds<T: ConvertTime>(self, calendar: &T) -> Seconds {
+        Seconds(calendar.days_to_seconds(self.0))
+    }
+}
+
+macro_rules! impl_unit {
+    ($unit:ident) => {
+        impl From<u32> for $unit {
+            fn from(val: u32) -> Self {
+                Self(val)
+            }
+        }
+
+        impl From<$unit> for u32 {
+            fn from(val: $unit) -> Self {
+                val.0
+            }
+        }
+
+        impl Add for $unit {
+            type Output = Self;
+
+            fn add(self, rhs: Self) -> Self {
+                Self(self.0 + rhs.0)
+            }
+        }
+
+        impl Sub for $unit {
+            type Output = Self;
+
+            fn sub(self, rhs: Self) -> Self {
+                Self(self.0 - rhs.0)
+            }
+        }
+
+        impl Mul<u32> for $unit {
+            type Output = Self;
+
+            fn mul(self, rhs: u32) -> Self {
+                Self(self.0 * rhs)
+            }
+        }
+
+        impl Rem for $unit {
+            type Output = Self;
+
+            fn rem(self, rhs: Self) -> Self {
+                Self(self.0 % rhs.0)
+            }
+        }
+    };
+}
+
+impl_unit!(Seconds);
+impl_unit!(Days);
+
+#[cfg(test)]
+mod test {
+    use test_case::test_case;
+
+    use crate::calendar::{Calendar, Day, Week, Year};
+
+    use super::{Days, Seconds};
+
+    #[test_case(0, 0)]
+    #[test_case(1, 86400)]
+    #[test_case(365, 31536000)]
+    pub fn days_to_seconds(days: u32, seconds: u32) {
+        let cal = Calendar::default();
+        assert_eq!(Days(days).to_seconds(&cal), Seconds(seconds));
+    }
+
+    #[test]
+    pub fn days_to_seconds_custom_day() {
+        let cal = Calendar::new(Year::default(), Week::default(), Day::new(100, 100, 10));
+        assert_eq!(Days(2).to_seconds(&cal), Seconds(200000));
+        assert_eq!(Seconds(250000).to_days(&cal), Days(2));
+    }
+
+    #[test]
+    pub fn arithmetic() {
+        assert_eq!(Seconds(10) + Seconds(5), Seconds(15));
+        assert_eq!(Seconds(10) - Seconds(5), Seconds(5));
+        assert_eq!(Days(3) * 4, Days(12));
+        assert_eq!(Seconds(10) % Seconds(4), Seconds(2));
+    }
+}
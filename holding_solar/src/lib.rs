@@ -22,6 +22,10 @@
 
 mod orbit;
 mod planet;
+mod season;
 
 pub use orbit::{Orbit, Phase};
-pub use planet::{CelestialBody, PlanetId, PlanetStore};
+pub use planet::{
+    CelestialBody, Kelvin, PlanetId, PlanetStore, Rings, ValidationError, EARTH_MASS, EARTH_RADIUS,
+};
+pub use season::Season;
@@ -7,14 +7,22 @@ use std::convert::TryFrom;
 use std::f64::consts::PI;
 
 use num_enum::{IntoPrimitive, TryFromPrimitive};
+use num_traits::ToPrimitive;
 use serde::{Deserialize, Serialize};
 use strum_macros::Display;
 use thiserror::Error;
 
-use holding_kronos::calendar::{traits::ConvertDate, Calendar};
-use holding_kronos::datetime::DateTime;
+use holding_kronos::calendar::{
+    traits::{ConvertDate, DayCycle},
+    Calendar,
+};
+use holding_kronos::datetime::{traits::ModifyDateTime, Date, DateTime, Time};
+use holding_kronos::units::{Days, Seconds};
 
-use crate::{CelestialBody, PlanetId, PlanetStore};
+use crate::{
+    planet::{G, SUN_TEMPERATURE},
+    CelestialBody, PlanetId, PlanetStore,
+};
 
 /// Describes the orbit of a given planet.
 ///
@@ -22,7 +30,9 @@ use crate::{CelestialBody, PlanetId, PlanetStore};
 /// to the nearest day relative to the parent object.
 /// This makes calendar calculation much much simpler,
 /// as you do not need to manage leap seconds / days.
-#[derive(Clone, Deserialize, Serialize, Debug, Copy)]
+///
+/// Like bodies, orbits compare their eccentricity exactly.
+#[derive(Clone, Deserialize, Serialize, Debug, Copy, PartialEq)]
 pub struct Orbit {
     /// The id of the parent planet.
     pub parent: PlanetId,
@@ -30,27 +40,36 @@ pub struct Orbit {
     /// The body that is orbiting.
     pub body: PlanetId,
 
-    /// The starting offset for the orbit in days.
-    pub shift: u32,
+    /// The starting offset for the orbit.
+    pub shift: Seconds,
 
     /// The eccentricity of the orbit, or how elliptic it is.
     pub eccentricity: f64,
 
-    /// The amount of time a single orbit takes in seconds.
+    /// The amount of time a single orbit takes.
     ///
     /// For simplicity, valid planets _must_ have a period
     /// that is a multiple of the number of seconds in a day.
-    pub period: u32,
+    pub period: Seconds,
 }
 
 impl Orbit {
-    /// Create a new orbit with a given perion.
-    pub fn from_period(target: &CelestialBody, parent: PlanetId, period: u32, shift: u32) -> Self {
+    /// Create a new orbit with a given period.
+    ///
+    /// Both the period and the shift are measured in days
+    /// of the orbiting body (ie. its rotational period).
+    pub fn from_period(
+        target: &CelestialBody,
+        parent: PlanetId,
+        period: Days,
+        shift: Days,
+    ) -> Self {
+        let period = target.rotational_period * period.0;
         Orbit {
             parent,
             body: target.id,
-            period: period * target.rotational_period,
-            shift: shift * target.rotational_period % period,
+            period,
+            shift: target.rotational_period * shift.0 % period,
             eccentricity: 0.0,
         }
     }
@@ -59,22 +78,145 @@ impl Orbit {
     /// Note that for now, since the period is a fixed number
     /// of days relative to the parent, this will be rounded.
     /// This is for simpler interop with the calendar. Nobody
-    /// wants to RP leap seconds!
+    /// wants to RP leap seconds! Returns None if the period
+    /// is too long to be counted in days.
     pub fn from_radius(
         target: &CelestialBody,
         parent: PlanetId,
         semimajor_axis: f64,
-        shift: u32,
-    ) -> Self {
-        let period = semimajor_axis.powf(3.0).sqrt() as u32;
-        Orbit::from_period(target, parent, period, shift)
+        shift: Days,
+    ) -> Option<Self> {
+        let period = Days(semimajor_axis.powf(3.0).sqrt().to_u32()?);
+        Some(Orbit::from_period(target, parent, period, shift))
     }
 
     /// Get the phase of the orbit.
     ///
     /// Only valid if the body being orbited is in turn
     /// orbiting something else (that gives off light).
-    pub fn get_phase(&self, lookup: &dyn PlanetStore, date_time: DateTime) -> Option<Phase> {
+    ///
+    /// The phase is calculated as seen from the center of the
+    /// parent, ignoring parallax. An observer on the surface at
+    /// some longitude (in radians east of the prime meridian) sees
+    /// the sky rotated ahead of the prime meridian, so phase changes
+    /// reach them earlier in the day.
+    pub fn get_phase(
+        &self,
+        lookup: &dyn PlanetStore,
+        date_time: DateTime,
+        observer_longitude: Option<f64>,
+    ) -> Option<Phase> {
+        let theta = self.phase_angle(lookup, date_time, observer_longitude)?;
+        let index = (theta * 4.0 / PI) as u8;
+
+        // we multiply 4/pi to put it in the range [0,8)
+        Some(Phase::try_from(index).expect("This should be in range"))
+    }
+
+    /// Gets the time between two matching phases, such as from one
+    /// full moon to the next. This is longer than the period, since
+    /// the parent moves along its own orbit in the meantime.
+    ///
+    /// Returns None if the parent has no orbit, or if the phase never
+    /// changes because both orbits take the same time. It is also
+    /// None if the orbits are so close that it can't be counted.
+    pub fn synodic_period(&self, lookup: &dyn PlanetStore) -> Option<Seconds> {
+        let parent_orbit = lookup.get_planet(self.parent)?.orbit?;
+        let rate = 1.0 / f64::from(self.period.0) - 1.0 / f64::from(parent_orbit.period.0);
+        if rate == 0.0 {
+            return None;
+        }
+
+        whole_seconds((1.0 / rate.abs()).round())
+    }
+
+    /// Gets the instant at which the body next reaches the exact
+    /// start of a phase, strictly after some time.
+    pub fn next_phase<'a>(
+        &self,
+        lookup: &dyn PlanetStore,
+        from: DateTime<'a>,
+        phase: Phase,
+    ) -> Option<DateTime<'a>> {
+        let theta = self.phase_angle(lookup, from, None)?;
+        let synodic = self.synodic_period(lookup)?;
+        let waxing =
+            f64::from(self.period.0) < f64::from(lookup.get_planet(self.parent)?.orbit?.period.0);
+
+        let target = f64::from(u8::from(phase)) * PI / 4.0;
+        let remaining = if waxing {
+            (target - theta).rem_euclid(2.0 * PI)
+        } else {
+            (theta - target).rem_euclid(2.0 * PI)
+        };
+        let seconds = whole_seconds((remaining / (2.0 * PI) * f64::from(synodic.0)).round())?;
+
+        Some(from.add_seconds(if seconds.0 == 0 { synodic.0 } else { seconds.0 }))
+    }
+
+    /// Gets the principal phases of the next cycle: the next new
+    /// moon after some time, followed by its first quarter, full
+    /// moon, and third quarter. Returns None for bodies without
+    /// phases.
+    pub fn principal_phases<'a>(
+        &self,
+        lookup: &dyn PlanetStore,
+        from: DateTime<'a>,
+    ) -> Option<[DateTime<'a>; 4]> {
+        let new = self.next_phase(lookup, from, Phase::New)?;
+        let first_quarter = self.next_phase(lookup, new, Phase::FirstQuarter)?;
+        let full = self.next_phase(lookup, first_quarter, Phase::Full)?;
+        let third_quarter = self.next_phase(lookup, full, Phase::ThirdQuarter)?;
+        Some([new, first_quarter, full, third_quarter])
+    }
+
+    /// Gets the fraction of the disk of the body that is lit,
+    /// from 0 when new to 1 when full.
+    pub fn illuminated_fraction(
+        &self,
+        lookup: &dyn PlanetStore,
+        date_time: DateTime,
+    ) -> Option<f64> {
+        let theta = self.phase_angle(lookup, date_time, None)?;
+        Some((1.0 + theta.cos()) / 2.0)
+    }
+
+    /// Gets how bright the body appears from its parent.
+    ///
+    /// This is relative to a full body that reflects all light,
+    /// lit by a star as hot as the sun, with both orbits at their
+    /// average distance, which has a brightness of 1. Closer or
+    /// more reflective bodies and hotter stars appear brighter.
+    pub fn apparent_brightness(
+        &self,
+        lookup: &dyn PlanetStore,
+        date_time: DateTime,
+    ) -> Option<f64> {
+        let fraction = self.illuminated_fraction(lookup, date_time)?;
+        let body = lookup.get_planet(self.body)?;
+        let parent_orbit = lookup.get_planet(self.parent)?.orbit.as_ref()?;
+        let sun = lookup.get_planet(parent_orbit.parent)?;
+
+        let luminosity = (f64::from(sun.temperature.0) / f64::from(SUN_TEMPERATURE.0)).powi(4);
+        let relative_distance = |orbit: &Orbit| {
+            orbit.get_distance(date_time.seconds_modulo(orbit.period)) / orbit.semimajor_axis()
+        };
+
+        Some(
+            body.albedo * fraction * luminosity
+                / relative_distance(self).powi(2)
+                / relative_distance(parent_orbit).powi(2),
+        )
+    }
+
+    /// Gets the angle between the body and the sun, as seen from
+    /// the parent, where 0 is full and pi is new.
+    fn phase_angle(
+        &self,
+        lookup: &dyn PlanetStore,
+        date_time: DateTime,
+        observer_longitude: Option<f64>,
+    ) -> Option<f64> {
         // luminous bodies don't have a visible phase.
         if lookup.get_planet(self.body)?.is_luminous() {
             return None;
@@ -89,67 +231,273 @@ impl Orbit {
             return None;
         }
 
+        let offset = observer_longitude
+            .map(|l| longitude_offset(l, parent.rotational_period))
+            .unwrap_or_default();
+
         // angle of moon relative to parent
-        let theta_moon = self.get_orbit_radians(date_time.seconds_modulo(self.period));
+        let theta_moon = self.get_orbit_radians(date_time.seconds_modulo(self.period) + offset);
 
         // angle of parent relative to sun
         let theta_parent =
-            parent_orbit.get_orbit_radians(date_time.seconds_modulo(parent_orbit.period));
+            parent_orbit.get_orbit_radians(date_time.seconds_modulo(parent_orbit.period) + offset);
 
         let mut theta = theta_moon - theta_parent;
         if theta.is_sign_negative() {
             theta += 2.0 * PI
         };
 
-        let index = (theta * 4.0 / PI) as u8;
+        Some(theta)
+    }
 
-        // we multiply 4/pi to put it in the range [0,8)
-        Some(Phase::try_from(index).expect("This should be in range"))
+    /// Gets the number of seconds until the body next rises
+    /// over the horizon for an observer on the parent at some
+    /// longitude (in radians east of the prime meridian).
+    ///
+    /// Returns None if the body never rises, for example if
+    /// it orbits as fast or faster than the parent rotates.
+    pub fn next_rise(
+        &self,
+        lookup: &dyn PlanetStore,
+        date_time: DateTime,
+        observer_longitude: f64,
+    ) -> Option<Seconds> {
+        // the body rises when it sits a quarter turn east of the observer
+        self.next_horizon_crossing(lookup, date_time, observer_longitude, PI / 2.0)
+    }
+
+    /// Gets the number of seconds until the body next sets
+    /// below the horizon for an observer on the parent at some
+    /// longitude (in radians east of the prime meridian).
+    pub fn next_set(
+        &self,
+        lookup: &dyn PlanetStore,
+        date_time: DateTime,
+        observer_longitude: f64,
+    ) -> Option<Seconds> {
+        // the body sets when it sits a quarter turn west of the observer
+        self.next_horizon_crossing(lookup, date_time, observer_longitude, -PI / 2.0)
+    }
+
+    /// Gets the times of day that the body next rises and sets,
+    /// starting from the beginning of some date, as seen from the
+    /// prime meridian of the parent.
+    ///
+    /// This is an approximation: orbits are treated as circular and
+    /// in the plane of the equator, and the horizon is flat. If the
+    /// body does not rise (or set) on the date, the time of the next
+    /// rise (or set) is given instead.
+    pub fn rise_set<'a>(
+        &self,
+        lookup: &dyn PlanetStore,
+        date: Date<'a>,
+    ) -> Option<(Time<'a>, Time<'a>)> {
+        let calendar = date.calendar;
+        let midnight = DateTime {
+            date,
+            time: Time::from_seconds(0, calendar),
+        };
+        let day = Days(1).to_seconds(calendar);
+
+        let rise = self.next_rise(lookup, midnight, 0.0)? % day;
+        let set = self.next_set(lookup, midnight, 0.0)? % day;
+
+        Some((
+            Time::from_seconds(rise.0, calendar),
+            Time::from_seconds(set.0, calendar),
+        ))
+    }
+
+    /// Gets the number of seconds until the body next sits at some
+    /// angle (in radians) ahead of the zenith of an observer.
+    ///
+    /// The observer faces away from the sun at midnight, so their
+    /// angle includes both the rotation of the parent and its own
+    /// orbit around the sun.
+    fn next_horizon_crossing(
+        &self,
+        lookup: &dyn PlanetStore,
+        date_time: DateTime,
+        observer_longitude: f64,
+        angle: f64,
+    ) -> Option<Seconds> {
+        let parent = lookup.get_planet(self.parent)?;
+        let day = parent.rotational_period;
+        let (year_angle, year_rate) = parent
+            .orbit
+            .as_ref()
+            .map(|o| {
+                (
+                    o.get_orbit_radians(date_time.seconds_modulo(o.period)),
+                    1.0 / f64::from(o.period.0),
+                )
+            })
+            .unwrap_or((0.0, 0.0));
+
+        // the observer turns faster than the body orbits, so the
+        // body slowly drifts west across the sky
+        let drift =
+            2.0 * PI * (1.0 / f64::from(day.0) + year_rate - 1.0 / f64::from(self.period.0));
+        if drift <= 0.0 {
+            return None;
+        }
+
+        let observer = year_angle
+            + f64::from(date_time.seconds_modulo(day).0) / f64::from(day.0) * 2.0 * PI
+            + observer_longitude;
+        let body = self.get_orbit_radians(date_time.seconds_modulo(self.period));
+        let remaining = (body - observer - angle).rem_euclid(2.0 * PI);
+
+        whole_seconds((remaining / drift).round())
     }
 
     /// Given some day, gets the radians relative to the periapsis.
-    pub fn get_orbit_radians(&self, seconds: u32) -> f64 {
-        f64::from(seconds + self.shift) / f64::from(self.period) % 1.0 * 2.0 * PI
+    pub fn get_orbit_radians(&self, seconds: Seconds) -> f64 {
+        f64::from((seconds + self.shift).0) / f64::from(self.period.0) % 1.0 * 2.0 * PI
     }
 
-    /// Calculates the distance between a body and its parent.
-    pub fn get_distance(&self, seconds: u32) -> f64 {
-        let radians = self.get_orbit_radians(seconds);
+    /// Gets the true anomaly (the actual angle from the periapsis,
+    /// in radians) at some time, by solving Kepler's equation. On an
+    /// eccentric orbit the body moves faster near the periapsis.
+    pub fn get_true_anomaly(&self, seconds: Seconds) -> f64 {
+        let mean = self.get_orbit_radians(seconds);
+        let e = self.eccentricity;
+
+        // newton's method converges quickly for e < 1
+        let mut eccentric = if e > 0.8 { PI } else { mean };
+        for _ in 0..50 {
+            let delta = (eccentric - e * eccentric.sin() - mean) / (1.0 - e * eccentric.cos());
+            eccentric -= delta;
+            if delta.abs() < 1e-12 {
+                break;
+            }
+        }
+
+        let true_anomaly = 2.0
+            * ((1.0 + e).sqrt() * (eccentric / 2.0).sin())
+                .atan2((1.0 - e).sqrt() * (eccentric / 2.0).cos());
+        true_anomaly.rem_euclid(2.0 * PI)
+    }
+
+    /// Gets the time into the orbit (ignoring the shift) at
+    /// which the body reaches some true anomaly in radians.
+    pub fn time_at_true_anomaly(&self, true_anomaly: f64) -> Seconds {
+        let e = self.eccentricity;
+        let eccentric = 2.0
+            * ((1.0 - e).sqrt() * (true_anomaly / 2.0).sin())
+                .atan2((1.0 + e).sqrt() * (true_anomaly / 2.0).cos());
+        let mean = (eccentric - e * eccentric.sin()).rem_euclid(2.0 * PI);
+        whole_seconds((mean / (2.0 * PI) * f64::from(self.period.0)).round())
+            .expect("This is at most the period")
+            % self.period
+    }
+
+    /// Calculates the distance between a body and its parent,
+    /// from the true anomaly so that it agrees with the position.
+    pub fn get_distance(&self, seconds: Seconds) -> f64 {
+        let radians = self.get_true_anomaly(seconds);
         self.semimajor_axis() * (1.0 - self.eccentricity.powf(2.0))
             / (1.0 + self.eccentricity * radians.cos())
     }
 
-    /// Gets the semimajor axis of the orbit ie. the furthest
-    /// distance of orbit. This is dependent on eccentricity.
+    /// Gets the semimajor axis of the orbit, ie. the average distance
+    /// between the body and its parent, in arbitrary units. By Kepler's
+    /// third law, it grows with the period to the power of two thirds.
     pub fn semimajor_axis(&self) -> f64 {
-        f64::from(self.period).powf(2.0).powf(-3.0)
+        f64::from(self.period.0).powf(2.0 / 3.0)
+    }
+
+    /// Gets the semimajor axis of the orbit in meters. This
+    /// is None if the mass of the parent is not known.
+    pub fn semimajor_axis_meters(&self, lookup: &dyn PlanetStore) -> Option<f64> {
+        let mass = lookup.get_planet(self.parent)?.mass?;
+        let period = f64::from(self.period.0);
+        Some((G * mass * period.powi(2) / (4.0 * PI * PI)).cbrt())
+    }
+
+    /// Calculates the distance between a body and its parent in
+    /// meters. This is None if the mass of the parent is not known.
+    pub fn get_distance_meters(&self, lookup: &dyn PlanetStore, seconds: Seconds) -> Option<f64> {
+        let scale = self.semimajor_axis_meters(lookup)? / self.semimajor_axis();
+        Some(self.get_distance(seconds) * scale)
+    }
+
+    /// Gets the number of whole days in the period,
+    /// as measured by the days of a calendar.
+    pub fn period_days(&self, calendar: &Calendar) -> u32 {
+        self.period.to_days(calendar).0
     }
 
     /// Validates an orbit against a calendar,
     /// ensuring the period is correct.
     pub fn validate_calendar(&self, calendar: &Calendar) -> Result<bool, ValidationError> {
-        let calendar_period = calendar.years_to_seconds(1);
+        let calendar_period = Seconds(calendar.years_to_seconds(1));
 
         if self.period != calendar_period {
-            Err(ValidationError::InconsistentPeriod(
-                self.period,
-                calendar_period,
-            ))
+            Err(ValidationError::InconsistentPeriod {
+                period: self.period.0,
+                expected: calendar_period.0,
+                day: calendar.seconds_in_day(),
+            })
         } else {
             Ok(true)
         }
     }
 }
 
+/// Converts a longitude (in radians) to the time it takes
+/// a body with the given day length to rotate through it.
+fn longitude_offset(longitude: f64, day: Seconds) -> Seconds {
+    let turns = longitude.rem_euclid(2.0 * PI) / (2.0 * PI);
+    whole_seconds(turns * f64::from(day.0)).expect("This is less than a day")
+}
+
+/// Converts a number of seconds to `Seconds`, dropping any
+/// fraction. Returns None if it is negative or does not fit.
+fn whole_seconds(seconds: f64) -> Option<Seconds> {
+    seconds.to_u32().map(Seconds)
+}
+
+/// Possible inconsistencies between an orbit and a calendar.
 #[derive(Error, Debug, Copy, Clone)]
 pub enum ValidationError {
-    #[error("the orbial period is inconsistent. planet: {0}, calendar: {1}")]
-    InconsistentPeriod(u32, u32),
+    /// The length of a year does not match the calendar.
+    #[error(
+        "the orbital period is {} but the calendar expects {}",
+        in_days(*.period, *.day),
+        in_days(*.expected, *.day)
+    )]
+    InconsistentPeriod {
+        /// The period of the orbit in seconds.
+        period: u32,
+        /// The length of a year in the calendar in seconds.
+        expected: u32,
+        /// The length of a day in the calendar in seconds.
+        day: u32,
+    },
+}
+
+/// Writes a number of seconds as days, keeping
+/// any fraction of a day so that near misses show.
+pub(crate) fn in_days(seconds: u32, day: u32) -> String {
+    let day = day.max(1);
+    match (seconds / day, seconds % day) {
+        (1, 0) => "1 day".to_string(),
+        (days, 0) => format!("{} days", days),
+        _ => {
+            // use as many decimals as it takes to not look whole
+            let days = f64::from(seconds) / f64::from(day);
+            let precision = (2..6)
+                .find(|&p| format!("{:.*}", p, days) != format!("{:.*}", p, days.round()))
+                .unwrap_or(6);
+            format!("{:.*} days", precision, days)
+        }
+    }
 }
 
 /// A phase is exhibited by 'grandchild' objects in orbit,
 /// as the light from a planet's parent hits its children.
-#[derive(IntoPrimitive, Clone, Debug, TryFromPrimitive, Copy, Display)]
+#[derive(IntoPrimitive, Clone, Debug, TryFromPrimitive, Copy, Display, PartialEq, Eq)]
 #[repr(u8)]
 #[allow(missing_docs)]
 pub enum Phase {
@@ -186,3 +534,212 @@ impl Phase {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::{collections::HashSet, f64::consts::PI};
+
+    use holding_color::Color;
+    use holding_kronos::{
+        calendar::Calendar,
+        datetime::{
+            traits::{ModifyDate, ModifyDateTime, ShowTime},
+            DateTime,
+        },
+        units::{Days, Seconds},
+    };
+
+    use super::{Orbit, Phase};
+    use crate::{CelestialBody, Kelvin, PlanetId, PlanetStore};
+
+    struct System(Vec<CelestialBody>);
+
+    impl PlanetStore for System {
+        fn get_planet(&self, id: PlanetId) -> Option<&CelestialBody> {
+            self.0.iter().find(|b| b.id == id)
+        }
+
+        fn get_planet_mut(&mut self, id: PlanetId) -> Option<&mut CelestialBody> {
+            self.0.iter_mut().find(|b| b.id == id)
+        }
+
+        fn find_planets(&self, name: &str) -> Vec<&CelestialBody> {
+            self.0.iter().filter(|b| b.name == name).collect()
+        }
+
+        fn create_planet(
+            &mut self,
+            name: String,
+            temperature: Kelvin,
+            rotational_period: Seconds,
+            color: Color,
+        ) -> &CelestialBody {
+            self.0.push(CelestialBody::new(
+                name,
+                temperature,
+                rotational_period,
+                color,
+            ));
+            self.0.last().expect("We just pushed a planet")
+        }
+    }
+
+    /// Builds a planet orbited by a moon every 28 days and
+    /// orbiting a sun every 365, returning the planet and moon.
+    fn system() -> (System, PlanetId, PlanetId) {
+        let day = Seconds(86400);
+        let mut system = System(vec![]);
+        let home = system
+            .create_planet("Earth".to_string(), Kelvin(290), day, Color::Green)
+            .id;
+        let moon = system
+            .create_planet("Moon".to_string(), Kelvin(240), day * 28, Color::White)
+            .id;
+        let sun = system
+            .create_planet("Sun".to_string(), Kelvin(5800), day * 28, Color::Yellow)
+            .id;
+        system.add_orbit(home, moon, Days(1));
+        system.add_orbit(sun, home, Days(365));
+        (system, home, moon)
+    }
+
+    #[test]
+    pub fn principal_phases_are_a_quarter_apart() {
+        let calendar = Calendar::default();
+        let (system, home, moon) = system();
+        let moon = system
+            .get_planet(moon)
+            .and_then(|m| m.orbit)
+            .expect("the moon orbits");
+        let now = DateTime::from_seconds(0, &calendar);
+
+        let synodic = i64::from(moon.synodic_period(&system).expect("has phases").0);
+        assert!(synodic > i64::from(moon.period.0));
+
+        let phases = moon
+            .principal_phases(&system, now)
+            .expect("the moon has phases");
+        let expected = [
+            Phase::New,
+            Phase::FirstQuarter,
+            Phase::Full,
+            Phase::ThirdQuarter,
+        ];
+        let mut previous = now.total_seconds();
+        for (instant, phase) in phases.iter().zip(expected.iter()) {
+            let gap = instant.total_seconds() - previous;
+            assert!(gap > 0 && gap <= synodic, "gap of {}s", gap);
+            assert_eq!(
+                moon.get_phase(&system, instant.add_seconds(60), None)
+                    .map(u8::from),
+                Some(u8::from(*phase))
+            );
+            previous = instant.total_seconds();
+        }
+
+        for pair in phases.windows(2) {
+            let gap = pair[1].total_seconds() - pair[0].total_seconds();
+            assert!((gap - synodic / 4).abs() <= 1, "gap of {}s", gap);
+        }
+
+        let home = system
+            .get_planet(home)
+            .and_then(|h| h.orbit)
+            .expect("home orbits the sun");
+        assert!(system
+            .get_planet(home.parent)
+            .expect("sun exists")
+            .is_luminous());
+        assert!(home.principal_phases(&system, now).is_none());
+    }
+
+    /// Gets the orbit of a moon in a system built by `system`.
+    fn moon_orbit(system: &System, moon: PlanetId) -> Orbit {
+        system
+            .get_planet(moon)
+            .and_then(|m| m.orbit)
+            .expect("the moon orbits")
+    }
+
+    #[test]
+    pub fn moonrise_depends_on_longitude() {
+        let calendar = Calendar::default();
+        let (system, _, moon) = system();
+        let moon = moon_orbit(&system, moon);
+        let now = DateTime::from_seconds(0, &calendar);
+
+        let west = moon.next_rise(&system, now, 0.0).expect("the moon rises");
+        let east = moon
+            .next_rise(&system, now, PI / 2.0)
+            .expect("the moon rises");
+
+        assert_ne!(west, east);
+    }
+
+    #[test]
+    pub fn albedo_brightens_moons() {
+        let calendar = Calendar::default();
+        let (mut system, home, _) = system();
+        let mut moon = |name: &str, albedo: f64| {
+            let id = system
+                .create_planet(
+                    name.to_string(),
+                    Kelvin(240),
+                    Seconds(86400 * 28),
+                    Color::White,
+                )
+                .id;
+            system.add_orbit(home, id, Days(1));
+            system.get_planet_mut(id).expect("moon exists").albedo = albedo;
+            id
+        };
+        let icy = moon("Europa", 0.6);
+        let rocky = moon("Phobos", 0.1);
+
+        let now = DateTime::from_seconds(0, &calendar);
+        let brightness = |id| {
+            moon_orbit(&system, id)
+                .apparent_brightness(&system, now)
+                .expect("moons are lit by the sun")
+        };
+
+        assert!(brightness(icy) > brightness(rocky));
+        assert!((brightness(icy) / brightness(rocky) - 6.0).abs() < 1e-9);
+    }
+
+    #[test]
+    pub fn phases_change_from_day_to_day() {
+        let calendar = Calendar::default();
+        let (system, _, moon) = system();
+        let moon = moon_orbit(&system, moon);
+        let now = DateTime::from_seconds(0, &calendar);
+        let synodic = moon.synodic_period(&system).expect("has phases");
+        let days = synodic.0 / Days(1).to_seconds(&calendar).0;
+
+        // phases follow the seconds since the epoch, not the time of day,
+        // so every midnight of a cycle doesn't show the same phase.
+        let phases: HashSet<_> = (0..=days)
+            .filter_map(|d| moon.get_phase(&system, now.add_days(d), None))
+            .map(u8::from)
+            .collect();
+        assert_eq!(phases.len(), 8);
+    }
+
+    #[test]
+    pub fn full_moon_rises_at_sunset() {
+        let calendar = Calendar::default();
+        let (system, _, moon) = system();
+        let moon = moon_orbit(&system, moon);
+
+        // at the epoch, the moon is on the far side from the sun
+        let now = DateTime::from_seconds(0, &calendar);
+        assert!(matches!(
+            moon.get_phase(&system, now, None),
+            Some(Phase::Full)
+        ));
+
+        let (rise, set) = moon.rise_set(&system, now.date).expect("the moon rises");
+        assert!((17..=19).contains(&rise.hour()), "rose at {}", rise);
+        assert!((5..=7).contains(&set.hour()), "set at {}", set);
+    }
+}
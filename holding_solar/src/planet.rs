@@ -1,18 +1,40 @@
-use holding_kronos::calendar::{traits::ConvertTime, Calendar};
+use std::{
+    fmt::{Display, Formatter, Result as FmtResult},
+    ops::{Add, Sub},
+};
+
+use holding_kronos::{
+    calendar::Calendar,
+    datetime::DateTime,
+    units::{Days, Seconds},
+};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use uuid::Uuid;
 
 use holding_color::Color;
 
-use crate::orbit::{self, Orbit};
+use crate::{
+    orbit::{self, Orbit},
+    season::Season,
+};
 
 /// A unique identifier for celestial bodies.
 #[derive(Copy, Clone, Deserialize, Serialize, PartialEq, Eq, Debug)]
 pub struct PlanetId(pub Uuid);
 
+impl Display for PlanetId {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        self.0.fmt(f)
+    }
+}
+
 /// A celestial body.
-#[derive(Clone, Deserialize, Serialize, Debug)]
+///
+/// Bodies are equal if all their fields are. The float fields are
+/// compared exactly, so bodies that only differ by rounding are not
+/// equal, and a body with a NaN field is not even equal to itself.
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq)]
 pub struct CelestialBody {
     /// The id of the body.
     pub id: PlanetId,
@@ -29,17 +51,102 @@ pub struct CelestialBody {
     /// The ids of the planets in orbit of this one.
     pub children: Vec<PlanetId>,
 
-    /// The number of seconds the planet
-    /// takes to rotate.
-    pub rotational_period: u32,
+    /// The time the planet takes to rotate.
+    pub rotational_period: Seconds,
+
+    /// The temperature of the planet.
+    pub temperature: Kelvin,
+
+    /// The mass of the body in kilograms, if known.
+    #[serde(default)]
+    pub mass: Option<f64>,
+
+    /// The radius of the body in meters, if known.
+    #[serde(default)]
+    pub radius: Option<f64>,
+
+    /// The tilt of the axis of rotation, in radians.
+    #[serde(default)]
+    pub axial_tilt: f64,
+
+    /// The fraction of light that the body reflects, from 0 to 1.
+    #[serde(default = "default_albedo")]
+    pub albedo: f64,
+
+    /// The rings around the body, if it has any.
+    #[serde(default)]
+    pub rings: Option<Rings>,
+}
+
+/// A system of rings around a body, such as those of Saturn.
+#[derive(Copy, Clone, Deserialize, Serialize, Debug, PartialEq)]
+pub struct Rings {
+    /// The distance from the center of the body
+    /// to the inner edge of the rings, in meters.
+    pub inner: f64,
+
+    /// The distance from the center of the body
+    /// to the outer edge of the rings, in meters.
+    pub outer: f64,
+}
+
+fn default_albedo() -> f64 {
+    0.3
+}
+
+/// The gravitational constant, in m³ kg⁻¹ s⁻².
+pub(crate) const G: f64 = 6.674e-11;
+
+/// The mass of Earth in kilograms.
+pub const EARTH_MASS: f64 = 5.972e24;
+
+/// The radius of Earth in meters.
+pub const EARTH_RADIUS: f64 = 6.371e6;
+
+/// The surface temperature of the sun.
+pub(crate) const SUN_TEMPERATURE: Kelvin = Kelvin(5800);
+
+/// A temperature in degrees kelvin.
+#[derive(Copy, Clone, Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord, Debug, Default)]
+#[serde(transparent)]
+pub struct Kelvin(pub i32);
+
+impl From<i32> for Kelvin {
+    fn from(val: i32) -> Self {
+        Self(val)
+    }
+}
+
+impl From<Kelvin> for i32 {
+    fn from(val: Kelvin) -> Self {
+        val.0
+    }
+}
+
+impl Add for Kelvin {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self(self.0 + rhs.0)
+    }
+}
+
+impl Sub for Kelvin {
+    type Output = Self;
 
-    /// The temperature in degrees kelvin.
-    pub temperature: i32,
+    fn sub(self, rhs: Self) -> Self {
+        Self(self.0 - rhs.0)
+    }
 }
 
 impl CelestialBody {
     /// Creates a new `CelestialBody`.
-    pub fn new(name: String, temperature: i32, rotational_period: u32, color: Color) -> Self {
+    pub fn new(
+        name: String,
+        temperature: Kelvin,
+        rotational_period: Seconds,
+        color: Color,
+    ) -> Self {
         Self {
             id: PlanetId(Uuid::new_v4()),
             name,
@@ -48,6 +155,11 @@ impl CelestialBody {
             rotational_period,
             orbit: None,
             children: vec![],
+            mass: None,
+            radius: None,
+            axial_tilt: 0.0,
+            albedo: default_albedo(),
+            rings: None,
         }
     }
 
@@ -55,20 +167,106 @@ impl CelestialBody {
     ///
     /// https://qph.fs.quoracdn.net/main-qimg-6bce97c94fb60cc1fe025591653de493
     pub fn is_luminous(&self) -> bool {
-        self.temperature > 3500
+        self.temperature > Kelvin(3500)
+    }
+
+    /// Checks whether it is day at the prime meridian of this body at
+    /// some time, going by its own rotation rather than the calendar.
+    ///
+    /// Like rise and set times, this treats orbits as circular and in
+    /// the plane of the equator, so the sun rises a quarter of the way
+    /// through a rotation and sets three quarters of the way through.
+    pub fn is_day(&self, date_time: DateTime) -> bool {
+        let day = self.rotational_period;
+        let turn = f64::from(date_time.seconds_modulo(day).0) / f64::from(day.0.max(1));
+        (0.25..0.75).contains(&turn)
+    }
+
+    /// Describes how the body looks from afar, going by its color and
+    /// temperature, such as "glows a dull crimson" for a red-hot body,
+    /// and whether it has rings.
+    pub fn describe(&self) -> String {
+        match self.rings {
+            Some(_) => format!("{}, circled by rings", self.describe_surface()),
+            None => self.describe_surface(),
+        }
+    }
+
+    fn describe_surface(&self) -> String {
+        let shade = match self.color {
+            Color::Black => "black",
+            Color::Red => "crimson",
+            Color::Green => "green",
+            Color::Yellow => "gold",
+            Color::Blue => "blue",
+            Color::Magenta => "violet",
+            Color::Cyan => "turquoise",
+            Color::White => "white",
+        };
+
+        match self.temperature {
+            _ if self.is_luminous() => format!("blazes a brilliant {}", shade),
+            t if t >= Kelvin(800) => format!("glows a dull {}", shade),
+            t if t < Kelvin(200) => format!("gleams a pale {}", shade),
+            _ => format!("shimmers a soft {}", shade),
+        }
+    }
+
+    /// Calculates the gravity at the surface of the body,
+    /// relative to that of Earth. Returns None if either
+    /// the mass or radius of the body is unknown.
+    pub fn surface_gravity(&self) -> Option<f64> {
+        let (mass, radius) = (self.mass?, self.radius?);
+        let earth = G * EARTH_MASS / EARTH_RADIUS.powi(2);
+        Some(G * mass / radius.powi(2) / earth)
+    }
+
+    /// Gets the season at some time. Only bodies with
+    /// a tilted axis and an orbit experience seasons.
+    pub fn season(&self, seconds: Seconds) -> Option<Season> {
+        let orbit = self.seasonal_orbit()?;
+        Some(Season::from_angle(orbit.get_true_anomaly(seconds)))
+    }
+
+    /// Lists the seasons of the year, along with their lengths.
+    /// On an eccentric orbit the body moves faster near periapsis,
+    /// so summer is shorter than winter. The lengths always sum to
+    /// the orbital period.
+    pub fn seasons_of_year(&self) -> Vec<(Season, Seconds)> {
+        let orbit = match self.seasonal_orbit() {
+            Some(orbit) => orbit,
+            None => return vec![],
+        };
+
+        let start = |season: Season| orbit.time_at_true_anomaly(season.start_angle());
+        Season::ALL
+            .iter()
+            .map(|&season| {
+                let length = (start(season.next()) + orbit.period - start(season)) % orbit.period;
+                (season, length)
+            })
+            .collect()
+    }
+
+    fn seasonal_orbit(&self) -> Option<&Orbit> {
+        if self.axial_tilt == 0.0 {
+            None
+        } else {
+            self.orbit.as_ref()
+        }
     }
 
     /// Adds a new moon to this planet.
-    pub fn with_moon(&mut self, moon: &mut CelestialBody, period: u32) -> &mut Self {
-        let orbit = Orbit::from_period(moon, self.id, period, 0);
+    pub fn with_moon(&mut self, moon: &mut CelestialBody, period: Days) -> &mut Self {
+        let orbit = Orbit::from_period(moon, self.id, period, Days(0));
         moon.orbit = Some(orbit);
         self.children.push(moon.id);
         self
     }
 
     /// Sets the parent of this planet.
-    pub fn with_parent(&mut self, parent: &mut CelestialBody, period: u32) -> &mut Self {
-        let orbit = Orbit::from_period(self, parent.id, period, 0);
+    pub fn with_parent(&mut self, parent: &mut CelestialBody, period: Days) -> &mut Self {
+        let orbit = Orbit::from_period(self, parent.id, period, Days(0));
         self.orbit = Some(orbit);
         parent.children.push(self.id);
         self
@@ -78,31 +276,48 @@ impl CelestialBody {
     /// ensuring the rotational and orbital
     /// periods are correct.
     pub fn validate_calendar(&self, calendar: &Calendar) -> Result<bool, ValidationError> {
+        match self.calendar_issues(calendar).into_iter().next() {
+            Some(issue) => Err(issue),
+            None => Ok(true),
+        }
+    }
+
+    /// Collects every inconsistency between this
+    /// body and a calendar, rather than just the first.
+    pub fn calendar_issues(&self, calendar: &Calendar) -> Vec<ValidationError> {
+        let mut issues = vec![];
+
         let planet_period = self.rotational_period;
-        let calendar_period = calendar.days_to_seconds(1);
-        let x = if planet_period != calendar_period {
-            Err(ValidationError::InconsistentRotationalPeriod(
-                planet_period,
-                calendar_period,
-            ))
-        } else {
-            Ok(true)
-        };
+        let calendar_period = Days(1).to_seconds(calendar);
+        if planet_period != calendar_period {
+            issues.push(ValidationError::InconsistentRotationalPeriod(
+                self.name.clone(),
+                planet_period.0,
+                calendar_period.0,
+            ));
+        }
 
-        if let Some(y) = self.orbit.as_ref().map(|o| o.validate_calendar(calendar)) {
-            x.and(y.map_err(Into::into))
-        } else {
-            x
+        if let Some(Err(e)) = self.orbit.as_ref().map(|o| o.validate_calendar(calendar)) {
+            issues.push(ValidationError::OrbitValidationError(self.name.clone(), e));
         }
+
+        issues
     }
 }
 
-#[derive(Error, Debug, Copy, Clone)]
+/// Possible inconsistencies between a body and a calendar.
+#[derive(Error, Debug, Clone)]
 pub enum ValidationError {
-    #[error("the rotational period is inconsistent. planet: {0}, calendar: {1}")]
-    InconsistentRotationalPeriod(u32, u32),
-    #[error("invalid orbit: {0}")]
-    OrbitValidationError(#[from] orbit::ValidationError),
+    /// The length of a day on the body does not match the calendar.
+    #[error(
+        "the rotational period of {0} is {} but the calendar expects {}",
+        orbit::in_days(*.1, *.2),
+        orbit::in_days(*.2, *.2)
+    )]
+    InconsistentRotationalPeriod(String, u32, u32),
+    /// The length of a year on the body does not match the calendar.
+    #[error("the orbit of {0} is invalid: {1}")]
+    OrbitValidationError(String, orbit::ValidationError),
 }
 
 /// Keeps track of `CelestialBody`s.
@@ -113,25 +328,264 @@ pub trait PlanetStore {
     /// Get a mutable reference to a `CelestialBody` from the `PlanetStore`.
     fn get_planet_mut(&mut self, id: PlanetId) -> Option<&mut CelestialBody>;
 
+    /// Get every `CelestialBody` in the `PlanetStore` with a given name.
+    /// Names are not unique, so there may be more than one.
+    fn find_planets(&self, name: &str) -> Vec<&CelestialBody>;
+
+    /// Follows the orbits up from a body, returning the bodies in the
+    /// cycle if they ever loop back around, such as when A orbits B
+    /// and B orbits A. Bodies orbiting into a cycle are not included.
+    fn orbit_cycle(&self, id: PlanetId) -> Option<Vec<PlanetId>> {
+        let mut path = vec![];
+        let mut current = Some(id);
+        while let Some(id) = current {
+            if let Some(start) = path.iter().position(|p| *p == id) {
+                return Some(path.split_off(start));
+            }
+            path.push(id);
+            current = self.get_planet(id).and_then(|b| b.orbit).map(|o| o.parent);
+        }
+        None
+    }
+
     /// Create a new `CelestialBody` in the `PlanetStore`.
     fn create_planet(
         &mut self,
         name: String,
-        temperature: i32,
-        rotational_period: u32,
+        temperature: Kelvin,
+        rotational_period: Seconds,
         color: Color,
     ) -> &CelestialBody;
 
-    /// Adds an orbit.
+    /// Adds an orbit, with the period in days of the child.
     ///
     /// todo(arlyon): Allow this to fail if
     /// - either planet doesn't exist
     /// - the child is orbiting something else
-    fn add_orbit(&mut self, parent_id: PlanetId, child_id: PlanetId, period: u32) {
+    fn add_orbit(&mut self, parent_id: PlanetId, child_id: PlanetId, period: Days) {
         let mut child = self.get_planet_mut(child_id).unwrap();
-        let orbit = Orbit::from_period(child, parent_id, period, 0);
+        let orbit = Orbit::from_period(child, parent_id, period, Days(0));
         child.orbit = Some(orbit);
         let parent = self.get_planet_mut(parent_id).unwrap();
         parent.children.push(child_id);
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::f64::consts::PI;
+
+    use holding_color::Color;
+    use holding_kronos::{
+        calendar::Calendar,
+        datetime::DateTime,
+        units::{Days, Seconds},
+    };
+
+    use super::{CelestialBody, Kelvin, Rings, EARTH_MASS, EARTH_RADIUS};
+    use crate::{Orbit, Season};
+
+    fn planet(mass: f64, radius: f64) -> CelestialBody {
+        let mut planet = CelestialBody::new(
+            "Terra".to_string(),
+            Kelvin(290),
+            Seconds(86400),
+            Color::Green,
+        );
+        planet.mass = Some(mass);
+        planet.radius = Some(radius);
+        planet
+    }
+
+    #[test]
+    pub fn earth_gravity() {
+        let gravity = planet(EARTH_MASS, EARTH_RADIUS)
+            .surface_gravity()
+            .expect("mass and radius are known");
+        assert!((gravity - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    pub fn day_follows_rotation() {
+        let calendar = Calendar::default();
+        let mut body = planet(EARTH_MASS, EARTH_RADIUS);
+        body.rotational_period = Seconds(86400 * 2);
+
+        let is_day = |hours: i64| body.is_day(DateTime::from_seconds(hours * 3600, &calendar));
+        assert!(!is_day(6));
+        assert!(is_day(12));
+        assert!(is_day(24));
+        assert!(!is_day(36));
+        assert!(!is_day(48));
+    }
+
+    #[test]
+    pub fn gravity_scales_with_mass() {
+        let light = planet(EARTH_MASS, EARTH_RADIUS).surface_gravity();
+        let heavy = planet(EARTH_MASS * 2.0, EARTH_RADIUS).surface_gravity();
+        let ratio = heavy
+            .zip(light)
+            .map(|(h, l)| h / l)
+            .expect("both are known");
+        assert!((ratio - 2.0).abs() < 1e-9);
+    }
+
+    fn orbiting(eccentricity: f64) -> CelestialBody {
+        let mut sun = planet(EARTH_MASS, EARTH_RADIUS);
+        let mut body = planet(EARTH_MASS, EARTH_RADIUS);
+        body.axial_tilt = 0.4;
+        body.with_parent(&mut sun, Days(365));
+        if let Some(orbit) = body.orbit.as_mut() {
+            orbit.eccentricity = eccentricity;
+        }
+        body
+    }
+
+    #[test]
+    pub fn circular_seasons_are_equal() {
+        let seasons = orbiting(0.0).seasons_of_year();
+        let lengths: Vec<_> = seasons.iter().map(|(_, l)| l.0).collect();
+
+        assert_eq!(lengths.len(), 4);
+        assert!(lengths.iter().all(|&l| l == lengths[0]));
+    }
+
+    #[test]
+    pub fn eccentric_seasons_are_unequal() {
+        let body = orbiting(0.3);
+        let seasons = body.seasons_of_year();
+        let length = |season| {
+            seasons
+                .iter()
+                .find(|(s, _)| *s == season)
+                .map(|(_, l)| *l)
+                .expect("every season is listed")
+        };
+
+        assert!(length(Season::Summer) < length(Season::Winter));
+        assert_eq!(
+            seasons.iter().map(|(_, l)| l.0).sum::<u32>(),
+            Days(365).to_seconds(&Calendar::default()).0
+        );
+
+        let orbit = body.orbit.expect("body is orbiting");
+        assert_eq!(body.season(Seconds(0)), Some(Season::Summer));
+        assert_eq!(
+            body.season(orbit.time_at_true_anomaly(PI)),
+            Some(Season::Winter)
+        );
+    }
+
+    #[test]
+    pub fn eccentric_distance_follows_true_anomaly() {
+        let orbit = orbiting(0.3).orbit.expect("body is orbiting");
+        let hour = 3600;
+        let closest = (0..orbit.period.0 / hour)
+            .map(|h| Seconds(h * hour))
+            .min_by(|a, b| orbit.get_distance(*a).total_cmp(&orbit.get_distance(*b)))
+            .expect("the orbit is longer than an hour");
+        let periapsis = (orbit.period - orbit.shift) % orbit.period;
+        assert!(closest.0.abs_diff(periapsis.0) <= hour);
+
+        let a = orbit.semimajor_axis();
+        let side = orbit.get_distance(orbit.time_at_true_anomaly(PI / 2.0) + periapsis);
+        assert!((side / (a * (1.0 - 0.3f64.powi(2))) - 1.0).abs() < 1e-4);
+    }
+
+    #[test]
+    pub fn semimajor_axis_follows_keplers_third_law() {
+        let sun = planet(EARTH_MASS * 333_000.0, EARTH_RADIUS * 109.0);
+        let body = planet(EARTH_MASS, EARTH_RADIUS);
+        let year = Orbit::from_period(&body, sun.id, Days(365), Days(0));
+        let longer = Orbit::from_period(&body, sun.id, Days(365 * 8), Days(0));
+
+        let period = f64::from(year.period.0);
+        assert!((year.semimajor_axis() / period.powf(2.0 / 3.0) - 1.0).abs() < 1e-12);
+        assert!((longer.semimajor_axis() / year.semimajor_axis() - 4.0).abs() < 1e-9);
+        assert!((year.get_distance(Seconds(0)) / year.semimajor_axis() - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    pub fn untilted_bodies_have_no_seasons() {
+        let mut body = orbiting(0.3);
+        body.axial_tilt = 0.0;
+        assert_eq!(body.season(Seconds(0)), None);
+        assert!(body.seasons_of_year().is_empty());
+    }
+
+    #[test]
+    pub fn unknown_gravity() {
+        let mut body = planet(EARTH_MASS, EARTH_RADIUS);
+        body.radius = None;
+        assert_eq!(body.surface_gravity(), None);
+    }
+
+    #[test]
+    pub fn identical_bodies_are_equal() {
+        let sun = planet(EARTH_MASS * 333_000.0, EARTH_RADIUS * 109.0);
+        let mut a = planet(EARTH_MASS, EARTH_RADIUS);
+        let mut b = planet(EARTH_MASS, EARTH_RADIUS);
+        assert_ne!(a, b, "bodies get their own ids");
+
+        b.id = a.id;
+        a.orbit = Some(Orbit::from_period(&a, sun.id, Days(365), Days(0)));
+        b.orbit = Some(Orbit::from_period(&b, sun.id, Days(365), Days(0)));
+        assert_eq!(a, b);
+
+        b.albedo += f64::EPSILON;
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    pub fn describes_by_color_and_temperature() {
+        let body = |temperature: i32, color: Color| {
+            CelestialBody::new(
+                "Body".to_string(),
+                Kelvin(temperature),
+                Seconds(86400),
+                color,
+            )
+        };
+
+        let lava = body(1200, Color::Red).describe();
+        let ice = body(100, Color::White).describe();
+        assert_eq!(lava, "glows a dull crimson");
+        assert_eq!(ice, "gleams a pale white");
+        assert_eq!(
+            body(5800, Color::Yellow).describe(),
+            "blazes a brilliant gold"
+        );
+        assert_ne!(body(290, Color::Red).describe(), lava);
+    }
+
+    #[test]
+    pub fn describes_rings() {
+        let mut giant = planet(EARTH_MASS * 95.0, EARTH_RADIUS * 9.0);
+        assert!(!giant.describe().contains("rings"));
+
+        giant.rings = Some(Rings {
+            inner: EARTH_RADIUS * 11.0,
+            outer: EARTH_RADIUS * 22.0,
+        });
+        assert_eq!(giant.describe(), "shimmers a soft green, circled by rings");
+    }
+
+    #[test]
+    pub fn reports_periods_in_days() {
+        let cal = Calendar::default();
+        let mut sun = planet(EARTH_MASS * 333_000.0, EARTH_RADIUS * 109.0);
+        let mut body = planet(EARTH_MASS, EARTH_RADIUS);
+        body.with_parent(&mut sun, Days(360));
+
+        let orbit = body.orbit.expect("has an orbit");
+        assert_eq!(orbit.period_days(&cal), 360);
+
+        let error = body
+            .validate_calendar(&cal)
+            .expect_err("the year is too short");
+        assert_eq!(
+            error.to_string(),
+            "the orbit of Terra is invalid: the orbital period is 360 days but the calendar expects 365 days"
+        );
+    }
+}
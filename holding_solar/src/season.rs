@@ -0,0 +1,63 @@
+//! season: The seasons brought about by axial tilt.
+
+use std::f64::consts::PI;
+
+use strum_macros::Display;
+
+/// A season, as experienced by the hemisphere of a body
+/// that is tilted towards its parent at periapsis.
+#[derive(Clone, Copy, Debug, Display, PartialEq, Eq)]
+#[allow(missing_docs)]
+pub enum Season {
+    Spring,
+    Summer,
+    Autumn,
+    Winter,
+}
+
+impl Season {
+    /// All the seasons, in the order they occur.
+    pub const ALL: [Season; 4] = [
+        Season::Spring,
+        Season::Summer,
+        Season::Autumn,
+        Season::Winter,
+    ];
+
+    /// Gets the season for an angle of orbit (in radians)
+    /// relative to the periapsis. Summer is centred on the
+    /// periapsis and winter on the apoapsis.
+    pub fn from_angle(angle: f64) -> Self {
+        let quarter = (angle + PI / 4.0).rem_euclid(2.0 * PI) * 2.0 / PI;
+        if quarter < 1.0 {
+            Season::Summer
+        } else if quarter < 2.0 {
+            Season::Autumn
+        } else if quarter < 3.0 {
+            Season::Winter
+        } else {
+            Season::Spring
+        }
+    }
+
+    /// Gets the angle of orbit (in radians, relative
+    /// to the periapsis) at which the season begins.
+    pub fn start_angle(self) -> f64 {
+        match self {
+            Season::Summer => -PI / 4.0,
+            Season::Autumn => PI / 4.0,
+            Season::Winter => 3.0 * PI / 4.0,
+            Season::Spring => 5.0 * PI / 4.0,
+        }
+    }
+
+    /// Gets the season that follows this one.
+    pub fn next(self) -> Self {
+        match self {
+            Season::Spring => Season::Summer,
+            Season::Summer => Season::Autumn,
+            Season::Autumn => Season::Winter,
+            Season::Winter => Season::Spring,
+        }
+    }
+}
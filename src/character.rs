@@ -1,8 +1,12 @@
+use std::fmt::Display;
+
+use holding_kronos::datetime::RawDateTime;
 use holding_solar::PlanetId;
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 use uuid::Uuid;
 
-#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct CharacterId(pub Uuid);
 
 impl Default for CharacterId {
@@ -17,7 +21,17 @@ pub enum Class {
     Warlock(Warlock),
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+impl Class {
+    /// Gets the number of sides on the hit die of the class.
+    pub fn hit_die(self) -> u32 {
+        match self {
+            Class::Fighter(_) => 10,
+            Class::Warlock(_) => 8,
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum Race {
     Unknown,
     Human,
@@ -59,6 +73,23 @@ pub struct Character {
 
     /// Only set if working with experience
     experience: Option<u32>,
+
+    /// Archived characters are hidden from listings,
+    /// but are kept so that old records still resolve.
+    #[serde(default)]
+    archived: bool,
+
+    /// The number of hit dice spent since the last long rest.
+    #[serde(default)]
+    hit_dice_spent: u32,
+
+    /// Only set while the character is in combat.
+    #[serde(default)]
+    initiative: Option<i32>,
+
+    /// Notes about the character, along with when they were written.
+    #[serde(default)]
+    notes: Vec<(RawDateTime, String)>,
 }
 
 impl Character {
@@ -67,15 +98,59 @@ impl Character {
         name: Option<String>,
         max_health: Option<u32>,
         race: Option<Race>,
-    ) -> Self {
-        Character {
+    ) -> Result<Self, CharacterError> {
+        if identifier.trim().is_empty() {
+            return Err(CharacterError::EmptyIdentifier);
+        }
+
+        Ok(Character {
             id: CharacterId(Uuid::new_v4()),
             name: name.unwrap_or_else(|| identifier.clone()),
             identifier,
             health: max_health.map(Health::new).unwrap_or_default(),
             race: race.unwrap_or_default(),
             ..Default::default()
-        }
+        })
+    }
+
+    /// Gets the full name of the character.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Gets the race of the character.
+    pub fn race(&self) -> Race {
+        self.race
+    }
+
+    /// Changes the full name of the character.
+    pub fn set_name(&mut self, name: String) {
+        self.name = name;
+    }
+
+    /// Changes the race of the character.
+    pub fn set_race(&mut self, race: Race) {
+        self.race = race;
+    }
+
+    /// Sets the maximum health of the character, healing them to full.
+    pub fn set_max_health(&mut self, max_health: u32) {
+        self.health = Health::new(max_health);
+    }
+
+    /// Gets the classes of the character, along with their levels.
+    pub fn classes(&self) -> &[(Option<Class>, u8)] {
+        &self.classes
+    }
+
+    /// Gets the description of the character.
+    pub fn description(&self) -> &Description {
+        &self.description
+    }
+
+    /// Gets whether the character has inspiration.
+    pub fn inspiration(&self) -> bool {
+        self.inspiration
     }
 
     pub fn level(&self) -> u8 {
@@ -86,8 +161,16 @@ impl Character {
             .sum()
     }
 
+    /// Takes a level in a class, starting the class if the character
+    /// doesn't have it yet. This fails if all the class slots are taken.
     pub fn level_up(&mut self, class: Class) -> Result<(), ()> {
-        if let Some((_, level)) = self.classes.iter_mut().find(|(c, _)| Some(class).eq(c)) {
+        let slot = match self.classes.iter().position(|(c, _)| Some(class).eq(c)) {
+            Some(i) => Some(i),
+            None => self.classes.iter().position(|(c, _)| c.is_none()),
+        };
+
+        if let Some((c, level)) = slot.and_then(|i| self.classes.get_mut(i)) {
+            *c = Some(class);
             *level += 1;
             if let Some(x) = &mut self.experience {
                 *x = 0;
@@ -97,6 +180,122 @@ impl Character {
             Err(())
         }
     }
+
+    /// Gets whether the character has been archived.
+    pub fn archived(&self) -> bool {
+        self.archived
+    }
+
+    /// Archives or restores the character.
+    pub fn set_archived(&mut self, archived: bool) {
+        self.archived = archived;
+    }
+
+    /// Gets the initiative the character rolled, if they are in combat.
+    pub fn initiative(&self) -> Option<i32> {
+        self.initiative
+    }
+
+    /// Sets the initiative of the character, or takes them out of combat.
+    pub fn set_initiative(&mut self, initiative: Option<i32>) {
+        self.initiative = initiative;
+    }
+
+    /// Gets the notes about the character, oldest first.
+    pub fn notes(&self) -> &[(RawDateTime, String)] {
+        &self.notes
+    }
+
+    /// Writes down a note about the character.
+    pub fn add_note(&mut self, date: RawDateTime, note: String) {
+        self.notes.push((date, note));
+    }
+
+    /// Moves each note to a new date, in the order of `notes`,
+    /// and then puts them back in order, oldest first.
+    pub fn set_note_dates(&mut self, dates: impl IntoIterator<Item = RawDateTime>) {
+        for ((date, _), new) in self.notes.iter_mut().zip(dates) {
+            *date = new;
+        }
+        self.notes.sort_by_key(|(date, _)| *date);
+    }
+
+    /// Gets the location of the character, if known.
+    pub fn location(&self) -> Option<LocationId> {
+        self.location
+    }
+
+    /// Moves the character to a new location.
+    pub fn move_to(&mut self, location: LocationId) {
+        self.location = Some(location);
+    }
+
+    /// Gets the health of the character.
+    pub fn health(&self) -> Health {
+        self.health
+    }
+
+    /// Deals damage to the character, returning their new status.
+    pub fn damage(&mut self, amount: u32, critical: bool) -> HealthStatus {
+        self.health.damage(amount, critical)
+    }
+
+    /// Heals the character, returning their new status.
+    pub fn heal(&mut self, amount: u32) -> HealthStatus {
+        self.health.heal(amount)
+    }
+
+    /// Gets the number of hit dice the character can still spend.
+    /// There is one per level, and they come back on a long rest.
+    pub fn hit_dice(&self) -> u32 {
+        u32::from(self.level()).saturating_sub(self.hit_dice_spent)
+    }
+
+    /// Takes a short rest, spending hit dice one at a time until the
+    /// character is at full health or out of dice. `roll` is given the
+    /// sides of each die and returns the result, and any error it
+    /// gives ends the rest early.
+    pub fn short_rest<E>(
+        &mut self,
+        mut roll: impl FnMut(u32) -> Result<u32, E>,
+    ) -> Result<HealthStatus, E> {
+        self.health.stabilize();
+        let die = self
+            .classes
+            .iter()
+            .filter(|(_, level)| *level > 0)
+            .filter_map(|(class, _)| class.map(Class::hit_die))
+            .max();
+
+        if let Some(die) = die {
+            while self.hit_dice() > 0 {
+                let missing = match self.health.status {
+                    HealthStatus::Alive(hp) => self.health.max.saturating_sub(hp.current),
+                    _ => 0,
+                };
+                if missing == 0 {
+                    break;
+                }
+
+                let healed = roll(die)?;
+                self.hit_dice_spent += 1;
+                self.health.heal(healed.min(missing));
+            }
+        }
+
+        Ok(self.health.status())
+    }
+
+    /// Takes a long rest, restoring the character to full health and
+    /// recovering up to half of their hit dice (at least one).
+    pub fn long_rest(&mut self) -> HealthStatus {
+        self.health.stabilize();
+        self.health.restore();
+
+        let recovered = (u32::from(self.level()) / 2).max(1);
+        self.hit_dice_spent = self.hit_dice_spent.saturating_sub(recovered);
+        self.health.status()
+    }
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
@@ -112,6 +311,20 @@ pub enum HealthStatus {
     Dead,
 }
 
+impl Display for HealthStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HealthStatus::Alive(hp) => write!(f, "alive with {} hp", hp.hp()),
+            HealthStatus::Unconscious(ds) => write!(
+                f,
+                "unconscious with {} successful and {} failed death saves",
+                ds.successes, ds.failures
+            ),
+            HealthStatus::Dead => write!(f, "dead"),
+        }
+    }
+}
+
 impl Default for HealthStatus {
     fn default() -> Self {
         Self::Alive(Default::default())
@@ -125,8 +338,14 @@ pub struct DeathSaves {
 }
 
 impl DeathSaves {
+    /// Whether three death saves have succeeded, so that the
+    /// character is no longer dying.
+    pub fn stable(self) -> bool {
+        self.successes >= 3
+    }
+
     pub fn damage(self, critical: bool) -> HealthStatus {
-        match self.failures + (if critical { 1 } else { 2 }) {
+        match self.failures + (if critical { 2 } else { 1 }) {
             x if x < 3 => HealthStatus::Unconscious(Self {
                 failures: x,
                 successes: self.successes,
@@ -193,7 +412,10 @@ impl Health {
     pub fn new(max: u32) -> Self {
         Health {
             max,
-            ..Default::default()
+            status: HealthStatus::Alive(Hitpoints {
+                current: max,
+                bonus: 0,
+            }),
         }
     }
 
@@ -221,13 +443,33 @@ impl Health {
         self.status
     }
 
+    /// Wakes a stable character with a single hit point,
+    /// as happens after a few hours of rest.
+    pub fn stabilize(&mut self) {
+        if let HealthStatus::Unconscious(ds) = self.status {
+            if ds.stable() {
+                self.status = ds.heal(1);
+            }
+        }
+    }
+
+    /// Restores a conscious character to full health.
+    pub fn restore(&mut self) {
+        if let HealthStatus::Alive(hp) = self.status {
+            self.status = HealthStatus::Alive(Hitpoints {
+                current: self.max.max(hp.current),
+                bonus: hp.bonus,
+            });
+        }
+    }
+
     pub fn dead(self) -> bool {
         matches!(self.status, HealthStatus::Dead)
     }
 
     pub fn full(self) -> bool {
         match self.status {
-            HealthStatus::Alive(hp) => hp.current == self.max,
+            HealthStatus::Alive(hp) => hp.current >= self.max,
             _ => false,
         }
     }
@@ -255,6 +497,33 @@ pub struct Description {
     backstory: Option<String>,
 }
 
+impl Description {
+    pub fn age(&self) -> Option<u32> {
+        self.age
+    }
+
+    /// height in cm
+    pub fn height(&self) -> Option<u32> {
+        self.height
+    }
+
+    /// wight in kg
+    pub fn weight(&self) -> Option<u32> {
+        self.weight
+    }
+
+    pub fn backstory(&self) -> Option<&str> {
+        self.backstory.as_deref()
+    }
+}
+
+/// Possible problems when creating a character.
+#[derive(Error, Debug, Clone, Copy, PartialEq)]
+pub enum CharacterError {
+    #[error("a character needs an identifier")]
+    EmptyIdentifier,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 /// Tracks a character reference inside a record string.
 pub struct CharacterReference {
@@ -266,12 +535,13 @@ pub struct CharacterReference {
 
 pub trait CharacterStore {
     fn get_character(&self, id: CharacterId) -> Option<Character>;
-    fn list_characters(&self) -> Vec<Character>;
+    fn list_characters(&self, include_archived: bool) -> Vec<Character>;
     fn lookup_character(&self, search: &str) -> Option<Character>;
-    fn create_character(&self) -> Character;
+    fn lookup_character_mut(&mut self, search: &str) -> Option<&mut Character>;
+    fn create_character(&mut self, ident: &str) -> Character;
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct LocationId(pub Uuid);
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -298,9 +568,81 @@ pub trait LocationStore {
 
 #[cfg(test)]
 mod test {
-    use crate::character::{HealthStatus, Hitpoints};
+    use std::convert::Infallible;
+
+    use crate::character::{
+        Character, CharacterError, Class, DeathSaves, Fighter, Health, HealthStatus, Hitpoints,
+        Race, Warlock,
+    };
     use test_case::test_case;
 
+    #[test]
+    pub fn reads_back_fields() {
+        let character = Character::new(
+            "gimli".to_string(),
+            Some("Gimli, son of Glóin".to_string()),
+            Some(12),
+            Some(Race::Dwarf),
+        )
+        .expect("valid identifier");
+
+        assert_eq!(character.identifier, "gimli");
+        assert_eq!(character.name(), "Gimli, son of Glóin");
+        assert_eq!(character.race(), Race::Dwarf);
+        assert_eq!(
+            character.health().status(),
+            HealthStatus::Alive(Hitpoints {
+                current: 12,
+                bonus: 0
+            })
+        );
+        assert!(character.classes().iter().all(|(c, _)| c.is_none()));
+        assert_eq!(character.description().backstory(), None);
+        assert!(!character.inspiration());
+    }
+
+    #[test]
+    pub fn defaults_name_to_identifier() {
+        let character =
+            Character::new("legolas".to_string(), None, None, None).expect("valid identifier");
+        assert_eq!(character.name(), "legolas");
+        assert_eq!(character.race(), Race::Unknown);
+    }
+
+    #[test]
+    pub fn levels_up_into_free_class_slots() {
+        let mut character =
+            Character::new("elrond".to_string(), None, None, None).expect("valid identifier");
+        let champion = Class::Fighter(Fighter::Champion);
+        let fiend = Class::Warlock(Warlock::Fiend);
+
+        character.level_up(champion).expect("has a class slot");
+        character.level_up(fiend).expect("has a class slot");
+        character.level_up(champion).expect("has the class");
+        assert_eq!(character.classes()[0], (Some(champion), 2));
+        assert_eq!(character.classes()[1], (Some(fiend), 1));
+
+        character
+            .level_up(Class::Fighter(Fighter::BattleMaster))
+            .expect("has a class slot");
+        character
+            .level_up(Class::Warlock(Warlock::GreatOldOne))
+            .expect("has a class slot");
+        assert!(character
+            .level_up(Class::Warlock(Warlock::ArchFey))
+            .is_err());
+        assert_eq!(character.level(), 5);
+    }
+
+    #[test_case("" ; "empty")]
+    #[test_case("  " ; "blank")]
+    pub fn rejects_empty_identifier(identifier: &str) {
+        assert_eq!(
+            Character::new(identifier.to_string(), None, None, None).err(),
+            Some(CharacterError::EmptyIdentifier)
+        );
+    }
+
     #[test_case(3, 5, 5, 3, 0)]
     #[test_case(10, 2, 5, 7, 0)]
     #[test_case(1, 10, 5, 1, 5)]
@@ -315,4 +657,95 @@ mod test {
             })
         );
     }
+
+    #[test_case(0, false, HealthStatus::Unconscious(DeathSaves { successes: 0, failures: 1 }) ; "one failure")]
+    #[test_case(0, true, HealthStatus::Unconscious(DeathSaves { successes: 0, failures: 2 }) ; "critical counts twice")]
+    #[test_case(1, true, HealthStatus::Dead ; "critical kills")]
+    #[test_case(2, false, HealthStatus::Dead ; "third failure kills")]
+    pub fn fails_death_saves(failures: u8, critical: bool, expected: HealthStatus) {
+        let saves = DeathSaves {
+            successes: 0,
+            failures,
+        };
+        assert_eq!(saves.damage(critical), expected);
+    }
+
+    #[test]
+    pub fn new_health_is_full() {
+        let health = Health::new(12);
+        assert_eq!(
+            health.status(),
+            HealthStatus::Alive(Hitpoints {
+                current: 12,
+                bonus: 0
+            })
+        );
+        assert!(health.full());
+    }
+
+    fn fighter(level: u8) -> Character {
+        let mut character =
+            Character::new("aragorn".to_string(), None, Some(30), None).expect("valid identifier");
+        character.classes[0] = (Some(Class::Fighter(Fighter::Champion)), level);
+        character
+    }
+
+    #[test]
+    pub fn short_rest_spends_hit_dice() {
+        let mut character = fighter(3);
+        character.damage(25, false);
+
+        let status = character
+            .short_rest(|sides| {
+                assert_eq!(sides, 10);
+                Ok::<_, Infallible>(6)
+            })
+            .expect("rolls can't fail");
+        assert_eq!(
+            status,
+            HealthStatus::Alive(Hitpoints {
+                current: 23,
+                bonus: 0
+            })
+        );
+        assert_eq!(character.hit_dice(), 0);
+
+        character.long_rest();
+        assert_eq!(character.hit_dice(), 1);
+        assert!(character.health().full());
+    }
+
+    #[test]
+    pub fn short_rest_stops_at_full_health() {
+        let mut character = fighter(3);
+        character.damage(4, false);
+
+        character
+            .short_rest(|_| Ok::<_, Infallible>(10))
+            .expect("rolls can't fail");
+        assert!(character.health().full());
+        assert_eq!(character.hit_dice(), 2);
+    }
+
+    #[test]
+    pub fn short_rest_stops_on_failed_roll() {
+        let mut character = fighter(3);
+        character.damage(25, false);
+
+        assert!(character.short_rest(|_| Err("no dice")).is_err());
+        assert_eq!(character.hit_dice(), 3);
+    }
+
+    #[test_case(3, HealthStatus::Alive(Hitpoints { current: 30, bonus: 0 }) ; "stable")]
+    #[test_case(1, HealthStatus::Unconscious(DeathSaves { successes: 1, failures: 0 }) ; "dying")]
+    pub fn long_rest_wakes_stable_characters(successes: u8, expected: HealthStatus) {
+        let mut character = fighter(1);
+        character.damage(30, false);
+        character.health.status = HealthStatus::Unconscious(DeathSaves {
+            successes,
+            failures: 0,
+        });
+
+        assert_eq!(character.long_rest(), expected);
+    }
 }
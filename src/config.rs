@@ -0,0 +1,174 @@
+//! Defaults for the command line, read from a `.holding/config` file.
+
+use std::{
+    collections::HashMap,
+    env,
+    fs::File,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use holding_kronos::calendar::Calendar;
+use serde::Deserialize;
+
+/// The settings that can be supplied by a config file. Anything
+/// passed on the command line takes precedence over these.
+///
+/// The file is yaml, just like the world itself:
+///
+/// ```yaml
+/// author: gandalf
+/// color: never
+/// path: ~/worlds/middle-earth
+/// calendar: ~/calendars/shire-reckoning.yaml
+/// scripts:
+///   loot: ~/scripts/roll-loot.py
+/// ```
+///
+/// Paths may start with `~` for the home directory, and relative
+/// paths are relative to the folder the config file is in. Scripts
+/// given by name alone are looked for on the `PATH` instead.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// The author to stamp on new records.
+    pub author: Option<String>,
+
+    /// When to color the output.
+    pub color: ColorMode,
+
+    /// The world to use when no path is given.
+    pub path: Option<PathBuf>,
+
+    /// A calendar file to use for new worlds.
+    pub calendar: Option<PathBuf>,
+
+    /// Programs that can be run against the world
+    /// with `holding run`, by name.
+    pub scripts: HashMap<String, PathBuf>,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorMode {
+    /// Color the output only when writing to a terminal.
+    #[default]
+    Auto,
+    /// Always color the output.
+    Always,
+    /// Never color the output.
+    Never,
+}
+
+impl Config {
+    /// Finds the config for the current directory, falling
+    /// back to the defaults if there is none.
+    pub fn load() -> Result<Self> {
+        let dir = env::current_dir()?;
+        Self::find(&dir)
+    }
+
+    /// Looks for a `.holding/config` in a directory and then each
+    /// of its ancestors, and finally in the home directory.
+    pub fn find(dir: &Path) -> Result<Self> {
+        let home = env::var_os("HOME").map(PathBuf::from);
+        let candidate = dir
+            .ancestors()
+            .map(Path::to_path_buf)
+            .chain(home)
+            .map(|d| d.join(".holding").join("config"))
+            .find(|p| p.is_file());
+
+        match candidate {
+            Some(path) => Self::read(&path),
+            None => Ok(Self::default()),
+        }
+    }
+
+    fn read(path: &Path) -> Result<Self> {
+        let f = File::open(path)
+            .with_context(|| format!("Couldn't open config at {}.", path.display()))?;
+        let config: Self = serde_yaml::from_reader(f)
+            .with_context(|| format!("The config at {} is corrupted.", path.display()))?;
+
+        let dir = path.parent().unwrap_or_else(|| Path::new("."));
+        Ok(Self {
+            path: config.path.map(|p| resolve(dir, &p)),
+            calendar: config.calendar.map(|p| resolve(dir, &p)),
+            scripts: config
+                .scripts
+                .into_iter()
+                // bare program names are left to be found on the PATH
+                .map(|(name, p)| match p.components().count() {
+                    1 => (name, p),
+                    _ => (name, resolve(dir, &p)),
+                })
+                .collect(),
+            ..config
+        })
+    }
+
+    /// Reads the calendar that new worlds should use.
+    pub fn calendar(&self) -> Result<Calendar> {
+        match &self.calendar {
+            Some(path) => {
+                let f = File::open(path)
+                    .with_context(|| format!("Couldn't open calendar at {}.", path.display()))?;
+                serde_yaml::from_reader(f)
+                    .with_context(|| format!("{} is not a valid calendar.", path.display()))
+            }
+            None => Ok(Calendar::default()),
+        }
+    }
+}
+
+/// Expands a leading `~` to the home directory, and makes relative
+/// paths relative to some directory rather than wherever holding
+/// happens to be run from.
+fn resolve(dir: &Path, path: &Path) -> PathBuf {
+    let home = env::var_os("HOME").map(PathBuf::from);
+    match (path.strip_prefix("~"), home) {
+        (Ok(rest), Some(home)) => home.join(rest),
+        _ => dir.join(path),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{env, fs, path::PathBuf};
+
+    use holding_kronos::calendar::Calendar;
+
+    use super::Config;
+    use crate::testing::scratch_dir;
+
+    #[test]
+    pub fn resolves_paths() {
+        let dir = scratch_dir();
+        let holding = dir.join(".holding");
+        fs::create_dir_all(holding.join("calendars")).expect("can create dir");
+        fs::write(
+            holding.join("config"),
+            "path: ~/worlds/middle-earth\ncalendar: calendars/shire.yaml\n\
+             scripts:\n  loot: scripts/loot.sh\n  roll: roll\n",
+        )
+        .expect("can write config");
+        fs::write(
+            holding.join("calendars").join("shire.yaml"),
+            serde_yaml::to_string(&Calendar::default()).expect("serializes"),
+        )
+        .expect("can write calendar");
+
+        let config = Config::find(&dir).expect("config is valid");
+        let home = env::var_os("HOME").map(PathBuf::from).expect("home is set");
+        assert_eq!(config.path, Some(home.join("worlds").join("middle-earth")));
+        assert_eq!(
+            config.calendar,
+            Some(holding.join("calendars").join("shire.yaml"))
+        );
+        assert_eq!(
+            config.calendar().expect("calendar reads"),
+            Calendar::default()
+        );
+    }
+}
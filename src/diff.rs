@@ -0,0 +1,125 @@
+//! Comparisons between two versions of a world.
+
+use std::{
+    collections::HashMap,
+    fmt::{Display, Formatter, Result as FmtResult},
+    hash::Hash,
+};
+
+use holding_kronos::datetime::Duration;
+use serde::Serialize;
+
+/// The changes between two versions of a world, such
+/// as a backup and the world after a session.
+#[derive(Debug, Default, PartialEq)]
+pub struct WorldDiff {
+    pub records: Changes,
+    pub characters: Changes,
+    pub locations: Changes,
+    pub bodies: Changes,
+
+    /// How far time moved, in seconds. This
+    /// is negative if time went backwards.
+    pub elapsed: i64,
+
+    /// How far time moved, in the units of the newer calendar.
+    pub duration: Duration,
+}
+
+/// The things that were added, removed, or edited in one
+/// part of a world, each described by a short label.
+#[derive(Debug, Default, PartialEq)]
+pub struct Changes {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub edited: Vec<String>,
+}
+
+impl Changes {
+    /// Compares two lists of things that share an id. A thing is
+    /// edited if it is in both lists but serializes differently.
+    pub fn between<T, K>(
+        before: &[T],
+        after: &[T],
+        id: impl Fn(&T) -> K,
+        label: impl Fn(&T) -> String,
+    ) -> Self
+    where
+        T: Serialize,
+        K: Eq + Hash,
+    {
+        let old: HashMap<K, &T> = before.iter().map(|t| (id(t), t)).collect();
+        let new: HashMap<K, &T> = after.iter().map(|t| (id(t), t)).collect();
+
+        let mut changes = Self::default();
+        for t in after {
+            match old.get(&id(t)) {
+                None => changes.added.push(label(t)),
+                Some(prev) if serde_yaml::to_string(prev).ok() != serde_yaml::to_string(t).ok() => {
+                    changes.edited.push(label(t))
+                }
+                Some(_) => {}
+            }
+        }
+        changes.removed = before
+            .iter()
+            .filter(|t| !new.contains_key(&id(t)))
+            .map(label)
+            .collect();
+
+        changes
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.edited.is_empty()
+    }
+}
+
+impl WorldDiff {
+    pub fn is_empty(&self) -> bool {
+        self.elapsed == 0
+            && [
+                &self.records,
+                &self.characters,
+                &self.locations,
+                &self.bodies,
+            ]
+            .iter()
+            .all(|c| c.is_empty())
+    }
+}
+
+impl Display for WorldDiff {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        if self.is_empty() {
+            return write!(f, "Nothing has changed.");
+        }
+
+        match self.elapsed {
+            0 => writeln!(f, "No time has passed.")?,
+            e if e > 0 => writeln!(f, "Time moved forward by {}.", self.duration)?,
+            _ => writeln!(f, "Time moved back by {}.", self.duration)?,
+        }
+
+        let sections = [
+            ("records", &self.records),
+            ("characters", &self.characters),
+            ("locations", &self.locations),
+            ("bodies", &self.bodies),
+        ];
+        for (name, changes) in sections.iter().filter(|(_, c)| !c.is_empty()) {
+            writeln!(f, "\n{}:", name)?;
+            for added in &changes.added {
+                writeln!(f, "+ {}", added)?;
+            }
+            for removed in &changes.removed {
+                writeln!(f, "- {}", removed)?;
+            }
+            for edited in &changes.edited {
+                writeln!(f, "~ {}", edited)?;
+            }
+        }
+
+        Ok(())
+    }
+}
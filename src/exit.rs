@@ -0,0 +1,135 @@
+//! exit: Maps errors onto process exit codes so that
+//! scripts can tell what went wrong without reading
+//! the message.
+
+use holding_kronos::calendar::ParseDateTimeError;
+
+use crate::{persistence::LoadError, world::WorldError};
+
+/// The broad categories of failure, each with its own exit code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// Anything not covered below.
+    Other,
+    /// There is no world at the given path.
+    NotFound,
+    /// The world file could not be read.
+    Corrupt,
+    /// The world was read but is inconsistent.
+    Invalid,
+    /// A date or duration could not be parsed.
+    Parse,
+}
+
+impl ErrorKind {
+    /// Finds the category of an error by looking
+    /// through its chain of causes.
+    pub fn of(error: &anyhow::Error) -> Self {
+        error
+            .chain()
+            .find_map(|cause| {
+                if let Some(e) = cause.downcast_ref::<LoadError>() {
+                    Some(match e {
+                        LoadError::NotFound(..) => ErrorKind::NotFound,
+                        LoadError::Unreadable(..) => ErrorKind::Other,
+                        LoadError::Corrupt(_) => ErrorKind::Corrupt,
+                    })
+                } else if cause.is::<WorldError>() {
+                    Some(ErrorKind::Invalid)
+                } else if cause.is::<ParseDateTimeError>() {
+                    Some(ErrorKind::Parse)
+                } else {
+                    None
+                }
+            })
+            .unwrap_or(ErrorKind::Other)
+    }
+
+    /// The process exit code for this kind of error.
+    pub fn code(self) -> i32 {
+        match self {
+            ErrorKind::Other => 1,
+            ErrorKind::NotFound => 2,
+            ErrorKind::Corrupt => 3,
+            ErrorKind::Invalid => 4,
+            ErrorKind::Parse => 5,
+        }
+    }
+
+    /// A short, stable name for this kind of error.
+    pub fn name(self) -> &'static str {
+        match self {
+            ErrorKind::Other => "other",
+            ErrorKind::NotFound => "world-not-found",
+            ErrorKind::Corrupt => "corrupt",
+            ErrorKind::Invalid => "validation-failed",
+            ErrorKind::Parse => "parse-failed",
+        }
+    }
+}
+
+/// Renders an error as a single line of JSON.
+pub fn to_json(error: &anyhow::Error) -> String {
+    let kind = ErrorKind::of(error);
+    format!(
+        r#"{{"error":"{}","code":{},"message":"{}"}}"#,
+        kind.name(),
+        kind.code(),
+        escape(&format!("{:#}", error))
+    )
+}
+
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{persistence::load_world, testing::scratch_dir};
+
+    #[test]
+    fn missing_world_exits_with_not_found() {
+        let dir = scratch_dir();
+        let error = load_world(&dir.join("nowhere"))
+            .err()
+            .expect("world should not exist");
+
+        assert_eq!(ErrorKind::of(&error), ErrorKind::NotFound);
+        assert_eq!(ErrorKind::of(&error).code(), 2);
+        assert!(error.chain().any(|cause| cause.is::<std::io::Error>()));
+    }
+
+    #[test]
+    fn unreadable_world_is_not_missing() {
+        let file = scratch_dir().join("notes.txt");
+        std::fs::write(&file, "not a folder").expect("can write");
+        let error = load_world(&file.join("world.yaml"))
+            .err()
+            .expect("world can't be read");
+
+        assert_eq!(ErrorKind::of(&error), ErrorKind::Other);
+        assert!(error.chain().any(|cause| cause.is::<std::io::Error>()));
+    }
+
+    #[test]
+    fn escapes_json_messages() {
+        let error = anyhow::anyhow!("a \"quoted\"\nline");
+        assert_eq!(
+            to_json(&error),
+            r#"{"error":"other","code":1,"message":"a \"quoted\"\nline"}"#
+        );
+    }
+}
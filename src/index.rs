@@ -0,0 +1,98 @@
+//! Lookups from characters and locations to the records mentioning them.
+
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    hash::Hash,
+};
+
+use crate::{
+    character::{CharacterId, LocationId},
+    record::RawRecord,
+};
+
+/// A lazily-built index over the records of a world, so that
+/// reference queries are proportional to their results.
+///
+/// The index is built on first use and thrown away whenever the
+/// records change. As a safeguard against records being pushed
+/// directly, it is also rebuilt if the number of records differs
+/// from when it was built.
+#[derive(Clone, Default)]
+pub struct RecordIndex {
+    index: RefCell<Option<Index>>,
+    builds: Cell<usize>,
+}
+
+#[derive(Clone)]
+struct Index {
+    len: usize,
+    characters: HashMap<CharacterId, Vec<usize>>,
+    locations: HashMap<LocationId, Vec<usize>>,
+}
+
+impl Index {
+    fn build(records: &[RawRecord]) -> Self {
+        let mut characters: HashMap<_, Vec<_>> = HashMap::new();
+        let mut locations: HashMap<_, Vec<_>> = HashMap::new();
+
+        for (position, record) in records.iter().enumerate() {
+            for id in record.chars.iter().map(|c| c.id) {
+                insert(&mut characters, id, position);
+            }
+            for id in record.locs.iter().map(|l| l.id) {
+                insert(&mut locations, id, position);
+            }
+        }
+
+        Self {
+            len: records.len(),
+            characters,
+            locations,
+        }
+    }
+}
+
+/// Adds a position to an entry, skipping repeated
+/// mentions of the same thing in a single record.
+fn insert<K: Eq + Hash>(map: &mut HashMap<K, Vec<usize>>, key: K, position: usize) {
+    let positions = map.entry(key).or_default();
+    if positions.last() != Some(&position) {
+        positions.push(position);
+    }
+}
+
+impl RecordIndex {
+    /// Gets the positions of the records mentioning a character.
+    pub fn character(&self, records: &[RawRecord], id: CharacterId) -> Vec<usize> {
+        self.with_index(records, |i| i.characters.get(&id).cloned())
+            .unwrap_or_default()
+    }
+
+    /// Gets the positions of the records mentioning a location.
+    pub fn location(&self, records: &[RawRecord], id: LocationId) -> Vec<usize> {
+        self.with_index(records, |i| i.locations.get(&id).cloned())
+            .unwrap_or_default()
+    }
+
+    /// Throws away the index, to be rebuilt on next use.
+    pub fn invalidate(&mut self) {
+        self.index.get_mut().take();
+    }
+
+    /// The number of times the index has been built.
+    #[cfg(test)]
+    pub fn builds(&self) -> usize {
+        self.builds.get()
+    }
+
+    fn with_index<T>(&self, records: &[RawRecord], f: impl FnOnce(&Index) -> T) -> T {
+        let mut index = self.index.borrow_mut();
+        if !matches!(index.as_ref(), Some(i) if i.len == records.len()) {
+            *index = Some(Index::build(records));
+            self.builds.set(self.builds.get() + 1);
+        }
+
+        f(index.as_ref().expect("We just built it"))
+    }
+}
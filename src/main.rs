@@ -8,40 +8,64 @@
 )]
 #![forbid(unsafe_code)]
 
+use std::{io, path::Path, process};
+
 use anyhow::Result;
 use clap::Clap;
+use holding_color::colored::control;
 use human_panic::setup_panic;
 
-use opts::Command;
+use config::Config;
+use exit::ErrorKind;
+use persistence::Store;
 
 mod character;
+mod config;
+mod diff;
+mod exit;
+mod index;
 mod opts;
 mod persistence;
 mod record;
+#[cfg(test)]
+mod testing;
 mod world;
 
 pub use opts::Opts;
 
-fn main() -> Result<()> {
+fn main() {
     setup_panic!();
 
     let opts: Opts = Opts::parse();
-    let path = opts.path.unwrap_or_else(|| ".".into());
-
-    match opts.command {
-        Command::Dice(d) => d.run()?,
-        #[cfg(feature = "sound")]
-        Command::Sound(s) => s.run()?,
-        Command::Now(n) => n.run(&path)?,
-        Command::Time(c) => c.run(&path)?,
-        Command::Planetarium(b) => b.run(&path)?,
-        Command::Record(r) => r.run(&path)?,
-        Command::New(n) => n.run(&path)?,
-        Command::Weather(w) => w.run(&path)?,
-        Command::Completion(c) => c.run()?,
-        Command::History(r) => r.run(&path)?,
-        Command::Characters(c) => c.run(&path)?,
-    };
-
-    Ok(())
+    let (quiet, json_errors) = (opts.quiet, opts.json_errors);
+
+    if let Err(error) = run(opts) {
+        if json_errors {
+            eprintln!("{}", exit::to_json(&error));
+        } else if !quiet {
+            eprintln!("Error: {:?}", error);
+        }
+        process::exit(ErrorKind::of(&error).code());
+    }
+}
+
+fn run(opts: Opts) -> Result<()> {
+    // a broken config shouldn't stop commands that don't need it, such
+    // as generating completions or fixing the config, from running.
+    let config = Config::load().unwrap_or_else(|error| {
+        if !opts.quiet {
+            eprintln!("Warning: {:?}", error);
+        }
+        Config::default()
+    });
+    control::set_override(opts.use_color(atty::is(atty::Stream::Stdout), &config));
+
+    let path = opts.world_path(config.path.as_deref().unwrap_or_else(|| Path::new(".")));
+
+    opts.command.run(
+        &mut Store::default(),
+        &path,
+        &config,
+        &mut io::stdin().lock(),
+    )
 }
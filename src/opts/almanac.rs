@@ -0,0 +1,212 @@
+use std::{collections::HashSet, path::Path};
+
+use anyhow::Result;
+use clap::Clap;
+use holding_kronos::calendar::{
+    traits::{DayCycle, YearCycle},
+    Calendar,
+};
+use itertools::Itertools;
+
+use crate::{persistence::Store, world::World};
+
+/// Consult the calendar of this world.
+#[derive(Clap)]
+pub enum Almanac {
+    /// Prints the months, weeks, and days that make up the calendar.
+    Show,
+
+    /// Prints a grid of the days in a month, marking those with records.
+    Month { year: i64, month: u32 },
+}
+
+impl Almanac {
+    pub fn run(&self, store: &Store, path: &Path) -> Result<()> {
+        let world = store.load_world(path)?;
+
+        match self {
+            Almanac::Show => println!("{}", describe(&world.calendar)),
+            Almanac::Month { year, month } => println!("{}", month_grid(&world, *year, *month)?),
+        }
+
+        Ok(())
+    }
+}
+
+/// Renders a readable summary of a calendar.
+fn describe(calendar: &Calendar) -> String {
+    let months = calendar
+        .months()
+        .iter()
+        .enumerate()
+        .map(|(i, m)| format!("{:>3}. {} ({} days)", i + 1, m.name, m.days))
+        .join("\n");
+
+    let week_days = calendar
+        .week_days()
+        .iter()
+        .enumerate()
+        .map(|(i, d)| format!("{:>3}. {}", i + 1, d.name))
+        .join("\n");
+
+    let cycles = calendar
+        .cycles()
+        .iter()
+        .map(|(name, cycle)| {
+            format!(
+                "The {} cycle runs alongside: {}.",
+                name,
+                cycle.iter().map(|d| &d.name).join(", ")
+            )
+        })
+        .join("\n");
+
+    let eras = calendar
+        .eras()
+        .iter()
+        .map(|era| match (era.start_year(), era.end_year()) {
+            (Some(start), Some(end)) => format!("  - {}, from {} to {}", era.name(), start, end),
+            (Some(start), None) => format!("  - {}, since {}", era.name(), start),
+            (None, Some(end)) => format!("  - {}, until {}", era.name(), end),
+            (None, None) => format!("  - {}, for all time", era.name()),
+        })
+        .join("\n");
+
+    let mut out = format!(
+        "A year has {} days over {} months:\n{}\n\nA week has {} days:\n{}\n\nA day has {} hours of {} minutes of {} seconds.",
+        calendar.days_in_year(),
+        calendar.months_in_year(),
+        months,
+        calendar.week_days().len(),
+        week_days,
+        calendar.hours_in_day(),
+        calendar.minutes_in_hour(),
+        calendar.seconds_in_minute(),
+    );
+
+    if !cycles.is_empty() {
+        out.push_str("\n\n");
+        out.push_str(&cycles);
+    }
+
+    if !eras.is_empty() {
+        out.push_str("\n\nYears are counted in eras:\n");
+        out.push_str(&eras);
+    }
+
+    out
+}
+
+/// Renders the days of a month as a grid with a column per week
+/// day, marking days that have records with an asterisk.
+fn month_grid(world: &World, year: i64, month: u32) -> Result<String> {
+    let calendar = &world.calendar;
+    let first = calendar.first_weekday_of_month(year, month)?;
+    let days = calendar.weekdays_in_month(year, month)?.len() as u32;
+    let week_length = calendar.week_days().len();
+
+    let recorded: HashSet<u32> = world
+        .records
+        .iter()
+        .filter(|r| r.date.date.year == year && r.date.date.month + 1 == month)
+        .map(|r| r.date.date.day + 1)
+        .collect();
+
+    let header = calendar
+        .week_days()
+        .iter()
+        .map(|d| format!("{:>3} ", d.name.chars().take(3).collect::<String>()))
+        .join("");
+
+    let cells = (1..first)
+        .map(|_| "    ".to_string())
+        .chain((1..=days).map(|day| {
+            let mark = if recorded.contains(&day) { '*' } else { ' ' };
+            format!("{:>3}{}", day, mark)
+        }))
+        .chunks(week_length)
+        .into_iter()
+        .map(|mut week| week.join("").trim_end().to_string())
+        .join("\n");
+
+    Ok(format!(
+        "{} {}\n{}\n{}",
+        calendar.months()[month as usize - 1].name,
+        year,
+        header.trim_end(),
+        cells
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use holding_kronos::calendar::{Calendar, Era};
+
+    use crate::{
+        persistence::{load_world, Store},
+        testing::scratch_world,
+        world::World,
+    };
+
+    use super::{describe, month_grid, Almanac};
+
+    #[test]
+    pub fn shows_default_calendar() {
+        let path = scratch_world(&World::default());
+        Almanac::Show
+            .run(&Store::default(), &path)
+            .expect("can show calendar");
+
+        let world = load_world(&path).expect("world loads");
+        let summary = describe(&world.calendar);
+
+        assert!(summary.contains("A year has 365 days over 12 months"));
+        assert!(summary.contains(" 12. December (31 days)"));
+        assert!(summary.contains("A week has 7 days"));
+        assert!(summary.contains("  7. Sunday"));
+        assert!(summary.contains("24 hours of 60 minutes of 60 seconds"));
+        assert!(summary.ends_with(
+            "Years are counted in eras:\n  \
+             - Before Common Era, until -1\n  \
+             - Common Era, since 0"
+        ));
+    }
+
+    #[test]
+    pub fn lists_eras() {
+        let mut calendar = Calendar::default();
+        calendar.add_era(Era::new("First Age".to_string(), None, Some(590)));
+        calendar.add_era(Era::new("Third Age".to_string(), Some(1), Some(3021)));
+        calendar.add_era(Era::new("Fourth Age".to_string(), Some(3022), None));
+
+        let summary = describe(&calendar);
+        assert!(summary.ends_with(
+            "  - Common Era, since 0\n  \
+             - First Age, until 590\n  \
+             - Third Age, from 1 to 3021\n  \
+             - Fourth Age, since 3022"
+        ));
+    }
+
+    #[test]
+    pub fn renders_month_grid() {
+        let mut world = World::default();
+        world.update_time("2d").expect("can step");
+        world.add_record("The party arrived at #waterdeep".to_string());
+        let path = scratch_world(&world);
+
+        Almanac::Month { year: 1, month: 1 }
+            .run(&Store::default(), &path)
+            .expect("can render month");
+
+        let grid = month_grid(&world, 1, 1).expect("valid month");
+        let lines: Vec<_> = grid.lines().collect();
+
+        assert_eq!(lines[0], "January 1");
+        assert!(lines[1].starts_with("Mon Tue"));
+        assert!(lines[2].starts_with("  1   2   3*  4"));
+        assert_eq!(lines.len(), 2 + 5);
+        assert!(lines[6].starts_with(" 29  30  31"));
+        assert!(month_grid(&world, 1, 13).is_err());
+    }
+}
@@ -1,10 +1,23 @@
-use std::path::Path;
+use std::{
+    io::{self, BufRead, Write},
+    path::Path,
+};
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use clap::Clap;
+use holding_color::Color;
+use holding_kronos::{
+    calendar::traits::ConvertTime,
+    units::{Days, Seconds},
+};
+use holding_solar::{CelestialBody, Kelvin, PlanetId, PlanetStore};
 use itertools::Itertools;
+use uuid::Uuid;
 
-use crate::persistence::load_world;
+use crate::{
+    persistence::{read_world, Store},
+    world::World,
+};
 
 /// Reveal information about celestial bodies.
 #[derive(Clap)]
@@ -13,31 +26,473 @@ pub enum Planetarium {
     List,
 
     /// Adds a new celestial object to this world.
-    Add,
+    Add(Add),
 
     /// Eradicates a celestial body from this world.
     Delete,
+
+    /// Checks that the bodies in this world are consistent with its calendar.
+    Validate,
+
+    /// Describes a single celestial body in detail.
+    Show(Show),
+
+    /// Prints how far each body is from the one it orbits right now.
+    Distances,
+
+    /// Moves the party to another body, so the sky is seen from there.
+    Observe(Observe),
+}
+
+#[derive(Clap)]
+pub struct Show {
+    /// The name or id of the body, such as Moon.
+    body: String,
+}
+
+#[derive(Clap)]
+pub struct Observe {
+    /// The body the party is on, such as Moon. Leave
+    /// this out to return to the home planet.
+    body: Option<String>,
 }
 
 impl Planetarium {
-    pub fn run(&self, path: &Path) -> Result<()> {
+    pub fn run(&self, store: &mut Store, path: &Path, input: &mut dyn BufRead) -> Result<()> {
         match self {
             Planetarium::List => {
-                let world = load_world(path)?;
+                let world = store.load_world(path)?;
                 println!("Known bodies:");
                 println!(
                     "{}",
                     world
                         .bodies
                         .iter()
-                        .map(|b| format!("- {}", b.name))
+                        .map(|b| match b.surface_gravity() {
+                            Some(g) => format!("- {} (gravity {:.1}× Earth)", b.name, g),
+                            None => format!("- {}", b.name),
+                        })
                         .join("\n")
                 );
-
                 Ok(())
             }
-            Planetarium::Add => todo!(),
+            Planetarium::Add(add) => add.run(store, path, input),
             Planetarium::Delete => todo!(),
+            Planetarium::Validate => {
+                let world = read_world(path)?;
+                let issues = world.issues();
+                if issues.is_empty() {
+                    println!("The heavens are in order.");
+                    return Ok(());
+                }
+
+                println!("Something is amiss in the heavens:");
+                println!("{}", issues.iter().map(|i| format!("- {}", i)).join("\n"));
+                Err(anyhow!("Found {} issue(s) in the world.", issues.len()))
+            }
+            Planetarium::Distances => {
+                let world = store.load_world(path)?;
+                for (name, distance) in distances(&world) {
+                    match distance {
+                        Some(Some(meters)) => println!("- {}: {}", name, format_distance(meters)),
+                        Some(None) => println!("- {}: unknown", name),
+                        None => println!("- {}: -", name),
+                    }
+                }
+                Ok(())
+            }
+            Planetarium::Show(Show { body }) => {
+                let world = store.load_world(path)?;
+                let body = world
+                    .get_planet(find(&world, body)?)
+                    .expect("We just found it");
+                println!("{}", show(&world, body));
+                Ok(())
+            }
+            Planetarium::Observe(Observe { body }) => {
+                let mut world = store.load_world(path)?;
+                world.observer_body = match body {
+                    Some(body) => Some(find(&world, body)?).filter(|id| *id != world.home_planet),
+                    None => None,
+                };
+                let observer = world
+                    .get_planet(world.observer())
+                    .map(|b| b.name.clone())
+                    .unwrap_or_default();
+                store.save_world(path, &world)?;
+                println!("The party is now on {}.", observer);
+                Ok(())
+            }
         }
     }
 }
+
+/// Describes a body along with what is known about it.
+fn show(world: &World, body: &CelestialBody) -> String {
+    let mut lines = vec![
+        format!("{} {}.", body.name, body.describe()),
+        format!("- Temperature: {}K", body.temperature.0),
+    ];
+    if let Some(parent) = body.orbit.and_then(|o| world.get_planet(o.parent)) {
+        lines.push(format!("- Orbits: {}", parent.name));
+    }
+    if let Some(g) = body.surface_gravity() {
+        lines.push(format!("- Gravity: {:.1}× Earth", g));
+    }
+    if let Some(rings) = body.rings {
+        lines.push(format!(
+            "- Rings: {:.0} to {:.0} km",
+            rings.inner / 1000.0,
+            rings.outer / 1000.0
+        ));
+    }
+    lines.join("\n")
+}
+
+/// Gets the current distance of each body from its parent in meters.
+/// This is None for bodies that orbit nothing or give off light, and
+/// Some(None) if the mass of the parent, and so the distance, is unknown.
+fn distances(world: &World) -> Vec<(&str, Option<Option<f64>>)> {
+    let now = world.time.into_datetime(&world.calendar);
+    world
+        .bodies
+        .iter()
+        .map(|b| {
+            let distance = b
+                .orbit
+                .filter(|_| !b.is_luminous())
+                .map(|orbit| orbit.get_distance_meters(world, now.seconds_modulo(orbit.period)));
+            (b.name.as_str(), distance)
+        })
+        .collect()
+}
+
+/// Formats a distance in kilometers, or in astronomical
+/// units if it is at least a tenth of one.
+fn format_distance(meters: f64) -> String {
+    const AU: f64 = 1.496e11;
+    if meters >= AU / 10.0 {
+        format!("{:.2} AU", meters / AU)
+    } else {
+        format!("{:.0} km", meters / 1000.0)
+    }
+}
+
+#[derive(Clap)]
+pub struct Add {
+    /// The name of the body, such as Mars.
+    #[clap(required_unless_present = "interactive")]
+    name: Option<String>,
+
+    /// The temperature of the body, in kelvin.
+    #[clap(long, default_value = "290")]
+    temperature: i32,
+
+    /// The time it takes for the body to turn once, in hours.
+    #[clap(long, default_value = "24")]
+    rotation: u32,
+
+    /// The color of the body.
+    #[clap(long, default_value = "white")]
+    color: Color,
+
+    /// The body that this one orbits.
+    #[clap(long, requires = "period")]
+    parent: Option<String>,
+
+    /// The time it takes to orbit the parent, in days.
+    #[clap(long, requires = "parent")]
+    period: Option<u32>,
+
+    /// Prompt for each property in turn rather than using flags.
+    #[clap(long)]
+    interactive: bool,
+}
+
+/// The validated properties of a new body.
+struct NewBody {
+    name: String,
+    temperature: Kelvin,
+    rotation: Seconds,
+    color: Color,
+    orbit: Option<(PlanetId, Days)>,
+}
+
+impl Add {
+    pub fn run(&self, store: &mut Store, path: &Path, input: &mut dyn BufRead) -> Result<()> {
+        let mut world = store.load_world(path)?;
+
+        let body = if self.interactive {
+            ask(&world, input)?
+        } else {
+            NewBody {
+                name: name(&world, self.name.as_deref().unwrap_or_default())?,
+                temperature: temperature(self.temperature)?,
+                rotation: rotation(&world, self.rotation)?,
+                color: self.color,
+                orbit: match (&self.parent, self.period) {
+                    (Some(parent_name), Some(days)) => {
+                        parent(&world, parent_name)?.map(|p| (p, Days(days)))
+                    }
+                    _ => None,
+                },
+            }
+        };
+
+        let id = world
+            .create_planet(body.name, body.temperature, body.rotation, body.color)
+            .id;
+        if let Some((parent, period)) = body.orbit {
+            world.add_orbit(parent, id, period);
+        }
+
+        store.save_world(path, &world)?;
+        println!("A new body has been added to the heavens.");
+        Ok(())
+    }
+}
+
+/// Prompts for each property of a new body, asking
+/// again whenever the answer is not valid.
+fn ask(world: &World, input: &mut dyn BufRead) -> Result<NewBody> {
+    let name = prompt(input, "Name", |s| name(world, s))?;
+    let temperature = prompt(input, "Temperature (kelvin)", |s| temperature(s.parse()?))?;
+    let rotation = prompt(input, "Rotational period (hours)", |s| {
+        rotation(world, s.parse()?)
+    })?;
+    let color = prompt(input, "Color", |s| Ok(s.parse::<Color>()?))?;
+    let orbit = match prompt(input, "Parent (leave empty for none)", |s| parent(world, s))? {
+        Some(parent) => Some((
+            parent,
+            prompt(input, "Orbital period (days)", |s| period(s.parse()?))?,
+        )),
+        None => None,
+    };
+
+    Ok(NewBody {
+        name,
+        temperature,
+        rotation,
+        color,
+        orbit,
+    })
+}
+
+fn prompt<T>(
+    input: &mut dyn BufRead,
+    question: &str,
+    parse: impl Fn(&str) -> Result<T>,
+) -> Result<T> {
+    loop {
+        print!("{}: ", question);
+        io::stdout().flush()?;
+
+        let mut line = String::new();
+        if input.read_line(&mut line)? == 0 {
+            return Err(anyhow!("Ran out of input while asking for {}.", question));
+        }
+
+        match parse(line.trim()) {
+            Ok(value) => return Ok(value),
+            Err(e) => println!("{}", e),
+        }
+    }
+}
+
+fn name(world: &World, name: &str) -> Result<String> {
+    if name.is_empty() {
+        Err(anyhow!("A body needs a name."))
+    } else if world.bodies.iter().any(|b| b.name == name) {
+        Err(anyhow!("There is already a body called {}.", name))
+    } else {
+        Ok(name.to_string())
+    }
+}
+
+fn temperature(kelvin: i32) -> Result<Kelvin> {
+    if kelvin > 0 {
+        Ok(Kelvin(kelvin))
+    } else {
+        Err(anyhow!("Nothing is colder than absolute zero."))
+    }
+}
+
+fn rotation(world: &World, hours: u32) -> Result<Seconds> {
+    if hours > 0 {
+        Ok(Seconds(world.calendar.hours_to_seconds(hours)))
+    } else {
+        Err(anyhow!("A body needs time to rotate."))
+    }
+}
+
+fn parent(world: &World, name: &str) -> Result<Option<PlanetId>> {
+    if name.is_empty() {
+        return Ok(None);
+    }
+    find(world, name).map(Some)
+}
+
+/// Finds a body by name or, when several
+/// share that name, by one of their ids.
+fn find(world: &World, search: &str) -> Result<PlanetId> {
+    if let Ok(id) = Uuid::parse_str(search) {
+        if let Some(body) = world.get_planet(PlanetId(id)) {
+            return Ok(body.id);
+        }
+    }
+
+    match world.find_planets(search).as_slice() {
+        [] => Err(anyhow!("There is no body called {}.", search)),
+        [body] => Ok(body.id),
+        bodies => Err(anyhow!(
+            "There are {} bodies called {}. Use one of their ids instead:\n{}",
+            bodies.len(),
+            search,
+            bodies.iter().map(|b| format!("- {}", b.id)).join("\n")
+        )),
+    }
+}
+
+fn period(days: u32) -> Result<Days> {
+    if days > 0 {
+        Ok(Days(days))
+    } else {
+        Err(anyhow!("An orbit needs time to complete."))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::Cursor;
+
+    use holding_color::Color;
+    use holding_kronos::units::Seconds;
+    use holding_solar::{Kelvin, PlanetStore};
+
+    use crate::{
+        persistence::{load_world, Store},
+        testing::scratch_world,
+        world::World,
+    };
+
+    use super::{distances, find, format_distance, Add, Planetarium};
+
+    #[test]
+    pub fn reports_ambiguous_names() {
+        let mut world = World::default();
+        let first = world
+            .create_planet("Luna".to_string(), Kelvin(250), Seconds(3600), Color::White)
+            .id;
+        let second = world
+            .create_planet("Luna".to_string(), Kelvin(200), Seconds(7200), Color::Red)
+            .id;
+
+        let ids: Vec<_> = world.find_planets("Luna").iter().map(|b| b.id).collect();
+        assert_eq!(ids, vec![first, second]);
+        assert!(world.find_planets("Phobos").is_empty());
+
+        let message = find(&world, "Luna")
+            .expect_err("name is ambiguous")
+            .to_string();
+        assert!(message.contains(&first.to_string()), "{}", message);
+        assert!(message.contains(&second.to_string()), "{}", message);
+        assert_eq!(find(&world, &second.to_string()).ok(), Some(second));
+        assert!(find(&world, "Earth").is_ok());
+    }
+
+    #[test]
+    pub fn adds_body_interactively() {
+        let path = scratch_world(&World::default());
+        let add = Add {
+            name: None,
+            temperature: 290,
+            rotation: 24,
+            color: Color::White,
+            parent: None,
+            period: None,
+            interactive: true,
+        };
+        let mut answers =
+            Cursor::new("\nEarth\nMars\ncold\n-4\n210\n24\npurple\nred\nPluto\nSun\n687\n");
+
+        add.run(&mut Store::default(), &path, &mut answers)
+            .expect("can add");
+
+        let world = load_world(&path).expect("world loads");
+        let mars = world
+            .bodies
+            .iter()
+            .find(|b| b.name == "Mars")
+            .expect("body was added");
+        let sun = world
+            .bodies
+            .iter()
+            .find(|b| b.name == "Sun")
+            .expect("sun exists");
+        assert_eq!(mars.temperature, Kelvin(210));
+        assert_eq!(mars.rotational_period, Seconds(24 * 3600));
+        assert!(matches!(mars.color, Color::Red));
+        assert_eq!(mars.orbit.map(|o| o.parent), Some(sun.id));
+        assert!(sun.children.contains(&mars.id));
+
+        let mut short = Cursor::new("Venus\n");
+        assert!(add.run(&mut Store::default(), &path, &mut short).is_err());
+    }
+
+    #[test]
+    pub fn validates_default_world() {
+        let path = scratch_world(&World::default());
+        assert!(Planetarium::Validate
+            .run(&mut Store::default(), &path, &mut Cursor::new(""))
+            .is_ok());
+    }
+
+    #[test]
+    pub fn reports_broken_world() {
+        let mut world = World::default();
+        let home = world.home_planet;
+        let earth = world.get_planet_mut(home).expect("home planet exists");
+        earth.rotational_period = Seconds(80000);
+        if let Some(orbit) = &mut earth.orbit {
+            orbit.period = Seconds(1000);
+        }
+        let path = scratch_world(&world);
+
+        assert!(Planetarium::Validate
+            .run(&mut Store::default(), &path, &mut Cursor::new(""))
+            .is_err());
+
+        let issues = world
+            .issues()
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        assert_eq!(issues.len(), 2);
+        assert!(issues[0]
+            .contains("rotational period of Earth is 0.93 days but the calendar expects 1 day"));
+        assert!(issues[1].contains("orbit of Earth"));
+        assert!(issues[1].contains("0.01 days but the calendar expects 365 days"));
+    }
+
+    #[test]
+    pub fn measures_distance_to_moon() {
+        let world = World::default();
+        let distances = distances(&world);
+
+        let moon = distances
+            .iter()
+            .find(|(name, _)| *name == "Moon")
+            .and_then(|(_, d)| *d)
+            .flatten()
+            .expect("the moon has a distance");
+        assert!(moon.is_finite());
+        assert!(format_distance(moon).ends_with(" km"));
+
+        let sun = distances.iter().find(|(name, _)| *name == "Sun");
+        assert_eq!(sun.map(|(_, d)| *d), Some(None));
+
+        let earth = distances.iter().find(|(name, _)| *name == "Earth");
+        let earth = earth.and_then(|(_, d)| *d).flatten().expect("earth orbits");
+        assert!(format_distance(earth).ends_with(" AU"));
+    }
+}
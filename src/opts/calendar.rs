@@ -3,67 +3,296 @@ use std::path::Path;
 use anyhow::Result;
 use clap::Clap;
 use holding_color::colored::Colorize;
+use holding_kronos::datetime::{Duration, Schedule};
 use itertools::Itertools;
 
-use crate::persistence::{load_world, save_world};
+use crate::{persistence::Store, record::RawRecord, world::World};
 
 /// Manipulate the very flow of time itself.
 #[derive(Clap)]
 pub enum Time {
     /// Steps forward in the flow of time.
-    Step(TimeSwitch),
+    Step(Step),
 
     /// Temporarily opens a rift to a new location in time, preserving your place.
     Jump(TimeSwitch),
 
     /// Returns to the 'canonical' time line.
     Return,
+
+    /// Adds an event that recurs, noting it each time it happens.
+    Schedule(NewSchedule),
+
+    /// Lists the scheduled events and records that are coming up.
+    Upcoming(Upcoming),
+}
+
+#[derive(Clap)]
+pub struct Upcoming {
+    /// How far ahead to look, such as 1mo.
+    #[clap(long, default_value = "1w")]
+    within: String,
+}
+
+#[derive(Clap)]
+pub struct NewSchedule {
+    /// How often the event recurs, such as 1w or 1y.
+    every: String,
+
+    /// The note to record each time, such as "The market opened in #waterdeep".
+    note: String,
+
+    /// When the event first happens, such as 2d or 1101-02-12. By
+    /// default it is now, so the first note is one interval away.
+    #[clap(long)]
+    first: Option<String>,
 }
 
 #[derive(Clap)]
 pub struct TimeSwitch {
+    /// Where to jump to, such as 1101-02-12, 3d, or -3d to go back.
+    #[clap(allow_hyphen_values = true)]
+    expr: String,
+}
+
+#[derive(Clap)]
+pub struct Step {
     expr: String,
+
+    /// Show what would happen without moving time forward.
+    #[clap(long)]
+    dry_run: bool,
+
+    /// Show how long the campaign has been running.
+    #[clap(long)]
+    elapsed: bool,
 }
 
 impl Time {
-    pub fn run(&self, path: &Path) -> Result<()> {
-        let mut world = load_world(path)?;
+    pub fn run(&self, store: &mut Store, path: &Path) -> Result<()> {
+        let mut world = store.load_world(path)?;
 
         match self {
-            Time::Step(TimeSwitch { expr }) => {
-                let prev = world.time;
-                world.update_time(expr)?;
-                println!("The time is now {}", world.time);
-
-                let events = world.records_between(prev, world.time);
-                if !events.is_empty() {
-                    println!("Things have happened:");
-                    println!(
-                        "{}",
-                        events
-                            .iter()
-                            .map(|r| format!(
-                                "- {} | {}",
-                                r.date.to_string().bright_black(),
-                                r.pretty()
-                            ))
-                            .join("\n")
-                    );
+            Time::Step(Step {
+                expr,
+                dry_run,
+                elapsed,
+            }) => {
+                println!("{}", step(&mut world, expr, *dry_run, *elapsed)?);
+                if *dry_run {
+                    return Ok(());
                 }
             }
             Time::Jump(TimeSwitch { expr }) => {
                 world.jump_time(expr)?;
                 println!("You open a rift and step through.");
-                println!("The time is now {}", world.time);
+                println!("The time is now {}", world.display_time());
             }
             Time::Return => {
                 world.return_time()?;
                 println!("You open a rift and step through.");
-                println!("You have returned to {}.", world.time)
+                println!("You have returned to {}.", world.display_time())
+            }
+            Time::Schedule(NewSchedule { every, note, first }) => {
+                let every = world.calendar.parse_duration(every)?;
+                let start = match first {
+                    Some(expr) => world
+                        .calendar
+                        .parse(expr, world.time.into_datetime(&world.calendar))?
+                        .into(),
+                    None => world.time,
+                };
+                world.add_schedule(Schedule::new(start, every), note.clone());
+                println!("This will happen every {}, starting {}.", every, start);
+            }
+            Time::Upcoming(Upcoming { within }) => {
+                let now = world.time.into_datetime(&world.calendar);
+                let until = world.calendar.parse(within, Some(now))?;
+                let upcoming = world.upcoming(until);
+                if upcoming.is_empty() {
+                    println!("Nothing is coming up before {}.", until);
+                } else {
+                    println!("Coming up:");
+                    for (date, note) in upcoming {
+                        println!("- {} | {}", date.to_string().bright_black(), note);
+                    }
+                }
+                return Ok(());
             }
         }
 
-        save_world(path, &world)?;
+        store.save_world(path, &world)?;
         Ok(())
     }
 }
+
+/// Steps time forward, describing the new time and the events
+/// that happened on the way, as `time step` prints them.
+fn step(world: &mut World, expr: &str, dry_run: bool, elapsed: bool) -> Result<String> {
+    let prev = world.time;
+    world.update_time(expr)?;
+
+    let events = world.records_between(prev, world.time);
+    let mut lines = vec![if dry_run {
+        format!("The time would be {}", world.display_time())
+    } else {
+        format!("The time is now {}", world.display_time())
+    }];
+
+    let span = world
+        .time
+        .into_datetime(&world.calendar)
+        .duration_since(&prev.into_datetime(&world.calendar))?;
+    lines.extend(describe_events(&events, span));
+
+    if elapsed {
+        lines.push(format!("The campaign has run for {}.", world.elapsed()?));
+    }
+
+    Ok(lines.join("\n"))
+}
+
+/// Lists the events that happened during a step in time,
+/// along with how long the step was.
+fn describe_events(events: &[RawRecord], span: Duration) -> Option<String> {
+    if events.is_empty() {
+        return None;
+    }
+
+    Some(format!(
+        "Things have happened in the last {}:\n{}",
+        span,
+        events.iter().map(|r| r.entry(None)).join("\n")
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use holding_kronos::datetime::{Duration, RawDateTime, Schedule};
+
+    use super::{step, NewSchedule, Step, Time, Upcoming};
+    use crate::{
+        persistence::{load_world, Store},
+        testing::scratch_world,
+        world::World,
+    };
+
+    #[test]
+    pub fn dry_run_keeps_time() {
+        let mut world = World::default();
+        world.add_record("The bandits struck at #waterdeep".to_string());
+        let start = world.time;
+        let daily = world.calendar.parse_duration("1d").expect("valid duration");
+        world.add_schedule(Schedule::new(start, daily), "The market opened".to_string());
+        let path = scratch_world(&world);
+
+        let output = step(&mut world.clone(), "2d", true, false).expect("can step");
+        assert!(output.starts_with("The time would be"), "{}", output);
+        assert!(
+            output.contains("Things have happened in the last 2d:"),
+            "{}",
+            output
+        );
+        assert!(output.contains("The market opened"), "{}", output);
+
+        Time::Step(Step {
+            expr: "2d".to_string(),
+            dry_run: true,
+            elapsed: false,
+        })
+        .run(&mut Store::default(), &path)
+        .expect("can step");
+
+        let world = load_world(&path).expect("world loads");
+        assert_eq!(world.time, start);
+        let notes: Vec<_> = world.records.iter().map(|r| r.note.as_str()).collect();
+        assert_eq!(notes, ["The bandits struck at #waterdeep"]);
+    }
+
+    #[test]
+    pub fn tracks_elapsed_time() {
+        let path = scratch_world(&World::default());
+        let step = |expr: &str| {
+            Time::Step(Step {
+                expr: expr.to_string(),
+                dry_run: false,
+                elapsed: true,
+            })
+            .run(&mut Store::default(), &path)
+            .expect("can step")
+        };
+
+        step("1d");
+        step("2h");
+
+        let world = load_world(&path).expect("world loads");
+        let elapsed = world.elapsed().expect("the campaign is short");
+        assert_eq!(
+            elapsed,
+            Duration::from_seconds(86400 + 7200, &world.calendar)
+        );
+        assert_eq!(elapsed.to_string(), "1d2h");
+    }
+
+    #[test]
+    pub fn steps_past_schedule() {
+        let path = scratch_world(&World::default());
+        Time::Schedule(NewSchedule {
+            every: "1w".to_string(),
+            note: "The market opened in #waterdeep".to_string(),
+            first: None,
+        })
+        .run(&mut Store::default(), &path)
+        .expect("can schedule");
+
+        Time::Step(Step {
+            expr: "3w".to_string(),
+            dry_run: false,
+            elapsed: false,
+        })
+        .run(&mut Store::default(), &path)
+        .expect("can step");
+
+        let world = load_world(&path).expect("world loads");
+        assert_eq!(world.schedules.len(), 1);
+        assert_eq!(world.records.len(), 3);
+    }
+
+    #[test]
+    pub fn lists_upcoming_events() {
+        let mut world = World::default();
+        let later = |world: &World, expr: &str| -> RawDateTime {
+            let now = world.time.into_datetime(&world.calendar);
+            world
+                .calendar
+                .parse(expr, Some(now))
+                .expect("valid expression")
+                .into()
+        };
+        let schedules = [
+            ("1w", world.time, "The market opened in #waterdeep"),
+            ("1y", later(&world, "10d"), "The harvest festival began"),
+        ];
+        for (every, first, note) in schedules {
+            let every = world
+                .calendar
+                .parse_duration(every)
+                .expect("valid duration");
+            world.add_schedule(Schedule::new(first, every), note.to_string());
+        }
+
+        let in_a_month = later(&world, "1mo");
+        let upcoming = world.upcoming(in_a_month.into_datetime(&world.calendar));
+        let notes: Vec<_> = upcoming.iter().map(|(_, note)| note.as_str()).collect();
+        assert_eq!(notes.len(), 5, "{:?}", notes);
+        assert_eq!(notes[1], "The harvest festival began");
+        assert!(upcoming.windows(2).all(|w| w[0].0 <= w[1].0));
+
+        let path = scratch_world(&world);
+        Time::Upcoming(Upcoming {
+            within: "1mo".to_string(),
+        })
+        .run(&mut Store::default(), &path)
+        .expect("can list upcoming events");
+    }
+}
@@ -1,18 +1,32 @@
-use std::path::Path;
+use std::{
+    cmp::Reverse,
+    fs,
+    path::{Path, PathBuf},
+};
 
-use anyhow::Result;
-use clap::Clap;
+use anyhow::{anyhow, Context, Result};
+use clap::{ArgEnum, Clap};
+use dnd_dice_roller::dice_set::DiceSet;
+use serde::Deserialize;
 
 use crate::{
-    character::CharacterStore,
-    persistence::{load_world, save_world},
+    character::{Character, CharacterId, CharacterStore, Class, HealthStatus, LocationStore, Race},
+    persistence::Store,
+    world::World,
 };
 
 /// See and change the characters in this world.
 #[derive(Clap)]
 pub enum Characters {
-    /// Show all characters alphabetically.
-    List,
+    /// Show all characters, alphabetically unless sorted otherwise.
+    List {
+        /// Include characters that have been archived.
+        #[clap(long)]
+        archived: bool,
+        /// Sort by name, level (highest first), race, or status.
+        #[clap(long, arg_enum, default_value = "name")]
+        sort: Sort,
+    },
 
     /// Shows characters that are around in the current time.
     Now,
@@ -22,6 +36,44 @@ pub enum Characters {
 
     /// Shows characters that are nearby.
     Nearby,
+
+    /// Deals damage to a character.
+    Damage(Damage),
+
+    /// Restores health to a character.
+    Heal(Heal),
+
+    /// Moves a character to a new location.
+    Move(Move),
+
+    /// Archives a character who has died or left the story.
+    Archive(Search),
+
+    /// Restores an archived character.
+    Unarchive(Search),
+
+    /// Rests, letting time pass while characters recover.
+    Rest(Rest),
+
+    /// Sets the initiative of a character, putting them in combat.
+    Initiative(Initiative),
+
+    /// Ends combat, clearing everyone's initiative.
+    EndCombat,
+
+    /// Writes down a note about a character.
+    Note(Note),
+
+    /// Adds the characters from a roster file, skipping any that exist.
+    Import(Import),
+}
+
+#[derive(ArgEnum, Clone, Copy, Debug, PartialEq)]
+pub enum Sort {
+    Name,
+    Level,
+    Race,
+    Status,
 }
 
 #[derive(Clap)]
@@ -29,21 +81,703 @@ pub struct Search {
     expr: String,
 }
 
+#[derive(Clap)]
+pub struct Damage {
+    /// The character to damage, such as @gandalf.
+    name: String,
+    /// The amount of damage to deal.
+    amount: u32,
+    /// Whether the hit was critical (counting as two failed death saves).
+    #[clap(long)]
+    critical: bool,
+}
+
+#[derive(Clap)]
+pub struct Heal {
+    /// The character to heal, such as @gandalf.
+    name: String,
+    /// The amount of health to restore.
+    amount: u32,
+}
+
+#[derive(Clap)]
+pub struct Move {
+    /// The character to move, such as @gandalf.
+    name: String,
+    /// The destination, such as #moria.
+    location: String,
+}
+
+#[derive(Clap)]
+pub struct Rest {
+    /// The character who rests, such as @gandalf.
+    #[clap(required_unless_present = "party")]
+    name: Option<String>,
+    /// Rest everyone who hasn't been archived.
+    #[clap(long, conflicts_with = "name")]
+    party: bool,
+    /// Rest for 4 hours, spending hit dice to heal.
+    #[clap(long, conflicts_with = "long", required_unless_present = "long")]
+    short: bool,
+    /// Rest for 8 hours, recovering health and hit dice.
+    #[clap(long)]
+    long: bool,
+}
+
+#[derive(Clap)]
+pub struct Note {
+    /// The character the note is about, such as @gandalf.
+    name: String,
+    /// The note, such as "Owes the party a favor".
+    text: String,
+}
+
+#[derive(Clap)]
+pub struct Initiative {
+    /// The character who rolled, such as @gandalf.
+    name: String,
+    /// The initiative they rolled.
+    #[clap(allow_hyphen_values = true)]
+    value: i32,
+}
+
+#[derive(Clap)]
+pub struct Import {
+    /// A yaml or json file with a list of characters, each with
+    /// an identifier and optionally a name, race, max_health, and
+    /// classes, such as `- class: { Fighter: Champion }, level: 3`.
+    path: PathBuf,
+}
+
+/// A character as written in a roster file.
+#[derive(Deserialize)]
+struct RosterEntry {
+    identifier: String,
+    name: Option<String>,
+    race: Option<Race>,
+    max_health: Option<u32>,
+    #[serde(default)]
+    classes: Vec<RosterClass>,
+}
+
+#[derive(Deserialize)]
+struct RosterClass {
+    class: Class,
+    level: u8,
+}
+
 impl Characters {
-    pub fn run(&self, path: &Path) -> Result<()> {
-        let world = load_world(path)?;
+    pub fn run(&self, store: &mut Store, path: &Path) -> Result<()> {
+        let mut world = store.load_world(path)?;
 
         match self {
-            Characters::List => {
-                let chars = world.list_characters();
-                println!("{:?}", chars);
+            Characters::List { archived, sort } => {
+                let chars = sorted(world.list_characters(*archived), *sort);
+                if chars.is_empty() {
+                    println!("There is nobody here.");
+                } else {
+                    print!("{}", table(&chars));
+                }
             }
             Characters::Now => todo!(),
-            Characters::Find(_) => todo!(),
+            Characters::Find(Search { expr }) => {
+                let id = find(&world, expr)?;
+                let character = world.get_character(id).expect("We just found them");
+                print!("{}", sheet(&world, &character));
+                return Ok(());
+            }
             Characters::Nearby => todo!(),
+            Characters::Damage(Damage {
+                name,
+                amount,
+                critical,
+            }) => {
+                let character = world
+                    .lookup_character_mut(identifier(name))
+                    .ok_or_else(|| anyhow!("There is no character called {}.", name))?;
+                let status = character.damage(*amount, *critical);
+                println!("{} is {}.", name, status);
+            }
+            Characters::Heal(Heal { name, amount }) => {
+                let character = world
+                    .lookup_character_mut(identifier(name))
+                    .ok_or_else(|| anyhow!("There is no character called {}.", name))?;
+                let status = character.heal(*amount);
+                println!("{} is {}.", name, status);
+            }
+            Characters::Move(Move { name, location }) => {
+                let location = location.strip_prefix('#').unwrap_or(location);
+                match world.move_character(identifier(name), location)? {
+                    Some(record) => println!("Noted at {}:\n{}", record.date, record.pretty()),
+                    None => {
+                        println!("{} is already at #{}.", name, location);
+                        return Ok(());
+                    }
+                }
+            }
+            Characters::Archive(Search { expr }) => {
+                let id = find(&world, expr)?;
+                world.archive_character(id)?;
+                println!("{} has been archived.", expr);
+            }
+            Characters::Unarchive(Search { expr }) => {
+                let id = find(&world, expr)?;
+                world.unarchive_character(id)?;
+                println!("{} has been restored.", expr);
+            }
+            Characters::Rest(Rest { name, long, .. }) => {
+                let resting: Vec<_> = match name {
+                    Some(name) => vec![identifier(name).to_string()],
+                    None => world
+                        .list_characters(false)
+                        .into_iter()
+                        .map(|c| c.identifier)
+                        .collect(),
+                };
+
+                world.update_time(if *long { "long rest" } else { "short rest" })?;
+                for ident in resting {
+                    let character = world
+                        .lookup_character_mut(&ident)
+                        .ok_or_else(|| anyhow!("There is no character called @{}.", ident))?;
+                    let status = if *long {
+                        character.long_rest()
+                    } else {
+                        character.short_rest(roll)?
+                    };
+                    println!("@{} is {}.", ident, status);
+                }
+                println!("The time is now {}", world.display_time());
+            }
+            Characters::Initiative(Initiative { name, value }) => {
+                let character = world
+                    .lookup_character_mut(identifier(name))
+                    .ok_or_else(|| anyhow!("There is no character called {}.", name))?;
+                character.set_initiative(Some(*value));
+
+                println!("The combat order is:");
+                for (i, c) in world.combat_order().iter().enumerate() {
+                    let initiative = c.initiative().unwrap_or_default();
+                    println!("{}. @{} ({})", i + 1, c.identifier, initiative);
+                }
+            }
+            Characters::EndCombat => {
+                world.end_combat();
+                println!("Combat is over.");
+            }
+            Characters::Note(Note { name, text }) => {
+                let time = world.time;
+                let character = world
+                    .lookup_character_mut(identifier(name))
+                    .ok_or_else(|| anyhow!("There is no character called {}.", name))?;
+                character.add_note(time, text.clone());
+                println!("Noted about {}.", name);
+            }
+            Characters::Import(Import { path: roster }) => {
+                let text = fs::read_to_string(roster)
+                    .with_context(|| format!("Couldn't read {}.", roster.display()))?;
+                let entries: Vec<RosterEntry> = serde_yaml::from_str(&text)
+                    .with_context(|| format!("{} is not a valid roster.", roster.display()))?;
+                for (identifier, added) in import(&mut world, entries)? {
+                    if added {
+                        println!("Added @{}.", identifier);
+                    } else {
+                        println!("Skipped @{}, who already exists.", identifier);
+                    }
+                }
+            }
         }
 
-        save_world(path, &world)?;
+        store.save_world(path, &world)?;
         Ok(())
     }
 }
+
+/// Creates the characters in a roster, returning each identifier
+/// along with whether it was added or skipped as a duplicate.
+fn import(world: &mut World, entries: Vec<RosterEntry>) -> Result<Vec<(String, bool)>> {
+    let mut imported = vec![];
+    for entry in entries {
+        let identifier = identifier(entry.identifier.trim()).to_string();
+        if identifier.is_empty() {
+            return Err(anyhow!(
+                "Every character in the roster needs an identifier."
+            ));
+        }
+        if world.lookup_character(&identifier).is_some() {
+            imported.push((identifier, false));
+            continue;
+        }
+
+        world.create_character(&identifier);
+        let character = world
+            .lookup_character_mut(&identifier)
+            .expect("We just created them");
+        if let Some(name) = entry.name {
+            character.set_name(name);
+        }
+        if let Some(race) = entry.race {
+            character.set_race(race);
+        }
+        if let Some(max_health) = entry.max_health {
+            character.set_max_health(max_health);
+        }
+        for RosterClass { class, level } in entry.classes {
+            for _ in 0..level {
+                character
+                    .level_up(class)
+                    .map_err(|_| anyhow!("@{} has too many classes.", identifier))?;
+            }
+        }
+        imported.push((identifier, true));
+    }
+
+    Ok(imported)
+}
+
+/// Gets the id of a character by reference.
+fn find(world: &World, name: &str) -> Result<CharacterId> {
+    world
+        .lookup_character(identifier(name))
+        .map(|c| c.id)
+        .ok_or_else(|| anyhow!("There is no character called {}.", name))
+}
+
+/// Describes a character, along with any notes about them.
+fn sheet(world: &World, character: &Character) -> String {
+    let location = character
+        .location()
+        .and_then(|id| world.get_location(id))
+        .map(|l| format!("#{}", l.identifier))
+        .unwrap_or_else(|| "unknown".to_string());
+
+    let mut out = format!(
+        "{} (@{})\n- Race: {:?}\n- Level: {}\n- Status: {}\n- Location: {}\n",
+        character.name(),
+        character.identifier,
+        character.race(),
+        character.level(),
+        character.health().status(),
+        location,
+    );
+    if !character.notes().is_empty() {
+        out.push_str("\nNotes:\n");
+        for (date, note) in character.notes() {
+            out.push_str(&format!("- {} | {}\n", date, note));
+        }
+    }
+    out
+}
+
+/// Sorts characters by some key, breaking ties by name.
+fn sorted(mut chars: Vec<Character>, sort: Sort) -> Vec<Character> {
+    chars.sort_by(|a, b| a.name().cmp(b.name()));
+    match sort {
+        Sort::Name => {}
+        Sort::Level => chars.sort_by_key(|c| Reverse(c.level())),
+        Sort::Race => chars.sort_by_cached_key(|c| format!("{:?}", c.race())),
+        Sort::Status => chars.sort_by_key(|c| match c.health().status() {
+            HealthStatus::Alive(_) => 0,
+            HealthStatus::Unconscious(_) => 1,
+            HealthStatus::Dead => 2,
+        }),
+    }
+    chars
+}
+
+/// Lays out characters in aligned columns under a header.
+fn table(chars: &[Character]) -> String {
+    let header = ["", "Name", "Level", "Race", "Status"].map(String::from);
+    let rows: Vec<[String; 5]> = chars
+        .iter()
+        .map(|c| {
+            [
+                format!("@{}", c.identifier),
+                c.name().to_string(),
+                c.level().to_string(),
+                format!("{:?}", c.race()),
+                format!(
+                    "{}{}",
+                    c.health().status(),
+                    if c.archived() { " (archived)" } else { "" }
+                ),
+            ]
+        })
+        .collect();
+
+    let mut widths = [0; 5];
+    for row in std::iter::once(&header).chain(&rows) {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    std::iter::once(&header)
+        .chain(&rows)
+        .map(|row| {
+            let line = row
+                .iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+                .collect::<Vec<_>>()
+                .join("  ");
+            format!("{}\n", line.trim_end())
+        })
+        .collect()
+}
+
+/// Rolls a single die with a number of sides.
+fn roll(sides: u32) -> Result<u32> {
+    let result = format!("1d{}", sides)
+        .parse::<DiceSet>()
+        .map_err(|_| anyhow!("Couldn't roll a d{}.", sides))?
+        .roll_dice_set()
+        .final_result;
+    u32::try_from(result).with_context(|| format!("Rolled {} on a d{}.", result, sides))
+}
+
+/// Strips the leading @ from a character reference.
+fn identifier(name: &str) -> &str {
+    name.strip_prefix('@').unwrap_or(name)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        character::{CharacterStore, Class, Fighter, HealthStatus, LocationStore, Race},
+        persistence::{load_world, Store},
+        testing::{add_character, character, scratch_world},
+        world::World,
+    };
+
+    use super::{
+        roll, sheet, sorted, table, Characters, Damage, Heal, Import, Initiative, Move, Note, Rest,
+        Search, Sort,
+    };
+
+    fn status(path: &std::path::Path) -> HealthStatus {
+        load_world(path)
+            .expect("world loads")
+            .lookup_character("gandalf")
+            .expect("character exists")
+            .health()
+            .status()
+    }
+
+    fn damage(amount: u32, critical: bool) -> Characters {
+        Characters::Damage(Damage {
+            name: "@gandalf".to_string(),
+            amount,
+            critical,
+        })
+    }
+
+    #[test]
+    pub fn damages_until_dead() {
+        let mut world = World::default();
+        add_character(&mut world, "gandalf");
+        let path = scratch_world(&world);
+
+        damage(4, false)
+            .run(&mut Store::default(), &path)
+            .expect("can damage");
+        assert!(matches!(status(&path), HealthStatus::Alive(hp) if hp.hp() == 6));
+
+        damage(6, false)
+            .run(&mut Store::default(), &path)
+            .expect("can damage");
+        assert!(matches!(status(&path), HealthStatus::Unconscious(_)));
+
+        damage(1, false)
+            .run(&mut Store::default(), &path)
+            .expect("can damage");
+        assert!(matches!(status(&path), HealthStatus::Unconscious(_)));
+
+        damage(1, true)
+            .run(&mut Store::default(), &path)
+            .expect("can damage");
+        assert_eq!(status(&path), HealthStatus::Dead);
+    }
+
+    #[test]
+    pub fn heals_from_unconscious() {
+        let mut world = World::default();
+        add_character(&mut world, "gandalf");
+        let path = scratch_world(&world);
+
+        damage(10, false)
+            .run(&mut Store::default(), &path)
+            .expect("can damage");
+        Characters::Heal(Heal {
+            name: "@gandalf".to_string(),
+            amount: 3,
+        })
+        .run(&mut Store::default(), &path)
+        .expect("can heal");
+
+        assert!(matches!(status(&path), HealthStatus::Alive(hp) if hp.hp() == 3));
+    }
+
+    #[test]
+    pub fn unknown_character_errors() {
+        let path = scratch_world(&World::default());
+        assert!(damage(1, false).run(&mut Store::default(), &path).is_err());
+    }
+
+    #[test]
+    pub fn moves_character() {
+        let mut world = World::default();
+        add_character(&mut world, "gandalf");
+        let path = scratch_world(&world);
+        let journey = || {
+            Characters::Move(Move {
+                name: "@gandalf".to_string(),
+                location: "#moria".to_string(),
+            })
+        };
+
+        journey()
+            .run(&mut Store::default(), &path)
+            .expect("can move");
+        journey()
+            .run(&mut Store::default(), &path)
+            .expect("can move again");
+
+        let world = load_world(&path).expect("world loads");
+        let moria = world
+            .lookup_location("moria")
+            .expect("location was created");
+        let gandalf = world.lookup_character("gandalf").expect("character exists");
+        assert_eq!(gandalf.location(), Some(moria.id));
+
+        assert_eq!(world.records.len(), 1);
+        let record = &world.records[0];
+        assert_eq!(record.note, "@gandalf traveled to #moria");
+        assert_eq!(record.chars[0].id, gandalf.id);
+        assert_eq!(record.locs[0].id, moria.id);
+    }
+
+    #[test]
+    pub fn staying_put_does_not_save() {
+        let mut world = World::default();
+        add_character(&mut world, "gandalf");
+        let path = scratch_world(&world);
+        let journey = Characters::Move(Move {
+            name: "@gandalf".to_string(),
+            location: "#moria".to_string(),
+        });
+        journey.run(&mut Store::default(), &path).expect("can move");
+
+        let modified = || {
+            std::fs::metadata(&path)
+                .and_then(|m| m.modified())
+                .expect("world has a modified time")
+        };
+        let before = modified();
+        journey
+            .run(&mut Store::default(), &path)
+            .expect("can stay put");
+
+        assert_eq!(modified(), before);
+    }
+
+    #[test]
+    pub fn archives_character() {
+        let mut world = World::default();
+        add_character(&mut world, "boromir");
+        world.add_record("@boromir defended the hobbits".to_string());
+        let path = scratch_world(&world);
+
+        Characters::Archive(Search {
+            expr: "@boromir".to_string(),
+        })
+        .run(&mut Store::default(), &path)
+        .expect("can archive");
+
+        let world = load_world(&path).expect("world loads");
+        assert!(world.list_characters(false).is_empty());
+        assert_eq!(world.list_characters(true).len(), 1);
+
+        let boromir = world.records[0].chars[0].id;
+        let archived = world.get_character(boromir).expect("still resolves");
+        assert!(archived.archived());
+        assert_eq!(world.records_for_character(boromir).len(), 1);
+
+        Characters::Unarchive(Search {
+            expr: "@boromir".to_string(),
+        })
+        .run(&mut Store::default(), &path)
+        .expect("can restore");
+        let world = load_world(&path).expect("world loads");
+        assert_eq!(world.list_characters(false).len(), 1);
+    }
+
+    #[test]
+    pub fn long_rest_restores_health() {
+        let mut world = World::default();
+        add_character(&mut world, "gandalf");
+        let path = scratch_world(&world);
+        let before = world.time.into_datetime(&world.calendar).total_seconds();
+
+        damage(7, false)
+            .run(&mut Store::default(), &path)
+            .expect("can damage");
+        Characters::Rest(Rest {
+            name: None,
+            party: true,
+            short: false,
+            long: true,
+        })
+        .run(&mut Store::default(), &path)
+        .expect("can rest");
+
+        let world = load_world(&path).expect("world loads");
+        let after = world.time.into_datetime(&world.calendar).total_seconds();
+        assert_eq!(after - before, 8 * 3600);
+        assert!(world
+            .lookup_character("gandalf")
+            .expect("character exists")
+            .health()
+            .full());
+    }
+
+    #[test]
+    pub fn rolls_a_single_die() {
+        for _ in 0..100 {
+            let result = roll(8).expect("a d8 rolls");
+            assert!((1..=8).contains(&result));
+        }
+    }
+
+    #[test]
+    pub fn sorts_by_level() {
+        let mut chars: Vec<_> = [("aragorn", 3), ("bilbo", 1), ("gimli", 3), ("legolas", 5)]
+            .iter()
+            .map(|(name, level)| {
+                let mut c = character(name);
+                for _ in 0..*level {
+                    c.level_up(Class::Fighter(Fighter::Champion))
+                        .expect("has a class slot");
+                }
+                c
+            })
+            .collect();
+        chars.reverse();
+
+        let names: Vec<_> = sorted(chars, Sort::Level)
+            .into_iter()
+            .map(|c| c.identifier)
+            .collect();
+        assert_eq!(names, ["legolas", "aragorn", "gimli", "bilbo"]);
+    }
+
+    #[test]
+    pub fn aligns_table() {
+        let chars = vec![character("gandalf"), character("sam")];
+        let table = table(&chars);
+        let lines: Vec<_> = table.lines().collect();
+
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("          Name     Level"));
+        assert!(lines[2].starts_with("@sam      sam      0      Unknown  alive"));
+    }
+
+    #[test]
+    pub fn orders_combat_by_initiative() {
+        let mut world = World::default();
+        for name in ["gandalf", "frodo", "aragorn", "sam"] {
+            add_character(&mut world, name);
+        }
+        let path = scratch_world(&world);
+
+        for (name, value) in [("@gandalf", 12), ("@frodo", 18), ("@aragorn", 12)] {
+            Characters::Initiative(Initiative {
+                name: name.to_string(),
+                value,
+            })
+            .run(&mut Store::default(), &path)
+            .expect("can set initiative");
+        }
+
+        let world = load_world(&path).expect("world loads");
+        let order: Vec<_> = world
+            .combat_order()
+            .iter()
+            .map(|c| c.identifier.as_str())
+            .collect();
+        assert_eq!(order, ["frodo", "aragorn", "gandalf"]);
+
+        Characters::EndCombat
+            .run(&mut Store::default(), &path)
+            .expect("can end combat");
+        let world = load_world(&path).expect("world loads");
+        assert!(world.combat_order().is_empty());
+    }
+
+    #[test]
+    pub fn keeps_notes_in_order() {
+        let mut world = World::default();
+        add_character(&mut world, "barliman");
+        let path = scratch_world(&world);
+
+        for text in ["Runs the Prancing Pony", "Forgot to send a letter"] {
+            Characters::Note(Note {
+                name: "@barliman".to_string(),
+                text: text.to_string(),
+            })
+            .run(&mut Store::default(), &path)
+            .expect("can note");
+        }
+
+        let world = load_world(&path).expect("world loads");
+        let barliman = world
+            .lookup_character("barliman")
+            .expect("character exists");
+        let notes: Vec<_> = barliman.notes().iter().map(|(_, n)| n.as_str()).collect();
+        assert_eq!(notes, ["Runs the Prancing Pony", "Forgot to send a letter"]);
+        assert!(sheet(&world, &barliman).contains("Forgot to send a letter"));
+        assert_eq!(world.records.len(), 0);
+    }
+
+    #[test]
+    pub fn imports_roster() {
+        let mut world = World::default();
+        add_character(&mut world, "gandalf");
+        let path = scratch_world(&world);
+        let roster = path.join("party.json");
+        std::fs::write(
+            &roster,
+            r#"[
+                {"identifier": "gimli", "name": "Gimli", "race": "Dwarf", "max_health": 14,
+                 "classes": [{"class": {"Fighter": "Champion"}, "level": 3}]},
+                {"identifier": "@aragorn", "race": "Human", "max_health": 12},
+                {"identifier": "gandalf", "name": "Gandalf the Grey"}
+            ]"#,
+        )
+        .expect("can write roster");
+
+        Characters::Import(Import { path: roster })
+            .run(&mut Store::default(), &path)
+            .expect("can import");
+
+        let world = load_world(&path).expect("world loads");
+        assert_eq!(world.list_characters(false).len(), 3);
+
+        let gimli = world.lookup_character("gimli").expect("was imported");
+        assert_eq!(gimli.name(), "Gimli");
+        assert_eq!(gimli.race(), Race::Dwarf);
+        assert_eq!(gimli.level(), 3);
+        assert_eq!(
+            gimli.classes()[0],
+            (Some(Class::Fighter(Fighter::Champion)), 3)
+        );
+        assert!(matches!(gimli.health().status(), HealthStatus::Alive(hp) if hp.hp() == 14));
+
+        let aragorn = world.lookup_character("aragorn").expect("was imported");
+        assert_eq!(aragorn.name(), "aragorn");
+        assert_eq!(aragorn.race(), Race::Human);
+
+        let gandalf = world.lookup_character("gandalf").expect("still exists");
+        assert_eq!(gandalf.name(), "gandalf", "duplicates are skipped");
+    }
+}
@@ -1,19 +1,43 @@
-use anyhow::Result;
+use std::path::Path;
+
+use anyhow::{anyhow, Result};
 use clap::Clap;
 use dnd_dice_roller::dice_set::DiceSet;
 use itertools::Itertools;
 
+use crate::persistence::Store;
+
 /// Tempt fate and throw some dice.
 #[derive(Clap)]
 pub struct Dice {
-    sets: Vec<DiceSet>,
+    /// Save the dice as a macro with this name, rather than rolling them.
+    #[clap(long)]
+    save: Option<String>,
+
+    /// Dice such as 2d6+3, or the name of a saved macro.
+    sets: Vec<String>,
 }
 
 impl Dice {
-    pub fn run(&self) -> Result<()> {
+    pub fn run(&self, store: &mut Store, path: &Path) -> Result<()> {
+        if let Some(name) = &self.save {
+            if self.sets.is_empty() {
+                return Err(anyhow!("There are no dice to save as {}.", name));
+            }
+            for set in &self.sets {
+                parse(set)?;
+            }
+
+            let mut world = store.load_world(path)?;
+            world.save_dice_macro(name.clone(), self.sets.join(" "));
+            store.save_world(path, &world)?;
+            println!("Saved {} as {}.", self.sets.join(" "), name);
+            return Ok(());
+        }
+
         println!(
             "{}",
-            self.sets
+            self.roll(store, path)?
                 .iter()
                 .map(|set| set.roll_dice_set().final_result)
                 .join(", ")
@@ -21,4 +45,85 @@ impl Dice {
 
         Ok(())
     }
+
+    /// Gets the dice to roll, expanding any saved macros.
+    /// The world is only loaded if a macro is used.
+    fn roll(&self, store: &Store, path: &Path) -> Result<Vec<DiceSet>> {
+        let mut world = None;
+        let mut sets = vec![];
+
+        for set in &self.sets {
+            if let Ok(dice) = parse(set) {
+                sets.push(dice);
+                continue;
+            }
+
+            if world.is_none() {
+                world = Some(store.load_world(path)?);
+            }
+
+            let expr = world
+                .as_ref()
+                .and_then(|w| w.dice_macro(set))
+                .ok_or_else(|| anyhow!("{} is neither dice nor a saved macro.", set))?;
+            for dice in expr.split_whitespace() {
+                sets.push(parse(dice)?);
+            }
+        }
+
+        Ok(sets)
+    }
+}
+
+fn parse(set: &str) -> Result<DiceSet> {
+    set.parse()
+        .map_err(|_| anyhow!("{} is not a valid dice expression.", set))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        persistence::{load_world, Store},
+        testing::scratch_world,
+        world::World,
+    };
+
+    use super::Dice;
+
+    #[test]
+    pub fn runs_saved_macro() {
+        let path = scratch_world(&World::default());
+
+        Dice {
+            save: Some("attack".to_string()),
+            sets: vec!["1d20+5".to_string()],
+        }
+        .run(&mut Store::default(), &path)
+        .expect("can save macro");
+
+        let world = load_world(&path).expect("world loads");
+        assert_eq!(world.dice_macro("attack"), Some("1d20+5"));
+
+        let rolls = Dice {
+            save: None,
+            sets: vec!["attack".to_string()],
+        }
+        .roll(&Store::default(), &path)
+        .expect("macro exists");
+
+        assert_eq!(rolls.len(), 1);
+        let result = rolls[0].roll_dice_set().final_result;
+        assert!((6..=25).contains(&result));
+    }
+
+    #[test]
+    pub fn rejects_unknown_macro() {
+        let path = scratch_world(&World::default());
+        let dice = Dice {
+            save: None,
+            sets: vec!["fireball".to_string()],
+        };
+
+        assert!(dice.roll(&Store::default(), &path).is_err());
+    }
 }
@@ -0,0 +1,23 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use clap::Clap;
+
+use crate::persistence::Store;
+
+/// Compare the world with another copy of it, such as a
+/// backup, showing what has changed since that copy.
+#[derive(Clap)]
+pub struct Diff {
+    /// The path to the other world.
+    other: PathBuf,
+}
+
+impl Diff {
+    pub fn run(&self, store: &Store, path: &Path) -> Result<()> {
+        let world = store.load_world(path)?;
+        let other = store.load_world(&self.other)?;
+        println!("{}", other.diff(&world)?);
+        Ok(())
+    }
+}
@@ -0,0 +1,227 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, Context, Result};
+use clap::{ArgEnum, Clap};
+use holding_kronos::calendar::traits::ConvertTime;
+use itertools::Itertools;
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::{persistence::Store, world::World};
+
+lazy_static! {
+    static ref DATE: Regex =
+        Regex::new(r"^(?P<y>\d{4})-(?P<m>\d{2})-(?P<d>\d{2})$").expect("compiles");
+}
+
+/// Share the chronicles of this world with the outside.
+#[derive(Clap)]
+pub struct Export {
+    /// The format to export to.
+    #[clap(long, arg_enum, default_value = "markdown")]
+    format: Format,
+
+    /// The real date (YYYY-MM-DD) that the start of the
+    /// world's calendar maps to, when exporting to ics.
+    #[clap(long, default_value = "2000-01-01")]
+    anchor: String,
+
+    /// The file to write the export to.
+    output: PathBuf,
+}
+
+#[derive(ArgEnum, Clone, Copy, Debug, PartialEq)]
+pub enum Format {
+    Ics,
+    Markdown,
+}
+
+impl Export {
+    pub fn run(&self, store: &Store, path: &Path) -> Result<()> {
+        let world = store.load_world(path)?;
+
+        let contents = match self.format {
+            Format::Markdown => markdown(&world),
+            Format::Ics => ics(&world, &self.anchor)?,
+        };
+
+        fs::write(&self.output, contents)
+            .with_context(|| format!("Could not write to {}.", self.output.display()))?;
+        println!(
+            "Exported {} record(s) to {}.",
+            world.records.len(),
+            self.output.display()
+        );
+        Ok(())
+    }
+}
+
+/// Renders the records of the world as a markdown chronology.
+fn markdown(world: &World) -> String {
+    let entries = world
+        .records
+        .iter()
+        .sorted_by_key(|r| r.date)
+        .map(|r| format!("- **{}** {}", r.date, r.note))
+        .join("\n");
+
+    format!("# {}\n\n{}\n", world.name, entries)
+}
+
+/// Renders the records of the world as an iCalendar file, mapping
+/// each day in the world to a real day counting from the anchor.
+fn ics(world: &World, anchor: &str) -> Result<String> {
+    let anchor = parse_anchor(anchor)?;
+    let stamp = format_civil(civil_from_days(anchor));
+
+    let events = world
+        .records
+        .iter()
+        .sorted_by_key(|r| r.date)
+        .map(|r| {
+            let days = r
+                .date
+                .into_datetime(&world.calendar)
+                .total_seconds()
+                .div_euclid(i64::from(world.calendar.days_to_seconds(1)));
+            format!(
+                "BEGIN:VEVENT\r\nUID:{}\r\nDTSTAMP:{}T000000Z\r\nDTSTART;VALUE=DATE:{}\r\nSUMMARY:{}\r\nEND:VEVENT\r\n",
+                r.id,
+                stamp,
+                format_civil(civil_from_days(anchor + days)),
+                escape(&r.note)
+            )
+        })
+        .join("");
+
+    Ok(format!(
+        "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//holding//{}//EN\r\n{}END:VCALENDAR\r\n",
+        escape(&world.name),
+        events
+    ))
+}
+
+/// Escapes text according to RFC 5545.
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Parses a YYYY-MM-DD date into days since 1970-01-01.
+fn parse_anchor(anchor: &str) -> Result<i64> {
+    let captures = DATE
+        .captures(anchor)
+        .ok_or_else(|| anyhow!("The anchor {} is not a YYYY-MM-DD date.", anchor))?;
+    let part = |name| -> i64 {
+        captures
+            .name(name)
+            .expect("This is in the regex")
+            .as_str()
+            .parse()
+            .expect("This is a valid i64")
+    };
+
+    Ok(days_from_civil(part("y"), part("m"), part("d")))
+}
+
+fn format_civil((year, month, day): (i64, i64, i64)) -> String {
+    format!("{:0>4}{:0>2}{:0>2}", year, month, day)
+}
+
+/// Converts a gregorian date into days since 1970-01-01.
+///
+/// http://howardhinnant.github.io/date_algorithms.html#days_from_civil
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Converts days since 1970-01-01 into a gregorian date.
+///
+/// http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use crate::{
+        persistence::Store,
+        testing::{scratch_dir, scratch_world},
+        world::World,
+    };
+
+    use super::{civil_from_days, days_from_civil, ics, Export, Format};
+
+    fn fixture() -> World {
+        let mut world = World::default();
+        world.add_record("The party arrived at #waterdeep".to_string());
+        world.update_time("1d").expect("can step");
+        world.add_record("The party left #waterdeep".to_string());
+        world
+    }
+
+    #[test]
+    pub fn exports_markdown() {
+        let path = scratch_world(&fixture());
+        let output = scratch_dir().join("timeline.md");
+
+        Export {
+            format: Format::Markdown,
+            anchor: "2000-01-01".to_string(),
+            output: output.clone(),
+        }
+        .run(&Store::default(), &path)
+        .expect("can export");
+
+        let timeline = fs::read_to_string(output).expect("export exists");
+        let arrived = timeline
+            .find("The party arrived at #waterdeep")
+            .expect("first record exported");
+        let left = timeline
+            .find("The party left #waterdeep")
+            .expect("second record exported");
+        assert!(arrived < left);
+    }
+
+    #[test]
+    pub fn exports_ics() {
+        let calendar = ics(&fixture(), "2000-01-01").expect("valid anchor");
+
+        assert_eq!(calendar.matches("BEGIN:VEVENT").count(), 2);
+        assert!(calendar.contains("DTSTART;VALUE=DATE:20000101"));
+        assert!(calendar.contains("DTSTART;VALUE=DATE:20000102"));
+        assert!(ics(&fixture(), "tomorrow").is_err());
+    }
+
+    #[test]
+    pub fn civil_round_trips() {
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+        assert_eq!(civil_from_days(days_from_civil(2000, 2, 29)), (2000, 2, 29));
+        assert_eq!(
+            civil_from_days(days_from_civil(1600, 12, 31)),
+            (1600, 12, 31)
+        );
+    }
+}
@@ -1,17 +1,331 @@
-use crate::persistence::load_world;
+use crate::{
+    character::{CharacterStore, LocationStore},
+    persistence::Store,
+    record::RawRecord,
+};
 use std::path::Path;
 
-use anyhow::Result;
-use clap::Clap;
+use anyhow::{anyhow, Result};
+use clap::{ArgEnum, Clap};
+use holding_color::colored::Colorize;
+use holding_kronos::{
+    calendar::Calendar,
+    datetime::{
+        traits::{ModifyDate, ShowDate},
+        Date, RawDate, RawDateTime,
+    },
+};
+use itertools::Itertools;
+use ordinal::Ordinal;
 
 /// Inspect the very flow of time.
 #[derive(Clap)]
-pub struct History {}
+pub struct History {
+    /// Only show records containing some text.
+    #[clap(long)]
+    search: Option<String>,
+
+    /// Treat the search as a regular expression.
+    #[clap(long, requires = "search")]
+    regex: bool,
+
+    /// Only show records mentioning some character, such as @gandalf.
+    #[clap(long, conflicts_with = "search")]
+    character: Option<String>,
+
+    /// Only show records mentioning some location, such as #moria.
+    #[clap(long, conflicts_with_all = &["search", "character"])]
+    location: Option<String>,
+
+    /// Group the records under a header for each day, week, or month.
+    #[clap(long, arg_enum)]
+    group_by: Option<Grouping>,
+
+    /// Wrap long records to fit in this many columns.
+    #[clap(long)]
+    width: Option<usize>,
+
+    /// Draw the records along a line from the first one until now.
+    #[clap(long, conflicts_with = "group-by")]
+    timeline: bool,
+}
+
+#[derive(ArgEnum, Clone, Copy, Debug, PartialEq)]
+pub enum Grouping {
+    Day,
+    Week,
+    Month,
+}
 
 impl History {
-    pub fn run(&self, path: &Path) -> Result<()> {
-        let world = load_world(path)?;
-        println!("{}", world.records_pretty());
+    pub fn run(&self, store: &Store, path: &Path) -> Result<()> {
+        let world = store.load_world(path)?;
+
+        let records = if let Some(query) = &self.search {
+            world.search_records(query, self.regex)?
+        } else if let Some(name) = &self.character {
+            let name = name.strip_prefix('@').unwrap_or(name);
+            let character = world
+                .lookup_character(name)
+                .ok_or_else(|| anyhow!("There is no character called @{}.", name))?;
+            world.records_for_character(character.id)
+        } else if let Some(name) = &self.location {
+            let name = name.strip_prefix('#').unwrap_or(name);
+            let location = world
+                .lookup_location(name)
+                .ok_or_else(|| anyhow!("There is no location called #{}.", name))?;
+            world.records_for_location(location.id)
+        } else if self.group_by.is_some() || self.timeline {
+            world.records.iter().collect()
+        } else {
+            println!("{}", world.records_pretty(self.width));
+            return Ok(());
+        };
+
+        if records.is_empty() {
+            println!("Nothing of the sort has happened.");
+        } else if self.timeline {
+            let width = self.width.unwrap_or(TIMELINE_WIDTH);
+            println!("{}", timeline(&records, world.time, &world.calendar, width));
+        } else if let Some(grouping) = self.group_by {
+            println!(
+                "{}",
+                grouped(&records, &world.calendar, grouping, self.width)
+            );
+        } else {
+            println!(
+                "{}",
+                records
+                    .iter()
+                    .sorted_by_key(|r| r.date)
+                    .map(|r| r.entry(self.width))
+                    .join("\n")
+            );
+        }
+
         Ok(())
     }
 }
+
+/// Renders records in chronological order under a header
+/// for each period, following the calendar's week and
+/// month boundaries. Empty periods are skipped.
+fn grouped(
+    records: &[&RawRecord],
+    calendar: &Calendar,
+    grouping: Grouping,
+    width: Option<usize>,
+) -> String {
+    records
+        .iter()
+        .sorted_by_key(|r| r.date)
+        .group_by(|r| period(r.date.date.into_date(calendar), grouping))
+        .into_iter()
+        .map(|(start, group)| {
+            let entries = group.map(|r| r.entry(width)).join("\n");
+            format!(
+                "{}\n{}",
+                header(start.into_date(calendar), grouping).bold(),
+                entries
+            )
+        })
+        .join("\n\n")
+}
+
+/// The width of a timeline when none is given.
+const TIMELINE_WIDTH: usize = 60;
+
+/// Draws records along a line of some width, spanning from the
+/// earliest record until `now`. Each record is a `o` on the line,
+/// records that land in the same column are collapsed into their
+/// count (or `#` past nine), and every marker is lettered so that
+/// it can be looked up in the legend below.
+fn timeline(records: &[&RawRecord], now: RawDateTime, calendar: &Calendar, width: usize) -> String {
+    let width = width.max(2);
+    let seconds = |date: RawDateTime| date.into_datetime(calendar).total_seconds();
+    let records: Vec<_> = records.iter().sorted_by_key(|r| r.date).collect();
+    let (first, last) = match (records.first(), records.last()) {
+        (Some(first), Some(last)) => (first.date, last.date.max(now)),
+        _ => return String::new(),
+    };
+
+    let start = seconds(first);
+    let span = (seconds(last) - start).max(1);
+    let column = |date: RawDateTime| {
+        let offset = i128::from(seconds(date) - start) * (width as i128 - 1) / i128::from(span);
+        usize::try_from(offset).unwrap_or(0).min(width - 1)
+    };
+    let buckets = records.iter().group_by(|r| column(r.date));
+    let buckets: Vec<_> = buckets
+        .into_iter()
+        .map(|(column, group)| (column, group.collect::<Vec<_>>()))
+        .collect();
+
+    let mut line = vec!['-'; width];
+    line[0] = '|';
+    line[width - 1] = '|';
+    let mut labels = vec![' '; width];
+    let mut legend = Vec::new();
+    for ((column, group), label) in buckets.iter().zip(marker_labels()) {
+        line[*column] = match group.len() {
+            1 => 'o',
+            n @ 2..=9 => std::char::from_digit(n as u32, 10).unwrap_or('#'),
+            _ => '#',
+        };
+        labels[*column] = label;
+
+        let more = match group.len() {
+            1 => String::new(),
+            n => format!(" (and {} more)", n - 1),
+        };
+        legend.push(format!(
+            "{} {} | {}{}",
+            label,
+            group[0].date.into_datetime(calendar).to_display_string(),
+            group[0].pretty(),
+            more
+        ));
+    }
+
+    let from = first.into_datetime(calendar).to_display_string();
+    let until = last.into_datetime(calendar).to_display_string();
+    let gap = width.saturating_sub(from.chars().count() + until.chars().count());
+    format!(
+        "{}{}{}\n{}\n{}\n\n{}",
+        from.bright_black(),
+        " ".repeat(gap.max(1)),
+        until.bright_black(),
+        line.into_iter().collect::<String>(),
+        labels.into_iter().collect::<String>().trim_end(),
+        legend.join("\n")
+    )
+}
+
+/// Letters for the markers on a timeline, running
+/// out into `?` if there are more than there are letters.
+fn marker_labels() -> impl Iterator<Item = char> {
+    ('a'..='z').chain('A'..='Z').chain(std::iter::repeat('?'))
+}
+
+/// Gets the first day of the period containing a date.
+fn period(date: Date, grouping: Grouping) -> RawDate {
+    match grouping {
+        Grouping::Day => date.date,
+        Grouping::Week => date.sub_days(date.week_day() - 1).date,
+        Grouping::Month => RawDate::from_index(date.date.year, date.date.month, 0),
+    }
+}
+
+fn header(start: Date, grouping: Grouping) -> String {
+    match grouping {
+        Grouping::Day => format!(
+            "{} the {} of {}, {}",
+            start.week_day_name(),
+            Ordinal(start.day()),
+            start.month_name(),
+            start.year()
+        ),
+        Grouping::Week => format!(
+            "The week of the {} of {}, {}",
+            Ordinal(start.day()),
+            start.month_name(),
+            start.year()
+        ),
+        Grouping::Month => format!("{} {}", start.month_name(), start.year()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{persistence::Store, testing::scratch_world, world::World};
+
+    use super::{grouped, timeline, Grouping, History};
+
+    fn fixture() -> World {
+        let mut world = World::default();
+        world.add_record("The party set out".to_string());
+        world.add_record("The party made camp".to_string());
+        world.update_time("1d").expect("can step");
+        world.add_record("The party was ambushed".to_string());
+        world.update_time("1w").expect("can step");
+        world.add_record("The party reached the city".to_string());
+        world
+    }
+
+    #[test]
+    pub fn groups_by_day() {
+        let world = fixture();
+        let records: Vec<_> = world.records.iter().collect();
+        let days = grouped(&records, &world.calendar, Grouping::Day, None);
+
+        let position = |text: &str| days.find(text).expect("text is present");
+        let order = [
+            "Monday the 1st of January, 1",
+            "The party set out",
+            "The party made camp",
+            "Tuesday the 2nd of January, 1",
+            "The party was ambushed",
+            "Tuesday the 9th of January, 1",
+            "The party reached the city",
+        ];
+        assert!(order.windows(2).all(|w| position(w[0]) < position(w[1])));
+
+        let weeks = grouped(&records, &world.calendar, Grouping::Week, None);
+        assert_eq!(weeks.matches("The week of").count(), 2);
+        assert!(weeks.contains("The week of the 8th of January, 1"));
+
+        let months = grouped(&records, &world.calendar, Grouping::Month, None);
+        assert_eq!(months.matches("January 1").count(), 1);
+
+        History {
+            search: None,
+            regex: false,
+            character: None,
+            location: None,
+            group_by: Some(Grouping::Week),
+            width: Some(40),
+            timeline: false,
+        }
+        .run(&Store::default(), &scratch_world(&world))
+        .expect("can show history");
+    }
+
+    #[test]
+    pub fn draws_timeline() {
+        let mut world = World::default();
+        world.add_record("The party set out".to_string());
+        world.update_time("10d").expect("can step");
+        world.add_record("The party was ambushed".to_string());
+        world.update_time("10d").expect("can step");
+        world.add_record("The party reached the city".to_string());
+        world.update_time("10d").expect("can step");
+
+        let records: Vec<_> = world.records.iter().collect();
+        let drawn = timeline(&records, world.time, &world.calendar, 31);
+        let lines: Vec<_> = drawn.lines().collect();
+
+        assert_eq!(lines[1].chars().count(), 31);
+        assert_eq!(lines[1], "o---------o---------o---------|");
+        assert_eq!(lines[2], "a         b         c");
+        assert!(lines[4].starts_with("a ") && lines[4].ends_with("The party set out"));
+        assert!(lines[6].ends_with("The party reached the city"));
+
+        world.add_record("The party rested".to_string());
+        let records: Vec<_> = world.records.iter().collect();
+        let crowded = timeline(&records, world.time, &world.calendar, 4);
+        assert!(crowded.lines().nth(1).expect("line is drawn").contains('2'));
+        assert!(crowded.contains("(and 1 more)"));
+
+        History {
+            search: None,
+            regex: false,
+            character: None,
+            location: None,
+            group_by: None,
+            width: Some(40),
+            timeline: true,
+        }
+        .run(&Store::default(), &scratch_world(&world))
+        .expect("can show timeline");
+    }
+}
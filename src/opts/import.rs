@@ -0,0 +1,179 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, Context, Result};
+use clap::Clap;
+use holding_kronos::{calendar::Calendar, datetime::RawDateTime};
+use itertools::Itertools;
+
+use crate::persistence::Store;
+
+/// Bring records kept elsewhere into this world.
+#[derive(Clap)]
+pub struct Import {
+    /// A file with one record per line, such as
+    /// `1101-02-12T08:30:00Z | The party arrived at #waterdeep`.
+    /// The date can be written in any form `time` accepts, such
+    /// as 1101-02-12 or 3 Tarsakh 1101, leaving out the time of day.
+    #[clap(long)]
+    records: PathBuf,
+}
+
+impl Import {
+    pub fn run(&self, store: &mut Store, path: &Path) -> Result<()> {
+        let mut world = store.load_world(path)?;
+        let text = fs::read_to_string(&self.records)
+            .with_context(|| format!("Could not read {}.", self.records.display()))?;
+
+        let entries = parse_records(&world.calendar, &text)?;
+        if let Some(latest) = entries.iter().map(|(date, _)| *date).max() {
+            if latest > world.time {
+                world.update_time(&latest.to_string())?;
+            }
+        }
+
+        let count = entries.len();
+        for (date, note) in entries {
+            world.add_record_at(note, None, date)?;
+        }
+
+        store.save_world(path, &world)?;
+        println!("Imported {} record(s).", count);
+        Ok(())
+    }
+}
+
+/// Reads the records in a file, skipping blank lines. Every malformed
+/// line is reported, and nothing is imported if there are any.
+fn parse_records(calendar: &Calendar, text: &str) -> Result<Vec<(RawDateTime, String)>> {
+    let (entries, errors): (Vec<_>, Vec<_>) = text
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            parse_record(calendar, line).map_err(|e| format!("- line {}: {:#}", i + 1, e))
+        })
+        .partition_result();
+
+    if errors.is_empty() {
+        Ok(entries)
+    } else {
+        Err(anyhow!(
+            "Found {} malformed line(s):\n{}",
+            errors.len(),
+            errors.join("\n")
+        ))
+    }
+}
+
+fn parse_record(calendar: &Calendar, line: &str) -> Result<(RawDateTime, String)> {
+    let (stamp, note) = line
+        .split_once('|')
+        .ok_or_else(|| anyhow!("Expected a date and a note, separated by |."))?;
+    let note = note.trim();
+    if note.is_empty() {
+        return Err(anyhow!("The note is empty."));
+    }
+
+    let date = calendar.parse(stamp.trim(), None)?;
+    Ok((date.into(), note.to_string()))
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use holding_kronos::{
+        calendar::{Calendar, Day, Week, Year},
+        datetime::Schedule,
+    };
+
+    use crate::{
+        character::{CharacterStore, LocationStore},
+        persistence::{load_world, Store},
+        testing::{scratch_dir, scratch_world},
+        world::World,
+    };
+
+    use super::Import;
+
+    #[test]
+    pub fn imports_records() {
+        let path = scratch_world(&World::default());
+        let records = scratch_dir().join("notes.txt");
+        fs::write(
+            &records,
+            "1-01-03T08:30:00Z | @gandalf arrived at #bree\n\
+             \n\
+             1-01-02 | The party met @aragorn\n",
+        )
+        .expect("can write notes");
+
+        Import { records }
+            .run(&mut Store::default(), &path)
+            .expect("can import");
+
+        let world = load_world(&path).expect("world loads");
+        let notes: Vec<_> = world.records.iter().map(|r| r.note.as_str()).collect();
+        assert_eq!(
+            notes,
+            ["The party met @aragorn", "@gandalf arrived at #bree"]
+        );
+
+        let gandalf = world.lookup_character("gandalf").expect("character exists");
+        let bree = world.lookup_location("bree").expect("location exists");
+        assert_eq!(world.records[1].chars[0].id, gandalf.id);
+        assert_eq!(world.records[1].locs[0].id, bree.id);
+        assert_eq!(world.records[1].date.time.hour, 8);
+    }
+
+    #[test]
+    pub fn reads_month_names_and_steps_through_schedules() {
+        let mut world = World::default();
+        let mut months = world.calendar.months().to_vec();
+        months[1].name = "Tarsakh".to_string();
+        world.calendar = Calendar::new(Year::new(months), Week::default(), Day::default());
+        let weekly = world.calendar.parse_duration("1w").expect("valid duration");
+        world.add_schedule(Schedule::new(world.time, weekly), "Market day".to_string());
+        let path = scratch_world(&world);
+        let records = scratch_dir().join("notes.txt");
+        fs::write(&records, "3 Tarsakh 1 | The thaw came\n").expect("can write notes");
+
+        Import { records }
+            .run(&mut Store::default(), &path)
+            .expect("can import");
+
+        let world = load_world(&path).expect("world loads");
+        let thaw = world
+            .records
+            .iter()
+            .find(|r| r.note == "The thaw came")
+            .expect("record is imported");
+        assert_eq!((thaw.date.date.month, thaw.date.date.day), (1, 2));
+        assert_eq!(world.time, thaw.date);
+        let markets = world.records.iter().filter(|r| r.note == "Market day");
+        assert_eq!(markets.count(), 4);
+    }
+
+    #[test]
+    pub fn reports_malformed_lines() {
+        let world = World::default();
+        let path = scratch_world(&world);
+        let records = scratch_dir().join("notes.txt");
+        fs::write(
+            &records,
+            "1-01-02 | Fine\nno separator\n1-13-40 | Bad date\n",
+        )
+        .expect("can write notes");
+
+        let error = Import { records }
+            .run(&mut Store::default(), &path)
+            .expect_err("malformed lines are rejected");
+        let message = format!("{:#}", error);
+        assert!(message.contains("line 2"), "{}", message);
+        assert!(message.contains("line 3"), "{}", message);
+        assert!(load_world(&path).expect("world loads").records.is_empty());
+    }
+}
@@ -1,25 +1,27 @@
-use anyhow::Result;
+use std::path::Path;
+
+use anyhow::{anyhow, Result};
 use clap::Clap;
 
-use crate::{
-    character::CharacterStore,
-    persistence::{load_world, save_world},
-};
+use crate::{character::LocationStore, persistence::Store, world::World};
 
-/// See and change the characters in this world.
+/// See and change the locations in this world.
 #[derive(Clap)]
 pub enum Locations {
-    /// Show all characters alphabetically.
+    /// Show all locations.
     List,
 
-    /// Shows characters that are around in the current time.
+    /// Shows locations that are around in the current time.
     Now,
 
-    /// Find a character by its name or identifier.
+    /// Find a location by its name.
     Find(Search),
 
-    /// Shows characters that are nearby.
+    /// Shows locations that are nearby.
     Nearby,
+
+    /// Shows the locations mentioned in the most records.
+    Top,
 }
 
 #[derive(Clap)]
@@ -28,20 +30,81 @@ pub struct Search {
 }
 
 impl Locations {
-    pub fn run(&self, path: &Path) -> Result<()> {
-        let mut world = load_world(&path)?;
+    pub fn run(&self, store: &Store, path: &Path) -> Result<()> {
+        let world = store.load_world(path)?;
 
         match self {
             Locations::List => {
-                let chars = world.list_locations();
-                println!("{:?}", chars);
+                for location in world.list_locations() {
+                    println!("#{}", location.identifier);
+                }
             }
             Locations::Now => todo!(),
-            Locations::Find(Search { expr }) => todo!(),
+            Locations::Find(Search { expr }) => {
+                let name = expr.strip_prefix('#').unwrap_or(expr);
+                let location = world
+                    .lookup_location(name)
+                    .ok_or_else(|| anyhow!("There is no location called #{}.", name))?;
+                let records = world.records_for_location(location.id);
+                println!("#{} ({} records)", location.identifier, records.len());
+            }
             Locations::Nearby => todo!(),
+            Locations::Top => println!("{}", top(&world)),
         }
 
-        save_world(&path, &world)?;
         Ok(())
     }
 }
+
+/// Lists the locations by how many records mention them.
+fn top(world: &World) -> String {
+    let density = world.record_density();
+    if density.is_empty() {
+        return "Nothing has happened anywhere yet.".to_string();
+    }
+
+    density
+        .into_iter()
+        .filter_map(|(id, count)| {
+            let location = world.get_location(id)?;
+            let plural = if count == 1 { "record" } else { "records" };
+            Some(format!("#{}: {} {}", location.identifier, count, plural))
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{persistence::Store, testing::scratch_world, world::World};
+
+    use super::{top, Locations};
+
+    #[test]
+    pub fn ranks_locations_by_records() {
+        let mut world = World::default();
+        world.add_record("The party arrived at #waterdeep".to_string());
+        world.add_record("The party left #waterdeep for #neverwinter".to_string());
+        world.add_record("The party rested in #waterdeep".to_string());
+        world.add_record("The party reached #neverwinter".to_string());
+        world.add_record("The party passed #phandalin".to_string());
+        world.add_record("The party camped".to_string());
+
+        assert_eq!(
+            top(&world),
+            "#waterdeep: 3 records\n#neverwinter: 2 records\n#phandalin: 1 record"
+        );
+        assert_eq!(
+            world
+                .record_density()
+                .iter()
+                .map(|(_, count)| *count)
+                .collect::<Vec<_>>(),
+            vec![3, 2, 1]
+        );
+
+        Locations::Top
+            .run(&Store::default(), &scratch_world(&world))
+            .expect("can rank locations");
+    }
+}
@@ -1,63 +1,223 @@
-use std::path::PathBuf;
+use std::{
+    io::BufRead,
+    path::{Path, PathBuf},
+};
 
+use anyhow::Result;
 use clap::Clap;
 
+use crate::{
+    config::{ColorMode, Config},
+    persistence::{self, Store},
+};
+use almanac::Almanac;
 use bodies::Planetarium;
 use calendar::Time;
 use characters::Characters;
 use completion::Completion;
 use dice::Dice;
+use diff::Diff;
+use export::Export;
 use history::History;
+use import::Import;
+use locations::Locations;
 use new::New;
 use now::Now;
 use record::AddRecord;
+use restore::Restore;
+use run::Run;
+use shell::Shell;
 #[cfg(feature = "sound")]
 use sound::Sound;
 use weather::Weather;
+use world::{WorldFile, Worlds};
 
+mod almanac;
 mod bodies;
 mod calendar;
 mod characters;
 mod completion;
 mod dice;
+mod diff;
+mod export;
 mod history;
 mod host;
+mod import;
+mod locations;
 mod new;
 mod now;
 mod record;
+mod restore;
+mod run;
+mod shell;
 #[cfg(feature = "sound")]
 mod sound;
 mod weather;
+mod world;
 
 /// Manage a DND world from the command line.
 #[derive(Clap)]
 #[clap(bin_name="holding", version = env!("CARGO_PKG_VERSION"))]
 pub struct Opts {
-    /// The path to the world (defaulting to here).
+    /// The path to the world (defaulting to the one in
+    /// the config file, or else here).
     #[clap(short)]
     pub path: Option<PathBuf>,
 
+    /// The name of a world under the path to use, rather than
+    /// the one in it, such as `tavern` for `tavern/world.yaml`,
+    /// `tavern.yaml`, or either of those in `worlds/`.
+    #[clap(long, global = true)]
+    pub world: Option<String>,
+
+    /// Disable colored output.
+    #[clap(long, global = true)]
+    pub no_color: bool,
+
+    /// Don't print errors, only exit with their code.
+    #[clap(long, global = true)]
+    pub quiet: bool,
+
+    /// Print errors as a line of JSON on stderr. Either way,
+    /// the exit code is 2 if the world could not be found,
+    /// 3 if it is corrupted, 4 if it failed validation,
+    /// 5 if a date could not be parsed, and 1 otherwise.
+    #[clap(long, global = true)]
+    pub json_errors: bool,
+
     #[clap(subcommand)]
     pub command: Command,
 }
 
+impl Opts {
+    /// Gets the path to the world to run against, which is the one
+    /// given by `-p` or else some default, narrowed to a world under
+    /// it if `--world` is given.
+    pub fn world_path(&self, default: &Path) -> PathBuf {
+        let path = self.path.as_deref().unwrap_or(default);
+        match &self.world {
+            Some(name) => persistence::find_world(path, name),
+            None => path.to_path_buf(),
+        }
+    }
+
+    /// Decides whether output should be colored, given whether
+    /// stdout is a terminal. Honors `--no-color` and `NO_COLOR`
+    /// before the color mode in the config.
+    pub fn use_color(&self, is_tty: bool, config: &Config) -> bool {
+        if self.no_color || std::env::var_os("NO_COLOR").is_some() {
+            return false;
+        }
+
+        match config.color {
+            ColorMode::Auto => is_tty,
+            ColorMode::Always => true,
+            ColorMode::Never => false,
+        }
+    }
+}
+
 #[derive(Clap)]
 pub enum Command {
     Dice(Dice),
     #[clap(subcommand)]
     Characters(Characters),
+    #[clap(subcommand)]
+    Locations(Locations),
     Now(Now),
     New(New),
     #[clap(subcommand)]
     Time(Time),
+    #[clap(subcommand)]
+    Calendar(Almanac),
     Record(AddRecord),
     History(History),
+    Export(Export),
+    Import(Import),
+    Run(Run),
+    Restore(Restore),
     Weather(Weather),
     #[clap(subcommand)]
     Planetarium(Planetarium),
     #[cfg(feature = "sound")]
     Sound(Sound),
     Completion(Completion),
+    Shell(Shell),
+    Diff(Diff),
+    #[clap(subcommand)]
+    World(WorldFile),
+    #[clap(subcommand)]
+    Worlds(Worlds),
     // Host(Host),
     // Join(Join),
 }
+
+impl Command {
+    /// Runs the command against the world at a given path, reading
+    /// any interactive input from `input`.
+    pub fn run(
+        self,
+        store: &mut Store,
+        path: &Path,
+        config: &Config,
+        input: &mut dyn BufRead,
+    ) -> Result<()> {
+        match self {
+            Command::Dice(d) => d.run(store, path)?,
+            #[cfg(feature = "sound")]
+            Command::Sound(s) => s.run()?,
+            Command::Now(n) => n.run(store, path)?,
+            Command::Time(c) => c.run(store, path)?,
+            Command::Calendar(c) => c.run(store, path)?,
+            Command::Planetarium(b) => b.run(store, path, input)?,
+            Command::Record(r) => r.run(store, path, config)?,
+            Command::New(n) => n.run(path, config)?,
+            Command::Weather(w) => w.run(store, path)?,
+            Command::Completion(c) => c.run()?,
+            Command::History(r) => r.run(store, path)?,
+            Command::Export(e) => e.run(store, path)?,
+            Command::Import(i) => i.run(store, path)?,
+            Command::Run(r) => r.run(store, path, config)?,
+            Command::Restore(r) => r.run(store, path)?,
+            Command::Characters(c) => c.run(store, path)?,
+            Command::Locations(l) => l.run(store, path)?,
+            Command::Shell(s) => s.run(path, config, input)?,
+            Command::Diff(d) => d.run(store, path)?,
+            Command::World(w) => w.run(path)?,
+            Command::Worlds(w) => w.run(path)?,
+        };
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use clap::Clap;
+
+    use super::Opts;
+    use crate::config::{ColorMode, Config};
+
+    #[test]
+    pub fn disables_color() {
+        let config = Config::default();
+        let opts = Opts::parse_from(&["holding", "--no-color", "now"]);
+        assert!(!opts.use_color(true, &config));
+
+        let opts = Opts::parse_from(&["holding", "now"]);
+        assert!(!opts.use_color(false, &config));
+    }
+
+    #[test]
+    pub fn flags_override_config() {
+        let config = Config {
+            color: ColorMode::Always,
+            ..Config::default()
+        };
+        let opts = Opts::parse_from(&["holding", "now"]);
+        assert!(opts.use_color(false, &config));
+
+        let opts = Opts::parse_from(&["holding", "--no-color", "now"]);
+        assert!(!opts.use_color(false, &config));
+    }
+}
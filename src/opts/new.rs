@@ -2,7 +2,7 @@ use anyhow::Result;
 use clap::Clap;
 use std::path::Path;
 
-use crate::persistence::create_world;
+use crate::{config::Config, persistence::create_world};
 
 /// Forge a new universe
 #[derive(Clap)]
@@ -16,8 +16,8 @@ pub struct New {
 }
 
 impl New {
-    pub fn run(&self, path: &Path) -> Result<()> {
-        create_world(path, self.name.clone(), self.force)?;
+    pub fn run(&self, path: &Path, config: &Config) -> Result<()> {
+        create_world(path, self.name.clone(), config.calendar()?, self.force)?;
         println!("Created world {}", self.name);
         Ok(())
     }
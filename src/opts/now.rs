@@ -4,81 +4,300 @@ use ordinal::Ordinal;
 
 use anyhow::Result;
 use clap::Clap;
-use holding_kronos::datetime::traits::{ShowDate, ShowTime};
+use holding_kronos::{
+    calendar::DayKind,
+    datetime::{traits::ShowDate, Date, DateTime},
+};
 
-use crate::persistence::load_world;
+use crate::{opts::weather::forecast, persistence::Store, world::World};
 use holding_color::colored::*;
 use holding_solar::PlanetStore;
 
 /// Observe your surroundings.
 #[derive(Clap)]
-pub struct Now;
+pub struct Now {
+    /// Show how long the campaign has been running.
+    #[clap(long)]
+    elapsed: bool,
+}
 
 impl Now {
-    pub fn run(&self, path: &Path) -> Result<()> {
-        let world = load_world(path)?;
-        let time = world.time.into_datetime(&world.calendar);
+    pub fn run(&self, store: &Store, path: &Path) -> Result<()> {
+        let world = store.load_world(path)?;
+        println!("{}", look(&world, self.elapsed)?);
+        Ok(())
+    }
+}
 
-        println!(
-            "It is {:0>2}:{:0>2}, {} on {} the {} day of {} in the year {}\n",
-            time.hour(),
-            time.minute(),
-            time.time_of_day(),
-            time.week_day_name(),
-            Ordinal(time.day()),
-            time.month_name(),
-            time.year()
-        );
+/// Describes the surroundings of the party, as `now` prints them,
+/// with a blank line between each part.
+fn look(world: &World, elapsed: bool) -> Result<String> {
+    let time = world.time.into_datetime(&world.calendar);
+
+    let mut parts = vec![format!(
+        "It is {} {}",
+        time.format("%H:%M, %P on %A the %o day of %B"),
+        year(&time.date)
+    )];
+
+    match time.date.day_kind() {
+        DayKind::Work => {}
+        kind => parts.push(format!("Today is a {} day.", kind)),
+    }
+
+    parts.extend(forecast(world, time));
+
+    if elapsed {
+        parts.push(format!("The campaign has run for {}.", world.elapsed()?));
+    }
+
+    if world.jumped() {
+        parts.push(format!(
+            "{}",
+            "⧗ A sting in your temporal lobe indicates that this is not your native timeline..."
+                .bright_black()
+        ));
+    }
+
+    parts.push(sky(world, time));
+
+    Ok(parts.join("\n\n"))
+}
 
-        if world.jumped() {
-            println!(
-                "{}\n",
-                "⧗ A sting in your temporal lobe indicates that this is not your native timeline..."
-                    .bright_black()
+/// Describes what can be seen in the sky from the body the party is on.
+fn sky(world: &World, time: DateTime) -> String {
+    let home = match world.get_planet(world.observer()) {
+        Some(home) => home,
+        None if world.observer_body.is_some() => {
+            return format!(
+                "{}",
+                "⚠ The party is on a body that no longer exists, so there is no sky to see. \
+                 Try `holding planetarium observe` to return home."
+                    .yellow()
+            )
+        }
+        None => {
+            return format!(
+                "{}",
+                "⚠ The home planet of this world is missing, so there is no sky to see. \
+                 Try `holding planetarium validate`."
+                    .yellow()
             )
         }
+    };
 
-        let is_day = time.time_of_day().is_day();
-        if let Some(home) = world.get_planet(world.home_planet) {
-            let night_status = if is_day { "the sky" } else { "the night sky" };
-            println!(
-                "You look up at {} from {} and you see",
-                night_status,
-                home.name.color(Color::from(home.color)),
-            );
+    let is_day = home.is_day(time);
+    let night_status = if is_day { "the sky" } else { "the night sky" };
+    let mut lines = vec![format!(
+        "You look up at {} from {} and you see",
+        night_status,
+        home.name.color(Color::from(home.color)),
+    )];
 
-            if is_day {
-                if let Some(orbit) = &home.orbit {
-                    if let Some(p) = world.get_planet(orbit.parent) {
-                        let status = if p.is_luminous() {
-                            "shining brightly"
-                        } else {
-                            "hanging ominously"
-                        };
-                        let name = p.name.color(Color::from(p.color)).bold();
-                        println!(
-                            "- The planet is oribiting around {}, {} in the sky.",
-                            name, status
-                        );
-                    }
-                }
-            }
-
-            for child in home.children.iter().filter_map(|c| world.get_planet(*c)) {
-                let name = child.name.color(Color::from(child.color)).bold();
-                let phase = child.orbit.and_then(|o| o.get_phase(&world, time));
-                if let Some(phase) = phase {
-                    println!("- {} The moon {} is {}.", phase.unicode(), name, phase);
-                } else {
-                    println!("- The moon {} is floating in the sky.", name);
-                }
-            }
-
-            if home.orbit.is_none() && home.children.is_empty() {
-                println!("Space is a cold and empty place.")
-            }
+    // a luminous parent is only seen by day, but a
+    // planet can be seen looming over its moons at any time
+    if let Some(orbit) = &home.orbit {
+        if let Some(p) = world
+            .get_planet(orbit.parent)
+            .filter(|p| is_day || !p.is_luminous())
+        {
+            let status = match (p.is_luminous(), p.rings) {
+                (true, _) => "shining brightly",
+                (false, Some(_)) => "hanging ominously, its rings slicing across",
+                (false, None) => "hanging ominously",
+            };
+            let name = p.name.color(Color::from(p.color)).bold();
+            lines.push(format!(
+                "- The planet is oribiting around {}, {} in the sky.",
+                name, status
+            ));
         }
+    }
 
-        Ok(())
+    for child in home.children.iter().filter_map(|c| world.get_planet(*c)) {
+        let name = child.name.color(Color::from(child.color)).bold();
+        let phase = child.orbit.and_then(|o| o.get_phase(world, time, None));
+        let brightness = child
+            .orbit
+            .and_then(|o| o.apparent_brightness(world, time))
+            .unwrap_or_default();
+        if let Some(phase) = phase {
+            let rings = if child.rings.is_some() {
+                ", circled by rings"
+            } else {
+                ""
+            };
+            lines.push(format!(
+                "- {} The moon {} is {}, {}{}.",
+                phase.unicode(),
+                name,
+                phase,
+                glow(brightness),
+                rings
+            ));
+        } else {
+            lines.push(format!(
+                "- The moon {} {} in the sky.",
+                name,
+                child.describe()
+            ));
+        }
+    }
+
+    if home.orbit.is_none() && home.children.is_empty() {
+        lines.push("Space is a cold and empty place.".to_string())
+    }
+
+    lines.join("\n")
+}
+
+/// Names the year of a date within its era, such as "in the
+/// 11th year of the Third Age", or just the year if the date
+/// is in no era. Years are counted as in `Date::era_year`.
+fn year(date: &Date) -> String {
+    let era_year = date.era_year().and_then(|year| u64::try_from(year).ok());
+    match (date.era(), era_year) {
+        (Some(era), Some(year)) => {
+            let article = if era.name().starts_with("the ") {
+                ""
+            } else {
+                "the "
+            };
+            format!("in the {} year of {}{}", Ordinal(year), article, era.name())
+        }
+        _ => format!("in the year {}", date.year()),
+    }
+}
+
+/// Describes how brightly a body shines, relative to a full
+/// and perfectly reflective moon.
+fn glow(brightness: f64) -> &'static str {
+    match brightness {
+        b if b < 0.02 => "barely visible",
+        b if b < 0.15 => "glowing dimly",
+        _ => "shining brightly",
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use holding_solar::{PlanetId, PlanetStore, Rings};
+    use uuid::Uuid;
+
+    use crate::{persistence::Store, testing::scratch_world, world::World};
+
+    use holding_kronos::{
+        calendar::{Calendar, Era},
+        datetime::Date,
+    };
+
+    use super::{look, sky, year, Now};
+
+    #[test]
+    pub fn names_year_in_era() {
+        let mut cal = Calendar::default();
+        let date = Date::from_ymd(1011, 1, 1, &cal).expect("valid date");
+        assert_eq!(year(&date), "in the 1011th year of the Common Era");
+
+        cal.add_era(Era::new("the Third Age".to_string(), Some(1000), None));
+        let date = Date::from_ymd(1011, 1, 1, &cal).expect("valid date");
+        assert_eq!(year(&date), "in the 11th year of the Third Age");
+
+        let date = Date::from_ymd(1000, 1, 1, &cal).expect("valid date");
+        assert_eq!(year(&date), "in the 0th year of the Third Age");
+    }
+
+    #[test]
+    pub fn looks_around() {
+        let mut world = World::default();
+        let home = world.home_planet;
+        world.get_planet_mut(home).expect("home exists").axial_tilt = 0.41;
+        let steps = [
+            ("0d", "It is summer; ", "0s"),
+            ("1d", "It is summer; ", "1d"),
+            ("3mo", "It is autumn; ", "3mo1d"),
+        ];
+        for (step, season, elapsed) in steps {
+            world.update_time(step).expect("can step");
+            let output = look(&world, true).expect("can look around");
+            let parts: Vec<_> = output.split("\n\n").collect();
+            assert_eq!(parts.len(), 4, "{}", output);
+            assert!(parts[1].starts_with(season), "{}", output);
+            assert_eq!(parts[2], format!("The campaign has run for {}.", elapsed));
+            assert!(
+                parts[3].starts_with("You look up at the night sky from Earth"),
+                "{}",
+                output
+            );
+        }
+        let path = scratch_world(&world);
+        Now { elapsed: true }
+            .run(&Store::default(), &path)
+            .expect("can look around");
+    }
+
+    #[test]
+    pub fn describes_sky() {
+        let world = World::default();
+        let time = world.time.into_datetime(&world.calendar);
+        assert!(sky(&world, time).starts_with("You look up at"));
+    }
+
+    #[test]
+    pub fn sees_home_planet_from_moon() {
+        let mut world = World::default();
+        let moon = world
+            .bodies
+            .iter()
+            .find(|b| b.name == "Moon")
+            .expect("the moon exists")
+            .id;
+        world.observer_body = Some(moon);
+        let time = world.time.into_datetime(&world.calendar);
+
+        let sky = sky(&world, time);
+        assert!(sky.contains("from Moon"), "{}", sky);
+        assert!(sky.contains("Earth"), "{}", sky);
+    }
+
+    #[test]
+    pub fn sees_rings_of_home_planet() {
+        let mut world = World::default();
+        let moon = world
+            .bodies
+            .iter()
+            .find(|b| b.name == "Moon")
+            .expect("the moon exists")
+            .id;
+        world.observer_body = Some(moon);
+        let time = world.time.into_datetime(&world.calendar);
+        assert!(!sky(&world, time).contains("rings"));
+
+        let earth = world
+            .bodies
+            .iter_mut()
+            .find(|b| b.name == "Earth")
+            .expect("the earth exists");
+        earth.rings = Some(Rings {
+            inner: 1.0e7,
+            outer: 2.0e7,
+        });
+        let time = world.time.into_datetime(&world.calendar);
+        let sky = sky(&world, time);
+        assert!(sky.contains("its rings"), "{}", sky);
+    }
+
+    #[test]
+    pub fn warns_without_home_planet() {
+        let mut world = World::default();
+        world.home_planet = PlanetId(Uuid::new_v4());
+        let time = world.time.into_datetime(&world.calendar);
+
+        let sky = sky(&world, time);
+        assert!(
+            sky.contains("home planet of this world is missing"),
+            "{}",
+            sky
+        );
     }
 }
@@ -1,26 +1,328 @@
-use std::path::Path;
+use std::{convert::TryFrom, path::Path};
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use clap::Clap;
+use holding_kronos::datetime::{DateTime, RawDateTime};
+use itertools::Itertools;
 
-use crate::persistence::{load_world, save_world};
+use crate::{config::Config, persistence::Store, world::World};
 
 /// Records a piece of information about the world.
 #[derive(Clap)]
 pub struct AddRecord {
     /// The information that is to be saved.
-    note: String,
+    note: Option<String>,
+
+    /// Who is writing the record.
+    #[clap(long = "as")]
+    author: Option<String>,
+
+    /// When it happened, if not now. This is either a date such
+    /// as 1101-02-12, or a time ago such as -1d.
+    #[clap(long, allow_hyphen_values = true)]
+    at: Option<String>,
+
+    /// Record new characters and locations even if their
+    /// names look like typos of ones that already exist.
+    #[clap(long)]
+    no_suggest: bool,
+
+    #[clap(subcommand)]
+    template: Option<RecordTemplate>,
+}
+
+#[derive(Clap)]
+pub enum RecordTemplate {
+    /// Records a note from a saved template, such as
+    /// `holding record tpl levelup gandalf 5`.
+    Tpl(Template),
+}
+
+#[derive(Clap)]
+pub struct Template {
+    /// The name of the template.
+    name: String,
+
+    /// Save this note as the template, rather than recording it.
+    /// It may use {0}, {1} and so on to stand for the arguments.
+    #[clap(long)]
+    save: Option<String>,
+
+    /// The values to put in place of {0}, {1} and so on.
+    #[clap(allow_hyphen_values = true)]
+    args: Vec<String>,
 }
 
 impl AddRecord {
-    pub fn run(&self, path: &Path) -> Result<()> {
-        let mut world = load_world(path)?;
-        let time = world.time;
+    pub fn run(&self, store: &mut Store, path: &Path, config: &Config) -> Result<()> {
+        let mut world = store.load_world(path)?;
+
+        let note = match (&self.note, &self.template) {
+            (
+                _,
+                Some(RecordTemplate::Tpl(Template {
+                    name,
+                    save: Some(template),
+                    ..
+                })),
+            ) => {
+                world.save_record_template(name.clone(), template.clone());
+                store.save_world(path, &world)?;
+                println!("Saved {} as {}.", template, name);
+                return Ok(());
+            }
+            (_, Some(RecordTemplate::Tpl(Template { name, args, .. }))) => {
+                let template = world
+                    .record_template(name)
+                    .ok_or_else(|| anyhow!("There is no template called {}.", name))?;
+                expand(template, args)?
+            }
+            (Some(note), None) => note.clone(),
+            (None, None) => return Err(anyhow!("There is nothing to record.")),
+        };
+
+        if !self.no_suggest {
+            let suggestions = world.suggest_references(&note);
+            if !suggestions.is_empty() {
+                return Err(anyhow!(
+                    "{}\nPass --no-suggest to record it anyway.",
+                    suggestions
+                        .iter()
+                        .map(|(typo, known)| format!("Did you mean {} instead of {}?", known, typo))
+                        .join("\n")
+                ));
+            }
+        }
 
-        let record = world.add_record(self.note.clone());
-        println!("Noted at {}:\n{}", time, record.note);
+        let author = self.author.clone().or_else(|| config.author.clone());
+        let record = match &self.at {
+            Some(expr) => {
+                let date = past(&world, expr)?;
+                world.add_record_at(note, author, date)?
+            }
+            None => world.add_record_by(note, author),
+        };
+        println!("Noted at {}:\n{}", record.date, record.note);
 
-        save_world(path, &world)?;
+        store.save_world(path, &world)?;
         Ok(())
     }
 }
+
+/// Fills in the `{0}`, `{1}`, ... placeholders of a template
+/// with the given arguments. Braces around anything else are
+/// left as they are.
+fn expand(template: &str, args: &[String]) -> Result<String> {
+    let mut note = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        note.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let index = rest
+            .find('}')
+            .and_then(|end| Some((rest[1..end].parse::<usize>().ok()?, end)));
+        match index {
+            Some((index, end)) => {
+                let arg = args.get(index).ok_or_else(|| {
+                    anyhow!(
+                        "The template needs at least {} arguments but got {}.",
+                        index + 1,
+                        args.len()
+                    )
+                })?;
+                note.push_str(arg);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                note.push('{');
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    note.push_str(rest);
+    Ok(note)
+}
+
+/// Parses a time relative to now, where a leading `-` counts backwards.
+fn past(world: &World, expr: &str) -> Result<RawDateTime> {
+    let now = world.time.into_datetime(&world.calendar);
+    let time = match expr.strip_prefix('-') {
+        Some(ago) => {
+            let seconds = world
+                .calendar
+                .parse_duration(ago)?
+                .to_seconds(&world.calendar);
+            DateTime::from_seconds(
+                now.total_seconds() - i64::try_from(seconds)?,
+                &world.calendar,
+            )
+        }
+        None => world.calendar.parse(expr, now)?,
+    };
+    Ok(time.into())
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use crate::{
+        character::{CharacterStore, LocationStore},
+        config::Config,
+        persistence::{load_world, Store},
+        testing::{add_character, scratch_dir, scratch_world},
+        world::World,
+    };
+
+    use super::{AddRecord, RecordTemplate, Template};
+
+    #[test]
+    pub fn stamps_author_from_config() {
+        let dir = scratch_dir();
+        fs::create_dir_all(dir.join(".holding")).expect("can create dir");
+        fs::write(dir.join(".holding").join("config"), "author: gandalf\n")
+            .expect("can write config");
+        let config = Config::find(&dir.join("nested")).expect("config is valid");
+        let path = scratch_world(&World::default());
+
+        let record = |author: Option<&str>| AddRecord {
+            note: Some("The party arrived at #waterdeep".to_string()),
+            author: author.map(ToString::to_string),
+            at: None,
+            no_suggest: false,
+            template: None,
+        };
+        record(None)
+            .run(&mut Store::default(), &path, &config)
+            .expect("can record");
+        record(Some("frodo"))
+            .run(&mut Store::default(), &path, &config)
+            .expect("can record");
+
+        let world = load_world(&path).expect("world loads");
+        assert_eq!(world.records[0].author.as_deref(), Some("gandalf"));
+        assert_eq!(world.records[1].author.as_deref(), Some("frodo"));
+    }
+
+    #[test]
+    pub fn backdates_records() {
+        let mut world = World::default();
+        world.add_record("The party arrived at #waterdeep".to_string());
+        world.update_time("2d").expect("can step");
+        world.add_record("The party left #waterdeep".to_string());
+        let now = world.time;
+        let path = scratch_world(&world);
+
+        AddRecord {
+            note: Some("The party heard rumours of a dragon".to_string()),
+            author: None,
+            at: Some("-1d".to_string()),
+            no_suggest: false,
+            template: None,
+        }
+        .run(&mut Store::default(), &path, &Config::default())
+        .expect("can record");
+
+        let world = load_world(&path).expect("world loads");
+        assert_eq!(world.time, now);
+        let notes: Vec<_> = world.records.iter().map(|r| r.note.as_str()).collect();
+        assert_eq!(
+            notes,
+            vec![
+                "The party arrived at #waterdeep",
+                "The party heard rumours of a dragon",
+                "The party left #waterdeep",
+            ]
+        );
+
+        let waterdeep = world.lookup_location("waterdeep").expect("location exists");
+        let mentions = world.records_for_location(waterdeep.id);
+        assert!(mentions.windows(2).all(|w| w[0].date <= w[1].date));
+
+        let future = AddRecord {
+            note: Some("The dragon attacked".to_string()),
+            author: None,
+            at: Some("1d".to_string()),
+            no_suggest: false,
+            template: None,
+        };
+        assert!(future
+            .run(&mut Store::default(), &path, &Config::default())
+            .is_err());
+    }
+
+    #[test]
+    pub fn suggests_known_character() {
+        let mut world = World::default();
+        add_character(&mut world, "gandalf");
+        let path = scratch_world(&world);
+        let record = |no_suggest| AddRecord {
+            note: Some("@gandlaf arrived".to_string()),
+            author: None,
+            at: None,
+            no_suggest,
+            template: None,
+        };
+
+        let err = record(false)
+            .run(&mut Store::default(), &path, &Config::default())
+            .expect_err("suggests a name");
+        assert!(err.to_string().contains("Did you mean @gandalf"));
+        assert!(load_world(&path).expect("world loads").records.is_empty());
+
+        record(true)
+            .run(&mut Store::default(), &path, &Config::default())
+            .expect("can record");
+        let world = load_world(&path).expect("world loads");
+        assert_eq!(world.records.len(), 1);
+        assert!(world.lookup_character("gandlaf").is_some());
+    }
+
+    #[test]
+    pub fn records_from_template() {
+        let path = scratch_world(&World::default());
+        let tpl = |name: &str, save: Option<&str>, args: &[&str]| AddRecord {
+            note: None,
+            author: None,
+            at: None,
+            no_suggest: false,
+            template: Some(RecordTemplate::Tpl(Template {
+                name: name.to_string(),
+                save: save.map(ToString::to_string),
+                args: args.iter().map(ToString::to_string).collect(),
+            })),
+        };
+
+        tpl("levelup", Some("@{0} reached level {1} in #{2}"), &[])
+            .run(&mut Store::default(), &path, &Config::default())
+            .expect("can save template");
+        tpl("levelup", None, &["gandalf", "5", "waterdeep"])
+            .run(&mut Store::default(), &path, &Config::default())
+            .expect("can record from template");
+
+        let world = load_world(&path).expect("world loads");
+        assert_eq!(
+            world.record_template("levelup"),
+            Some("@{0} reached level {1} in #{2}")
+        );
+        assert_eq!(world.records.len(), 1);
+        assert_eq!(
+            world.records[0].note,
+            "@gandalf reached level 5 in #waterdeep"
+        );
+        let gandalf = world.lookup_character("gandalf").expect("character exists");
+        assert_eq!(world.records_for_character(gandalf.id).len(), 1);
+        let waterdeep = world.lookup_location("waterdeep").expect("location exists");
+        assert_eq!(world.records_for_location(waterdeep.id).len(), 1);
+
+        assert!(tpl("levelup", None, &["gandalf"])
+            .run(&mut Store::default(), &path, &Config::default())
+            .is_err());
+        assert!(tpl("missing", None, &[])
+            .run(&mut Store::default(), &path, &Config::default())
+            .is_err());
+        assert_eq!(load_world(&path).expect("world loads").records.len(), 1);
+    }
+}
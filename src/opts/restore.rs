@@ -0,0 +1,109 @@
+use std::path::Path;
+
+use anyhow::Result;
+use clap::Clap;
+
+use crate::persistence::{list_backups, restore_backup, Store, BACKUPS_KEPT};
+
+/// Go back to an earlier save of the world.
+///
+/// The last few saves are kept in `.holding/backups`
+/// next to the world, newest last.
+#[derive(Clap)]
+pub struct Restore {
+    /// The name of the backup to restore. If left
+    /// out, the backups are listed instead.
+    backup: Option<String>,
+}
+
+impl Restore {
+    pub fn run(&self, store: &mut Store, path: &Path) -> Result<()> {
+        match &self.backup {
+            Some(name) => {
+                let world = restore_backup(store, path, name)?;
+                println!("Restored {} from {}.", world.name, name);
+            }
+            None => {
+                let backups = list_backups(path);
+                if backups.is_empty() {
+                    println!("There are no backups yet.");
+                }
+                for backup in backups {
+                    if let Some(name) = backup.file_name() {
+                        println!("{}", name.to_string_lossy());
+                    }
+                }
+                println!("(the last {} saves are kept)", BACKUPS_KEPT);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        persistence::{list_backups, read_world, save_world, Store, BACKUPS_KEPT},
+        testing::{scratch_dir, scratch_world},
+        world::World,
+    };
+
+    use super::Restore;
+
+    #[test]
+    pub fn rotates_and_restores_backups() {
+        let mut world = World::default();
+        let path = scratch_world(&world);
+        assert!(list_backups(&path).is_empty());
+
+        for i in 0..BACKUPS_KEPT + 3 {
+            world.add_record(format!("Day {}", i));
+            save_world(&path, &world).expect("can save");
+        }
+
+        let backups = list_backups(&path);
+        assert_eq!(backups.len(), BACKUPS_KEPT);
+        let oldest = read_world(&backups[0]).expect("backup reads");
+        assert_eq!(oldest.records.len(), 3, "the oldest saves are dropped");
+        let newest = read_world(&backups[BACKUPS_KEPT - 1]).expect("backup reads");
+        assert_eq!(newest.records.len(), BACKUPS_KEPT + 2);
+
+        let name = backups[0]
+            .file_name()
+            .expect("backups are files")
+            .to_string_lossy()
+            .into_owned();
+        Restore { backup: Some(name) }
+            .run(&mut Store::default(), &path)
+            .expect("can restore");
+
+        let restored = read_world(&path).expect("world reads");
+        assert_eq!(restored.records.len(), 3);
+        assert_eq!(list_backups(&path).len(), BACKUPS_KEPT);
+        let undo = list_backups(&path).pop().expect("there are backups");
+        assert_eq!(
+            read_world(&undo).expect("backup reads").records.len(),
+            BACKUPS_KEPT + 3,
+            "the replaced world is backed up"
+        );
+
+        assert!(Restore {
+            backup: Some("nothing.yaml".to_string())
+        }
+        .run(&mut Store::default(), &path)
+        .is_err());
+    }
+
+    #[test]
+    pub fn keeps_backups_of_similar_names_apart() {
+        let dir = scratch_dir();
+        let (tavern, old) = (dir.join("tavern.yaml"), dir.join("tavern-old.yaml"));
+        for path in &[&tavern, &tavern, &old, &old, &old] {
+            save_world(path, &World::default()).expect("can save");
+        }
+
+        assert_eq!(list_backups(&tavern).len(), 1);
+        assert_eq!(list_backups(&old).len(), 2);
+    }
+}
@@ -0,0 +1,141 @@
+use std::{
+    io::Write,
+    path::Path,
+    process::{Command, Stdio},
+    thread,
+};
+
+use anyhow::{anyhow, Context, Result};
+use clap::Clap;
+
+use crate::{config::Config, persistence::Store, world::World};
+
+/// Run a script from the config against the world.
+///
+/// The script is given the world as yaml on stdin, and may
+/// print a changed world to stdout to replace it. If it
+/// prints nothing, the world is left as it was.
+#[derive(Clap)]
+pub struct Run {
+    /// The name of the script in the config.
+    script: String,
+
+    /// Arguments to pass on to the script.
+    #[clap(allow_hyphen_values = true)]
+    args: Vec<String>,
+}
+
+impl Run {
+    pub fn run(&self, store: &mut Store, path: &Path, config: &Config) -> Result<()> {
+        let program = config
+            .scripts
+            .get(&self.script)
+            .ok_or_else(|| anyhow!("There is no script called {}.", self.script))?;
+
+        let world = store.load_world(path)?;
+        if let Some(world) = run_script(program, &self.args, &world)
+            .with_context(|| format!("The script {} failed.", self.script))?
+        {
+            store.save_world(path, &world)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Runs a program with the world on its stdin, returning
+/// the world it prints, or None if it prints nothing. The
+/// returned world must be valid.
+fn run_script(program: &Path, args: &[String], world: &World) -> Result<Option<World>> {
+    let input = serde_yaml::to_string(world)?;
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Couldn't start {}.", program.display()))?;
+
+    // write from another thread so that a script that prints
+    // as it reads can't fill its stdout while we wait on stdin
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    let writer = thread::spawn(move || stdin.write_all(input.as_bytes()));
+    let output = child.wait_with_output()?;
+    // a script that ignores its input may close stdin early
+    writer.join().ok();
+
+    if !output.status.success() {
+        return Err(anyhow!("It exited with {}.", output.status));
+    }
+    if output.stdout.iter().all(u8::is_ascii_whitespace) {
+        return Ok(None);
+    }
+
+    let world: World =
+        serde_yaml::from_slice(&output.stdout).context("It returned a corrupted world.")?;
+    world.validate().context("It returned an invalid world.")?;
+    Ok(Some(world))
+}
+
+#[cfg(test)]
+mod test {
+    use std::{collections::HashMap, path::PathBuf};
+
+    use crate::{
+        config::Config,
+        persistence::{read_world, Store},
+        testing::scratch_world,
+        world::World,
+    };
+
+    use super::Run;
+
+    fn config(name: &str, program: &str) -> Config {
+        let mut scripts = HashMap::new();
+        scripts.insert(name.to_string(), PathBuf::from(program));
+        Config {
+            scripts,
+            ..Config::default()
+        }
+    }
+
+    fn run(script: &str) -> Run {
+        Run {
+            script: script.to_string(),
+            args: vec![],
+        }
+    }
+
+    #[test]
+    pub fn identity_script_keeps_world() {
+        let mut world = World::default();
+        world.add_record("The party arrived at #waterdeep".to_string());
+        let path = scratch_world(&world);
+        let before =
+            serde_yaml::to_string(&read_world(&path).expect("world reads")).expect("can serialize");
+
+        run("identity")
+            .run(&mut Store::default(), &path, &config("identity", "cat"))
+            .expect("can run script");
+
+        let after =
+            serde_yaml::to_string(&read_world(&path).expect("world reads")).expect("can serialize");
+        assert_eq!(before, after);
+    }
+
+    #[test]
+    pub fn failing_script_changes_nothing() {
+        let path = scratch_world(&World::default());
+        let before = std::fs::read_to_string(path.join("world.yaml")).expect("world exists");
+
+        let config = config("broken", "false");
+        assert!(run("broken")
+            .run(&mut Store::default(), &path, &config)
+            .is_err());
+        assert!(run("missing")
+            .run(&mut Store::default(), &path, &config)
+            .is_err());
+
+        let after = std::fs::read_to_string(path.join("world.yaml")).expect("world exists");
+        assert_eq!(before, after);
+    }
+}
@@ -0,0 +1,195 @@
+use std::{
+    io::{self, BufRead, Write},
+    iter,
+    path::Path,
+};
+
+use anyhow::{anyhow, Result};
+use clap::Clap;
+
+use crate::{config::Config, persistence::Store};
+
+use super::{Command, Opts};
+
+/// Runs many commands against the world, loading it once
+/// and saving it on exit. Leave with `exit` or ctrl-d.
+#[derive(Clap)]
+pub struct Shell {}
+
+impl Shell {
+    pub fn run(&self, path: &Path, config: &Config, input: &mut dyn BufRead) -> Result<()> {
+        let mut store = Store::session(path)?;
+        let result = read_commands(&mut store, path, config, input);
+        let saved = store.close()?;
+        result?;
+
+        if saved {
+            println!("The world has been saved.");
+        }
+        Ok(())
+    }
+}
+
+/// Reads and runs commands until the input runs out. A
+/// failing command is reported without leaving the shell.
+///
+/// Commands run against the world the shell was opened on, unless
+/// they pick another with `-p` or `--world`.
+fn read_commands(
+    store: &mut Store,
+    path: &Path,
+    config: &Config,
+    input: &mut dyn BufRead,
+) -> Result<()> {
+    loop {
+        print!("> ");
+        io::stdout().flush()?;
+
+        let mut line = String::new();
+        if input.read_line(&mut line)? == 0 {
+            return Ok(());
+        }
+
+        let words = match split(&line) {
+            Ok(words) => words,
+            Err(e) => {
+                println!("{}", e);
+                continue;
+            }
+        };
+        match words.first().map(String::as_str) {
+            None => continue,
+            Some("exit") | Some("quit") => return Ok(()),
+            _ => {}
+        }
+
+        let opts = match Opts::try_parse_from(iter::once("holding".to_string()).chain(words)) {
+            Ok(opts) => opts,
+            Err(e) => {
+                println!("{}", e);
+                continue;
+            }
+        };
+
+        let path = opts.world_path(path);
+        let result = match opts.command {
+            Command::Shell(_) => Err(anyhow!("You are already in the shell.")),
+            Command::New(_) => Err(anyhow!("Leave the shell to create a new world.")),
+            command => command.run(store, &path, config, input),
+        };
+        if let Err(e) = result {
+            println!("{:#}", e);
+        }
+    }
+}
+
+/// Splits a line into words on whitespace,
+/// keeping quoted text together.
+fn split(line: &str) -> Result<Vec<String>> {
+    let mut words = vec![];
+    let mut word: Option<String> = None;
+    let mut quote = None;
+
+    for c in line.chars() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => word.get_or_insert_with(String::new).push(c),
+            None if c == '"' || c == '\'' => {
+                quote = Some(c);
+                word.get_or_insert_with(String::new);
+            }
+            None if c.is_whitespace() => words.extend(word.take()),
+            None => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+
+    if quote.is_some() {
+        return Err(anyhow!("There is an unclosed quote."));
+    }
+    words.extend(word);
+    Ok(words)
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::Cursor;
+
+    use test_case::test_case;
+
+    use crate::{
+        config::Config,
+        persistence::{load_world, read_world},
+        testing::scratch_world,
+        world::World,
+    };
+
+    use super::{split, Shell};
+
+    #[test_case("now", &["now"] ; "single word")]
+    #[test_case("  time   step 2d\n", &["time", "step", "2d"] ; "extra whitespace")]
+    #[test_case(r#"record "The party arrived""#, &["record", "The party arrived"] ; "double quotes")]
+    #[test_case("record 'It''s late'", &["record", "Its late"] ; "adjacent quotes")]
+    #[test_case("record ''", &["record", ""] ; "empty quotes")]
+    pub fn splits_words(line: &str, expected: &[&str]) {
+        assert_eq!(split(line).expect("can split"), expected);
+    }
+
+    #[test]
+    pub fn rejects_unclosed_quote() {
+        assert!(split("record \"The party").is_err());
+    }
+
+    #[test]
+    pub fn runs_scripted_commands() {
+        let world = World::default();
+        let start = world.time;
+        let path = scratch_world(&world);
+
+        let mut script = Cursor::new(
+            "record \"The party arrived at #waterdeep\"\n\
+             time step 2d\n\
+             now\n\
+             not a command\n\
+             shell\n\
+             record 'They rested'\n\
+             exit\n\
+             record 'This is never read'\n",
+        );
+        Shell {}
+            .run(&path, &Config::default(), &mut script)
+            .expect("shell runs");
+
+        let world = load_world(&path).expect("world loads");
+        assert_eq!(world.records.len(), 2);
+        assert!(world.time > start);
+        assert_eq!(
+            read_world(&path).expect("world reads").records.len(),
+            world.records.len()
+        );
+    }
+
+    #[test]
+    pub fn honors_path_flag() {
+        let path = scratch_world(&World::default());
+        let other = scratch_world(&World::default());
+
+        let mut script = Cursor::new(format!(
+            "-p {} record 'They went elsewhere'\nrecord 'They stayed'\n",
+            other.display()
+        ));
+        Shell {}
+            .run(&path, &Config::default(), &mut script)
+            .expect("shell runs");
+
+        let notes = |path| {
+            read_world(path)
+                .expect("world reads")
+                .records
+                .into_iter()
+                .map(|r| r.note)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(notes(&other), ["They went elsewhere"]);
+        assert_eq!(notes(&path), ["They stayed"]);
+    }
+}
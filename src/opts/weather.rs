@@ -1,14 +1,244 @@
 use std::path::Path;
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use clap::Clap;
+use holding_kronos::{calendar::traits::ConvertTime, datetime::DateTime};
+use holding_solar::{Kelvin, PlanetStore, Season};
+
+use crate::{persistence::Store, world::World};
 
 /// Prints information about the current weather.
 #[derive(Clap)]
-pub struct Weather {}
+pub struct Weather {
+    #[clap(subcommand)]
+    command: Option<WeatherCommand>,
+}
+
+#[derive(Clap)]
+pub enum WeatherCommand {
+    /// Decides the weather on some day, rather than leaving it to chance.
+    Set(SetWeather),
+}
+
+#[derive(Clap)]
+pub struct SetWeather {
+    /// The day to set the weather for, such as 1101-02-12, or 0d for today.
+    #[clap(allow_hyphen_values = true)]
+    expr: String,
+
+    /// What the weather is like, such as "a storm rages".
+    description: String,
+}
 
 impl Weather {
-    pub fn run(&self, _path: &Path) -> Result<()> {
-        todo!();
+    pub fn run(&self, store: &mut Store, path: &Path) -> Result<()> {
+        let mut world = store.load_world(path)?;
+        let time = world.time.into_datetime(&world.calendar);
+
+        if let Some(WeatherCommand::Set(SetWeather { expr, description })) = &self.command {
+            let date = world.calendar.parse(expr, time)?.date.into();
+            let description = description.trim_end_matches('.').to_string();
+            println!("The weather on {} will be: {}.", date, description);
+            world.set_weather(date, description);
+            store.save_world(path, &world)?;
+            return Ok(());
+        }
+
+        let forecast = forecast(&world, time)
+            .ok_or_else(|| anyhow!("The home planet does not experience seasons."))?;
+        println!("{}", forecast);
+        Ok(())
+    }
+}
+
+/// Summarises the season and weather where the party is in a
+/// single line, such as "It is autumn; cold rain falls."
+///
+/// The weather is derived from the temperature of the planet, shifted
+/// by the season, and the day so that it is the same all day long,
+/// unless it has been set for the day. Weather that has been set is
+/// reported even if that body does not experience seasons, on
+/// its own. Otherwise, returns None for planets without seasons.
+pub fn forecast(world: &World, time: DateTime) -> Option<String> {
+    let body = world.get_planet(world.observer())?;
+    let season = body
+        .orbit
+        .as_ref()
+        .and_then(|orbit| body.season(time.seconds_modulo(orbit.period)));
+
+    if let Some(weather) = world.weather_on(time.date.into()) {
+        return Some(match season {
+            Some(season) => format!("It is {}; {}.", season.to_string().to_lowercase(), weather),
+            None => {
+                let mut chars = weather.chars();
+                let first = chars.next().map(|c| c.to_uppercase().collect::<String>());
+                format!("{}{}.", first.unwrap_or_default(), chars.as_str())
+            }
+        });
+    }
+
+    let season = season?;
+
+    let shift = match season {
+        Season::Summer => Kelvin(8),
+        Season::Winter => Kelvin(-12),
+        Season::Spring | Season::Autumn => Kelvin(-2),
+    };
+    let temperature = body.temperature + shift;
+    let feel = match temperature.0 {
+        t if t < 273 => "freezing",
+        t if t < 283 => "cold",
+        t if t < 293 => "mild",
+        t if t < 303 => "warm",
+        _ => "hot",
+    };
+
+    let day = time
+        .total_seconds()
+        .div_euclid(i64::from(world.calendar.days_to_seconds(1)));
+    let weather = match mix(u64::from_le_bytes(day.to_le_bytes())) % 4 {
+        0 => format!("the {} air is still and the sky clear", feel),
+        1 => "clouds gather overhead".to_string(),
+        2 if temperature < Kelvin(273) => "snow falls".to_string(),
+        2 => format!("{} rain falls", feel),
+        _ => format!("a {} wind blows", feel),
+    };
+
+    Some(format!(
+        "It is {}; {}.",
+        season.to_string().to_lowercase(),
+        weather
+    ))
+}
+
+/// Scrambles a number with the splitmix64 finalizer, so that
+/// consecutive days don't have the weather in a fixed rotation.
+fn mix(x: u64) -> u64 {
+    let x = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    let x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^ (x >> 31)
+}
+
+#[cfg(test)]
+mod test {
+    use holding_kronos::datetime::traits::ModifyDate;
+    use holding_solar::{PlanetStore, Season};
+
+    use crate::{
+        persistence::{load_world, Store},
+        testing::scratch_world,
+        world::World,
+    };
+
+    use super::{forecast, SetWeather, Weather, WeatherCommand};
+
+    fn seasonal() -> World {
+        let mut world = World::default();
+        let home = world.home_planet;
+        world.get_planet_mut(home).expect("home exists").axial_tilt = 0.41;
+        world
+    }
+
+    #[test]
+    pub fn forecasts_season() {
+        let world = seasonal();
+        let time = world.time.into_datetime(&world.calendar);
+        let home = world.get_planet(world.home_planet).expect("home exists");
+        let season = home
+            .season(time.seconds_modulo(home.orbit.expect("orbits the sun").period))
+            .expect("tilted planets have seasons");
+
+        let line = forecast(&world, time).expect("has a forecast");
+        assert_eq!(season, Season::Summer);
+        assert!(line.starts_with("It is summer; "), "{}", line);
+        assert!(line.ends_with('.'));
+
+        let path = scratch_world(&world);
+        Weather { command: None }
+            .run(&mut Store::default(), &path)
+            .expect("can forecast");
+    }
+
+    #[test]
+    pub fn weather_does_not_rotate() {
+        let world = seasonal();
+        let start = world.time.into_datetime(&world.calendar);
+        let on = |day| forecast(&world, start.add_days(day)).expect("has a forecast");
+        assert!((0..28).any(|day| on(day) != on(day + 4)));
+    }
+
+    #[test]
+    pub fn forecasts_for_the_observer() {
+        let mut world = seasonal();
+        let moon = world
+            .bodies
+            .iter()
+            .find(|b| b.name == "Moon")
+            .expect("the moon exists")
+            .id;
+        world.observer_body = Some(moon);
+        let time = world.time.into_datetime(&world.calendar);
+        assert_eq!(forecast(&world, time), None);
+
+        world.get_planet_mut(moon).expect("moon exists").axial_tilt = 0.41;
+        let time = world.time.into_datetime(&world.calendar);
+        assert!(forecast(&world, time).is_some());
+    }
+
+    #[test]
+    pub fn no_seasons_without_tilt() {
+        let world = World::default();
+        let time = world.time.into_datetime(&world.calendar);
+        assert_eq!(forecast(&world, time), None);
+    }
+
+    #[test]
+    pub fn reports_set_weather_without_seasons() {
+        let mut world = World::default();
+        let today = world.time.into_datetime(&world.calendar).date.into();
+        world.set_weather(today, "a great storm rages".to_string());
+        let time = world.time.into_datetime(&world.calendar);
+        assert_eq!(
+            forecast(&world, time).as_deref(),
+            Some("A great storm rages.")
+        );
+    }
+
+    #[test]
+    pub fn reports_set_weather() {
+        let path = scratch_world(&seasonal());
+        let set = |expr: &str, description: &str| Weather {
+            command: Some(WeatherCommand::Set(SetWeather {
+                expr: expr.to_string(),
+                description: description.to_string(),
+            })),
+        };
+
+        set("0d", "a great storm rages.")
+            .run(&mut Store::default(), &path)
+            .expect("can set the weather");
+        set("1d", "the storm has passed")
+            .run(&mut Store::default(), &path)
+            .expect("can set the weather");
+
+        let mut world = load_world(&path).expect("world loads");
+        let today = world.time.into_datetime(&world.calendar);
+        assert_eq!(
+            forecast(&world, today).as_deref(),
+            Some("It is summer; a great storm rages.")
+        );
+
+        world.update_time("1d").expect("can step");
+        let tomorrow = world.time.into_datetime(&world.calendar);
+        assert_eq!(
+            forecast(&world, tomorrow).as_deref(),
+            Some("It is summer; the storm has passed.")
+        );
+
+        world.update_time("1d").expect("can step");
+        let later = world.time.into_datetime(&world.calendar);
+        let generated = forecast(&world, later).expect("has a forecast");
+        assert!(!generated.contains("storm"), "{}", generated);
     }
 }
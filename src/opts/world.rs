@@ -0,0 +1,123 @@
+use std::path::Path;
+
+use anyhow::Result;
+use clap::Clap;
+
+use crate::{
+    persistence::{list_worlds, read_format_version},
+    world::FORMAT_VERSION,
+};
+
+/// Inspect the file the world is kept in.
+#[derive(Clap)]
+pub enum WorldFile {
+    /// Shows the format version of the world, and whether it needs
+    /// migrating. The world is saved in the newest format by any
+    /// command that changes it.
+    FormatVersion,
+}
+
+impl WorldFile {
+    pub fn run(&self, path: &Path) -> Result<()> {
+        match self {
+            WorldFile::FormatVersion => println!("{}", format_version(read_format_version(path)?)),
+        }
+
+        Ok(())
+    }
+}
+
+/// Find the other worlds kept alongside this one.
+#[derive(Clap)]
+pub enum Worlds {
+    /// Lists the worlds that can be chosen with `--world`.
+    List,
+}
+
+impl Worlds {
+    pub fn run(&self, path: &Path) -> Result<()> {
+        match self {
+            Worlds::List => {
+                let worlds = list_worlds(path);
+                if worlds.is_empty() {
+                    println!("There are no other worlds here.");
+                }
+                for (name, path) in worlds {
+                    println!("{} ({})", name, path.display());
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Describes a format version relative to the newest one.
+fn format_version(version: u32) -> String {
+    if version < FORMAT_VERSION {
+        format!("v{} (needs migration to v{})", version, FORMAT_VERSION)
+    } else {
+        format!("v{} (up to date)", version)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use crate::{
+        persistence::{find_world, list_worlds, load_world, read_format_version, save_world},
+        testing::{scratch_dir, scratch_world},
+        world::{World, FORMAT_VERSION},
+    };
+
+    use super::{format_version, Worlds};
+
+    #[test]
+    pub fn reports_unversioned_world() {
+        let path = scratch_dir();
+        let world = serde_yaml::to_string(&World::default()).expect("can serialize");
+        let old: String = world
+            .lines()
+            .filter(|l| !l.starts_with("version:"))
+            .map(|l| format!("{}\n", l))
+            .collect();
+        fs::write(path.join("world.yaml"), old).expect("can write world");
+
+        let version = read_format_version(&path).expect("can read version");
+        assert_eq!(version, 0);
+        assert_eq!(format_version(version), "v0 (needs migration to v1)");
+
+        let world = load_world(&path).expect("old worlds still load");
+        let path = scratch_world(&world);
+        assert_eq!(
+            read_format_version(&path).expect("can read version"),
+            FORMAT_VERSION
+        );
+    }
+
+    #[test]
+    pub fn selects_worlds_by_name() {
+        let path = scratch_dir();
+        let world = |name: &str| {
+            let mut world = World::default();
+            world.name = name.to_string();
+            world
+        };
+        fs::create_dir_all(path.join("tavern")).expect("can create folder");
+        save_world(&path.join("tavern"), &world("The Prancing Pony")).expect("can save");
+        fs::create_dir_all(path.join("worlds")).expect("can create folder");
+        save_world(&path.join("worlds/dungeon.yaml"), &world("The Mines")).expect("can save");
+        fs::write(path.join("notes.yaml"), "- not a world").expect("can write notes");
+
+        let tavern = load_world(&find_world(&path, "tavern")).expect("tavern loads");
+        let dungeon = load_world(&find_world(&path, "dungeon")).expect("dungeon loads");
+        assert_eq!(tavern.name, "The Prancing Pony");
+        assert_eq!(dungeon.name, "The Mines");
+        assert!(load_world(&find_world(&path, "notes")).is_err());
+
+        let names: Vec<_> = list_worlds(&path).into_iter().map(|(n, _)| n).collect();
+        assert_eq!(names, vec!["dungeon", "tavern"]);
+        Worlds::List.run(&path).expect("can list worlds");
+    }
+}
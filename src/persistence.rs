@@ -1,74 +1,435 @@
 use std::{
     ffi::OsStr,
-    fs::{create_dir_all, File},
-    path::Path,
+    fs::{self, create_dir_all, File},
+    io,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
 };
 
 use anyhow::{anyhow, Context, Result};
+use holding_kronos::calendar::Calendar;
+use serde::Deserialize;
 use thiserror::Error;
 
-use crate::world::World;
+use crate::world::{World, FORMAT_VERSION};
+
+/// A world held in memory so that many commands can share a
+/// single load and save, such as those run from the shell.
+struct Session {
+    path: PathBuf,
+    world: World,
+    changed: bool,
+}
+
+/// Where commands load worlds from and save them to. By default
+/// this is straight to and from disk, but a store can instead hold
+/// one world in a session until it is closed.
+#[derive(Default)]
+pub struct Store {
+    session: Option<Session>,
+}
+
+impl Store {
+    /// Opens a store holding the world at a given path in memory.
+    /// Until the store is closed, loads and saves of that world go
+    /// to memory instead of disk. Other worlds still use the disk.
+    pub fn session(path: &Path) -> Result<Self> {
+        Ok(Self {
+            session: Some(Session {
+                path: world_file(path),
+                world: load_world(path)?,
+                changed: false,
+            }),
+        })
+    }
+
+    /// Closes the store, writing the world in its session to
+    /// disk if it has changed. Returns whether it was saved.
+    pub fn close(self) -> Result<bool> {
+        match self.session {
+            Some(Session {
+                path,
+                world,
+                changed: true,
+            }) => save_world(&path, &world).map(|_| true),
+            _ => Ok(false),
+        }
+    }
+
+    /// Loads a world, from the session if it holds it.
+    pub fn load_world(&self, path: &Path) -> Result<World> {
+        match &self.session {
+            Some(session) if session.path == world_file(path) => Ok(session.world.clone()),
+            _ => load_world(path),
+        }
+    }
+
+    /// Saves a world, to the session if it holds it.
+    pub fn save_world(&mut self, path: &Path, world: &World) -> Result<()> {
+        match &mut self.session {
+            Some(session) if session.path == world_file(path) => {
+                session.world = world.clone();
+                session.changed = true;
+                Ok(())
+            }
+            _ => save_world(path, world),
+        }
+    }
+}
+
+/// Gets the path to the world file, given either it (which may be
+/// any yaml file) or the directory that contains it.
+fn world_file(path: &Path) -> PathBuf {
+    let mut path = path.to_path_buf();
+    if path.extension() != Some(OsStr::new("yaml")) {
+        path.push("world.yaml");
+    }
+    path
+}
+
+/// Finds the world with some name under a directory, which is either
+/// a folder holding a `world.yaml` or a yaml file of that name, looked
+/// for first in the directory itself and then in its `worlds` folder.
+///
+/// If there is no such world, this is where a new one would be made.
+pub fn find_world(path: &Path, name: &str) -> PathBuf {
+    [path.to_path_buf(), path.join("worlds")]
+        .iter()
+        .flat_map(|dir| [dir.join(name), dir.join(format!("{}.yaml", name))])
+        .find(|candidate| world_file(candidate).is_file())
+        .unwrap_or_else(|| path.join(name))
+}
+
+/// Lists the names and paths of the worlds under a directory
+/// that can be chosen with `find_world`, sorted by name. Yaml
+/// files that do not hold a world are skipped.
+pub fn list_worlds(path: &Path) -> Vec<(String, PathBuf)> {
+    let mut worlds: Vec<_> = [path.to_path_buf(), path.join("worlds")]
+        .iter()
+        .filter_map(|dir| dir.read_dir().ok())
+        .flatten()
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|candidate| candidate.is_dir() || candidate.extension() == Some(OsStr::new("yaml")))
+        .filter(|candidate| candidate.file_name() != Some(OsStr::new("world.yaml")))
+        .filter(|candidate| world_file(candidate).is_file() && read_world(candidate).is_ok())
+        .filter_map(|candidate| {
+            let name = candidate.file_stem()?.to_string_lossy().into_owned();
+            Some((name, candidate))
+        })
+        .collect();
+    worlds.sort();
+    worlds
+}
 
 /// Loads or creates a world at a given path.
 /// Fails if the path exists and the world
-/// could not be read.
+/// could not be read or is invalid.
 pub fn load_world(path: &Path) -> Result<World> {
+    let mut world = read_world(path)?;
+
+    world
+        .validate()
+        .with_context(|| format!("Could not validate the world at {}.", path.display()))?;
+
+    // older formats only lack fields that have defaults,
+    // so reading them is all the migration they need
+    world.version = FORMAT_VERSION;
+
+    Ok(world)
+}
+
+/// Just enough of a world to know what format it is in.
+#[derive(Deserialize)]
+struct Header {
+    #[serde(default)]
+    version: u32,
+}
+
+/// Reads the format version of the world at a given path,
+/// without reading or validating the rest of the world.
+pub fn read_format_version(path: &Path) -> Result<u32> {
     if !path.exists() {
         return Err(anyhow!("Invalid path."));
     };
 
-    let mut path = path.to_path_buf();
-    if path.file_name() != Some(OsStr::new("world.yaml")) {
-        path.push("world.yaml");
-    }
+    let f = File::open(world_file(path)).context("Couldn't find world.")?;
+    let header: Header = serde_yaml::from_reader(f).context("world file is corrupted.")?;
 
-    let f = File::open(&path).context("Couldn't find world.")?;
-    let world: World = serde_yaml::from_reader(f).context("world file is corrupted.")?;
+    Ok(header.version)
+}
 
-    world.validate().context("Could not validate the world.")?;
+/// Reads a world at a given path without validating it.
+pub fn read_world(path: &Path) -> Result<World> {
+    let f = File::open(world_file(path)).map_err(|e| match e.kind() {
+        io::ErrorKind::NotFound => LoadError::NotFound(path.to_owned(), e),
+        _ => LoadError::Unreadable(path.to_owned(), e),
+    })?;
+    let world: World = serde_yaml::from_reader(f).map_err(LoadError::Corrupt)?;
 
     Ok(world)
 }
 
 pub fn save_world(path: &Path, world: &World) -> Result<()> {
-    if !path.exists() {
+    let file = world_file(path);
+    if !file.parent().is_some_and(Path::exists) {
         return Err(anyhow!("Invalid path."));
     };
 
-    let mut path = path.to_path_buf();
-    if path.file_name() != Some(OsStr::new("world.yaml")) {
-        path.push("world.yaml");
+    if file.exists() {
+        back_up(&file).context("Couldn't back up the world.")?;
     }
 
-    let f = File::create(&path).context("Couldn't find world.")?;
+    let f = File::create(&file).context("Couldn't find world.")?;
     serde_yaml::to_writer(f, world).context("world file is corrupted.")?;
     Ok(())
 }
 
+/// The number of backups kept of each world.
+pub const BACKUPS_KEPT: usize = 10;
+
+/// Gets the folder that backups of a world file are kept in.
+fn backup_dir(file: &Path) -> PathBuf {
+    file.parent()
+        .unwrap_or_else(|| Path::new("."))
+        .join(".holding")
+        .join("backups")
+}
+
+/// Copies a world file into its backups, named by the time so
+/// that they sort oldest first, and drops all but the newest.
+fn back_up(file: &Path) -> Result<()> {
+    let dir = backup_dir(file);
+    create_dir_all(&dir)?;
+
+    let stem = file.file_stem().unwrap_or_default().to_string_lossy();
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_nanos();
+    fs::copy(file, dir.join(format!("{}-{:020}.yaml", stem, now)))?;
+
+    let backups = list_backups(file);
+    for old in backups.iter().rev().skip(BACKUPS_KEPT) {
+        fs::remove_file(old)?;
+    }
+    Ok(())
+}
+
+/// Lists the backups of the world at a given path, oldest first.
+///
+/// Backups are named `{stem}-{timestamp}.yaml`, and only names of
+/// exactly that shape count, so that `tavern.yaml` doesn't pick up
+/// the backups of `tavern-old.yaml`.
+pub fn list_backups(path: &Path) -> Vec<PathBuf> {
+    let file = world_file(path);
+    let stem = file.file_stem().unwrap_or_default().to_string_lossy();
+    let is_backup = |name: &str| {
+        name.strip_prefix(&*stem)
+            .and_then(|n| n.strip_prefix('-'))
+            .and_then(|n| n.strip_suffix(".yaml"))
+            .is_some_and(|t| t.len() == 20 && t.bytes().all(|b| b.is_ascii_digit()))
+    };
+    let mut backups: Vec<_> = backup_dir(&file)
+        .read_dir()
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|p| {
+            p.file_name()
+                .is_some_and(|n| is_backup(&n.to_string_lossy()))
+        })
+        .collect();
+    backups.sort();
+    backups
+}
+
+/// Replaces the world at a given path with one of its backups,
+/// found by file name. The world being replaced is backed up too,
+/// so a restore can itself be undone.
+pub fn restore_backup(store: &mut Store, path: &Path, name: &str) -> Result<World> {
+    let backup = list_backups(path)
+        .into_iter()
+        .find(|p| p.file_name() == Some(OsStr::new(name)))
+        .ok_or_else(|| anyhow!("There is no backup called {}.", name))?;
+
+    let world = read_world(&backup)?;
+    world
+        .validate()
+        .with_context(|| format!("The backup {} is not valid.", name))?;
+    store.save_world(path, &world)?;
+    Ok(world)
+}
+
+/// The ways reading a world from disk can fail.
+#[derive(Error, Debug)]
+pub enum LoadError {
+    #[error("Couldn't find a world at {}.", .0.display())]
+    NotFound(PathBuf, #[source] io::Error),
+    #[error("Couldn't open the world at {}.", .0.display())]
+    Unreadable(PathBuf, #[source] io::Error),
+    #[error("world file is corrupted.")]
+    Corrupt(#[source] serde_yaml::Error),
+}
+
 #[derive(Error, Debug)]
 enum WorldCreationError {
     #[error("There is already a folder at this path.")]
     PathExists,
 }
 
-pub fn create_world(path: &Path, name: String, force: bool) -> Result<World> {
+/// Creates a new world following some calendar. The home planet's day
+/// and year are taken from the calendar, and the world is checked
+/// before it is written so that it will load again.
+pub fn create_world(path: &Path, name: String, calendar: Calendar, force: bool) -> Result<World> {
     if path.exists() && path.read_dir()?.next().is_some() && !force {
         return Err(WorldCreationError::PathExists.into());
     };
 
-    create_dir_all(path)?;
+    let mut world = World::from_calendar(calendar);
+    world.name = name;
+    world
+        .validate()
+        .context("The calendar can't be used for a new world.")?;
 
-    let mut path = path.to_path_buf();
-    if path.file_name() != Some(OsStr::new("world.yaml")) {
-        path.push("world.yaml");
-    }
+    create_dir_all(path)?;
 
-    let f = File::create(&path)?;
-    let world = World {
-        name,
-        ..Default::default()
-    };
+    let f = File::create(world_file(path))?;
     serde_yaml::to_writer(f, &world)?;
 
     Ok(world)
 }
+
+#[cfg(test)]
+mod test {
+    use holding_kronos::{
+        calendar::{
+            traits::{ConvertDate, ConvertTime, YearCycle},
+            Calendar, Day, Month, Week, Year,
+        },
+        units::{Days, Seconds},
+    };
+    use holding_solar::{Orbit, PlanetStore};
+
+    use crate::{
+        testing::{scratch_dir, scratch_world},
+        world::World,
+    };
+
+    use super::{create_world, load_world, read_world, Store};
+
+    #[test]
+    pub fn reports_inconsistent_rotation() {
+        let mut world = World::default();
+        let home = world.home_planet;
+        world
+            .get_planet_mut(home)
+            .expect("home planet exists")
+            .rotational_period = Seconds(86000);
+        let path = scratch_world(&world);
+
+        let err = load_world(&path).err().expect("world is invalid");
+        let message = format!("{:#}", err);
+
+        assert!(
+            message.contains("rotational period of Earth"),
+            "{}",
+            message
+        );
+        assert!(
+            message.contains("is 0.995 days but the calendar expects 1 day"),
+            "{}",
+            message
+        );
+    }
+
+    #[test]
+    pub fn reports_invalid_record_date() {
+        let mut world = World::default();
+        world.add_record("The party arrived at #waterdeep".to_string());
+        world.records[0].date.date.day = 40;
+        let path = scratch_world(&world);
+
+        let err = load_world(&path).err().expect("world is invalid");
+        let message = format!("{:#}", err);
+
+        assert!(
+            message.contains("the record \"The party arrived at #waterdeep\""),
+            "{}",
+            message
+        );
+        assert!(message.contains("does not exist"), "{}", message);
+    }
+
+    #[test]
+    pub fn rejects_orbit_cycle() {
+        let mut world = World::default();
+        let home = world.home_planet;
+        let sun = world
+            .bodies
+            .iter_mut()
+            .find(|b| b.name == "Sun")
+            .expect("the sun exists");
+        // the earth already orbits the sun
+        let orbit = Orbit::from_period(sun, home, Days(365), Days(0));
+        sun.orbit = Some(orbit);
+        let path = scratch_world(&world);
+
+        let err = load_world(&path).err().expect("world is invalid");
+        let message = format!("{:#}", err);
+        assert!(message.contains("form a cycle"), "{}", message);
+        assert!(message.contains("Earth"), "{}", message);
+        assert!(message.contains("Sun"), "{}", message);
+    }
+
+    #[test]
+    pub fn session_saves_once() {
+        let path = scratch_world(&World::default());
+        let mut store = Store::session(&path).expect("can open session");
+
+        let mut world = store.load_world(&path).expect("world loads");
+        world.add_record("The party arrived at #waterdeep".to_string());
+        store.save_world(&path, &world).expect("can save");
+
+        let on_disk = read_world(&path).expect("world reads");
+        assert_eq!(on_disk.records.len(), 0);
+        assert_eq!(
+            store.load_world(&path).expect("world loads").records.len(),
+            1
+        );
+        assert_eq!(load_world(&path).expect("world loads").records.len(), 0);
+
+        assert!(store.close().expect("can close session"));
+        assert_eq!(read_world(&path).expect("world reads").records.len(), 1);
+    }
+
+    #[test]
+    pub fn unchanged_session_does_not_save() {
+        let path = scratch_world(&World::default());
+        let store = Store::session(&path).expect("can open session");
+        store.load_world(&path).expect("world loads");
+        assert!(!store.close().expect("can close session"));
+    }
+
+    #[test]
+    pub fn custom_calendar_world_is_consistent() {
+        let year = Year::new(
+            (1..=10)
+                .map(|i| Month {
+                    name: format!("Month {}", i),
+                    days: 30,
+                })
+                .collect(),
+        );
+        let calendar = Calendar::new(year, Week::default(), Day::new(60, 50, 20));
+        let path = scratch_dir();
+
+        create_world(&path, "Elsewhere".to_string(), calendar.clone(), false)
+            .expect("can create world");
+        let world = load_world(&path).expect("world is valid");
+
+        let home = world
+            .get_planet(world.home_planet)
+            .expect("home planet exists");
+        assert_eq!(home.rotational_period, Seconds(calendar.days_to_seconds(1)));
+        let orbit = home.orbit.as_ref().expect("home planet orbits");
+        assert_eq!(orbit.period, Seconds(calendar.years_to_seconds(1)));
+        assert_eq!(calendar.days_in_year(), 300);
+    }
+}
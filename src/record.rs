@@ -1,5 +1,6 @@
-use std::fmt::Display;
+use std::{fmt::Display, ops::Range};
 
+use holding_color::colored::*;
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
@@ -15,6 +16,10 @@ pub struct RawRecord {
     pub date: RawDateTime,
     pub chars: Vec<CharacterReference>,
     pub locs: Vec<LocationReference>,
+
+    /// Who wrote the record, if known.
+    #[serde(default)]
+    pub author: Option<String>,
 }
 
 impl RawRecord {
@@ -38,32 +43,98 @@ impl RawRecord {
             date,
             chars,
             locs,
+            author: None,
         }
     }
 
     pub fn pretty(&self) -> String {
+        self.render(0..self.note.len())
+    }
+
+    /// Like `pretty`, but wraps the note on word boundaries so that no
+    /// line is wider than `width`, unless a single word is. Colors take
+    /// up no width, and a reference that is split across lines is
+    /// colored on each of them.
+    pub fn pretty_wrapped(&self, width: usize) -> Vec<String> {
+        wrap(&self.note, width)
+            .into_iter()
+            .map(|line| self.render(line))
+            .collect()
+    }
+
+    /// Formats the record as an entry in the history, such as
+    /// `- 1-01-01 00:00:00 | The party arrived`. Given a width, the
+    /// note is wrapped to fit, with later lines indented to match.
+    pub fn entry(&self, width: Option<usize>) -> String {
+        let date = self.date.to_string();
+        let note = match width {
+            Some(width) => {
+                let indent = date.chars().count() + 5;
+                self.pretty_wrapped(width.saturating_sub(indent).max(1))
+                    .join(&format!("\n{}", " ".repeat(indent)))
+            }
+            None => self.pretty(),
+        };
+        format!("- {} | {}", date.bright_black(), note)
+    }
+
+    /// Colors the references within part of the note.
+    fn render(&self, range: Range<usize>) -> String {
         let mut out = String::new();
-        let mut curr = 0;
+        let mut curr = range.start;
 
-        let chars = self.chars.iter().map(|r| (r.start, r.end, 33));
-        let locs = self.locs.iter().map(|r| (r.start, r.end, 31));
+        let chars = self.chars.iter().map(|r| (r.start, r.end, Color::Yellow));
+        let locs = self.locs.iter().map(|r| (r.start, r.end, Color::Red));
 
         for (start, end, color) in chars
             .chain(locs)
             .sorted_by(|(s1, _, _), (s2, _, _)| s1.cmp(s2))
         {
+            let (start, end) = (start.max(range.start), end.min(range.end));
+            if start >= end {
+                continue;
+            }
             out.push_str(&self.note[curr..start]);
-            out.push_str(&format!("\x1b[0;{}m", color));
-            out.push_str(&self.note[start..end]);
-            out.push_str("\x1b[0m");
+            out.push_str(&self.note[start..end].color(color).to_string());
             curr = end;
         }
 
-        out.push_str(&self.note[curr..]);
+        out.push_str(&self.note[curr..range.end]);
         out
     }
 }
 
+/// Splits some text into lines of at most `width` characters, breaking
+/// between words. The whitespace at each break is dropped.
+fn wrap(text: &str, width: usize) -> Vec<Range<usize>> {
+    let words = text
+        .char_indices()
+        .filter(|(_, c)| !c.is_whitespace())
+        .map(|(i, c)| i..i + c.len_utf8())
+        .coalesce(|a, b| {
+            if a.end == b.start {
+                Ok(a.start..b.end)
+            } else {
+                Err((a, b))
+            }
+        });
+
+    let mut lines: Vec<Range<usize>> = vec![];
+    for word in words {
+        match lines.last_mut() {
+            Some(line) if text[line.start..word.end].chars().count() <= width => {
+                line.end = word.end
+            }
+            _ => lines.push(word),
+        }
+    }
+
+    if lines.is_empty() {
+        lines.push(0..0);
+    }
+    lines
+}
+
 impl Display for RawRecord {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.note)
@@ -78,6 +149,7 @@ impl From<&Record<'_>> for RawRecord {
             date: val.date.into(),
             chars: vec![],
             locs: vec![],
+            author: None,
         }
     }
 }
@@ -98,3 +170,61 @@ impl<'a> Record<'a> {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use test_case::test_case;
+
+    use crate::world::World;
+
+    use super::wrap;
+
+    /// Removes the color codes from some text.
+    fn visible(line: &str) -> String {
+        let mut out = String::new();
+        let mut chars = line.chars();
+        while let Some(c) = chars.next() {
+            if c == '\x1b' {
+                chars.by_ref().find(|c| *c == 'm');
+            } else {
+                out.push(c);
+            }
+        }
+        out
+    }
+
+    #[test_case("", 10, &[""] ; "empty")]
+    #[test_case("a bb ccc", 4, &["a bb", "ccc"] ; "breaks between words")]
+    #[test_case("a  bb", 5, &["a  bb"] ; "keeps inner spacing")]
+    #[test_case("unbreakable word", 4, &["unbreakable", "word"] ; "long word")]
+    pub fn wraps_words(text: &str, width: usize, expected: &[&str]) {
+        let lines: Vec<_> = wrap(text, width).into_iter().map(|r| &text[r]).collect();
+        assert_eq!(lines, expected);
+    }
+
+    #[test]
+    pub fn wraps_colored_note() {
+        let mut world = World::default();
+        let record = world.add_record(
+            "After many long days the wizard @gandalf finally emerged from the halls of #moria"
+                .to_string(),
+        );
+
+        let lines = record.pretty_wrapped(20);
+        assert!(lines.len() > 1);
+        for line in &lines {
+            assert!(visible(line).chars().count() <= 20, "{:?}", line);
+            assert_eq!(
+                line.matches("\x1b[0m").count(),
+                line.matches("\x1b[3").count()
+            );
+        }
+
+        let text = lines
+            .iter()
+            .map(|l| visible(l))
+            .collect::<Vec<_>>()
+            .join(" ");
+        assert_eq!(text, record.note);
+    }
+}
@@ -0,0 +1,35 @@
+//! Helpers shared between tests.
+
+use std::{env::temp_dir, fs::create_dir_all, path::PathBuf};
+
+use uuid::Uuid;
+
+use crate::{character::Character, persistence::save_world, world::World};
+
+/// Creates an empty directory to hold a world.
+pub fn scratch_dir() -> PathBuf {
+    let path = temp_dir().join(format!("holding-{}", Uuid::new_v4()));
+    create_dir_all(&path).expect("can create temp dir");
+    path
+}
+
+/// Saves a world into a fresh directory, returning its path.
+pub fn scratch_world(world: &World) -> PathBuf {
+    let path = scratch_dir();
+    save_world(&path, world).expect("can save world");
+    path
+}
+
+/// Creates a character with 10 hitpoints and nothing else known about them.
+pub fn character(identifier: &str) -> Character {
+    Character::new(identifier.to_string(), None, Some(10), None).expect("valid identifier")
+}
+
+/// Adds a character, as made by `character`, to a world.
+pub fn add_character<'a>(world: &'a mut World, identifier: &str) -> &'a mut Character {
+    world.characters.push(character(identifier));
+    world
+        .characters
+        .last_mut()
+        .expect("We just pushed a character")
+}
@@ -1,3 +1,5 @@
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
 use crate::character::{Character, CharacterReference, Location, LocationId, LocationReference};
 use crate::character::{CharacterStore, LocationStore};
 use anyhow::{anyhow, Result};
@@ -6,22 +8,42 @@ use itertools::Itertools;
 use lazy_static::lazy_static;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 
 use holding_color::colored::*;
 use holding_kronos::{
-    calendar::Calendar,
-    datetime::{traits::ModifyDateTime, DateTime, RawDateTime},
+    calendar::{
+        traits::{DayCycle, YearCycle},
+        Calendar,
+    },
+    datetime::{
+        traits::{ModifyDate, ModifyDateTime, ShowDate},
+        DateTime, Duration, RawDate, RawDateTime, RawTime, Schedule,
+    },
+    units::{Days, Seconds},
+};
+use holding_solar::{
+    CelestialBody, Kelvin, Phase, PlanetId, PlanetStore, Season, ValidationError, EARTH_MASS,
+    EARTH_RADIUS,
 };
-use holding_solar::{CelestialBody, PlanetId, PlanetStore};
 use uuid::Uuid;
 
-use crate::{character::CharacterId, record::RawRecord};
+use crate::{
+    character::CharacterId,
+    diff::{Changes, WorldDiff},
+    index::RecordIndex,
+    record::RawRecord,
+};
 
 lazy_static! {
     static ref CHAR: Regex = Regex::new(r"\B@(?P<name>\w+)\b").expect("compiles");
     static ref LOC: Regex = Regex::new(r"\B#(?P<name>\w+)\b").expect("compiles");
 }
 
+/// The version of the world format written by this build.
+/// Worlds from before the format was versioned are version 0.
+pub const FORMAT_VERSION: u32 = 1;
+
 /// The data structure for the world.
 ///
 /// To avoid this being self-referential, the
@@ -31,6 +53,10 @@ lazy_static! {
 /// todo(arlyon): Devise a more elegant data-structure.
 #[derive(Clone, Serialize, Deserialize)]
 pub struct World {
+    /// The version of the format this world was saved in.
+    #[serde(default)]
+    pub version: u32,
+
     pub name: String,
     pub calendar: Calendar,
     pub time: RawDateTime,
@@ -38,48 +64,110 @@ pub struct World {
     /// If Some, it means we have jumped to
     /// a different location in time.
     pub canonical_time: Option<RawDateTime>,
+
+    /// When the campaign began. Older worlds fall
+    /// back to the date of the earliest record.
+    #[serde(default)]
+    pub campaign_start: Option<RawDateTime>,
     pub home_planet: PlanetId,
+
+    /// The body the party is on, if they have left the home planet.
+    #[serde(default)]
+    pub observer_body: Option<PlanetId>,
     pub bodies: Vec<CelestialBody>,
     pub records: Vec<RawRecord>,
 
     pub characters: Vec<Character>,
     pub locations: Vec<Location>,
+
+    /// Named dice expressions, such as an attack roll.
+    #[serde(default)]
+    pub dice_macros: Vec<(String, String)>,
+
+    /// Named record notes with `{0}`, `{1}`, ... placeholders,
+    /// such as `@{0} reached level {1}`.
+    #[serde(default)]
+    pub record_templates: Vec<(String, String)>,
+
+    /// Recurring events, along with the note to record
+    /// each time one occurs.
+    #[serde(default)]
+    pub schedules: Vec<(Schedule, String)>,
+
+    /// Weather that has been decided for a day, which
+    /// is reported instead of the generated weather.
+    #[serde(default)]
+    pub weather_overrides: Vec<(RawDate, String)>,
+
+    #[serde(skip)]
+    index: RecordIndex,
 }
 
 impl World {
     pub fn new(name: String, calendar: Calendar, home_planet: CelestialBody) -> Self {
         let home_planet_id = home_planet.id;
+        let time = DateTime::from_seconds(0, &calendar).into();
         Self {
+            version: FORMAT_VERSION,
             name,
-            time: DateTime::from_seconds(0, &calendar).into(),
+            time,
             canonical_time: None,
+            campaign_start: Some(time),
             calendar,
             home_planet: home_planet_id,
+            observer_body: None,
             bodies: vec![home_planet],
-            ..Default::default()
+            records: vec![],
+            characters: vec![],
+            locations: vec![],
+            dice_macros: vec![],
+            record_templates: vec![],
+            schedules: vec![],
+            weather_overrides: vec![],
+            index: RecordIndex::default(),
         }
     }
 
+    /// Gets the body the party is on, which is the home planet
+    /// unless they have traveled elsewhere.
+    pub fn observer(&self) -> PlanetId {
+        self.observer_body.unwrap_or(self.home_planet)
+    }
+
+    /// Shows the current time of the world to people, using the
+    /// display format of its calendar if it has one.
+    pub fn display_time(&self) -> String {
+        self.time.into_datetime(&self.calendar).to_display_string()
+    }
+
     pub fn jumped(&self) -> bool {
         self.canonical_time.is_some()
     }
 
-    pub fn records_pretty(&self) -> String {
-        let records = self.records.iter().map(|r| (r.date, r.pretty()));
+    /// Lists the records in order, along with markers for the current
+    /// time. Given a width, long notes are wrapped to fit within it.
+    pub fn records_pretty(&self, width: Option<usize>) -> String {
+        let records = self.records.iter().map(|r| (r.date, r.entry(width)));
+        let marker = |date: RawDateTime, text: ColoredString| {
+            (
+                date,
+                format!("- {} | {}", date.to_string().bright_black(), text),
+            )
+        };
 
         self.canonical_time
             .filter(|t| !self.time.eq(t))
             .map(|t| {
                 vec![
-                    (self.time, "Now".purple().to_string()),
-                    (t, "Canonical Time".bright_purple().to_string()),
+                    marker(self.time, "Now".purple()),
+                    marker(t, "Canonical Time".bright_purple()),
                 ]
             })
-            .unwrap_or_else(|| vec![(self.time, "Now".purple().to_string())])
+            .unwrap_or_else(|| vec![marker(self.time, "Now".purple())])
             .into_iter()
             .chain(records)
             .sorted_by_key(|(x, _)| *x)
-            .map(|(date, text)| format!("- {} | {}", date.to_string().bright_black(), text))
+            .map(|(_, entry)| entry)
             .join("\n")
     }
 
@@ -89,11 +177,53 @@ impl World {
     /// @character - refers to a character
     /// #location - refers to a location
     pub fn add_record(&mut self, note: String) -> &RawRecord {
+        self.add_record_by(note, None)
+    }
+
+    /// Add a record to the world, noting who wrote it.
+    pub fn add_record_by(&mut self, note: String, author: Option<String>) -> &RawRecord {
+        let mut x = self.new_record(self.time, note);
+        x.author = author;
+        self.records.push(x);
+        self.index.invalidate();
+        self.time = self
+            .time
+            .into_datetime(&self.calendar)
+            .add_seconds(1)
+            .into();
+        self.records.last().unwrap()
+    }
+
+    /// Add a record to the world for something that happened earlier,
+    /// noting who wrote it. The records are kept in date order, and
+    /// the time of the world does not change.
+    pub fn add_record_at(
+        &mut self,
+        note: String,
+        author: Option<String>,
+        date: RawDateTime,
+    ) -> Result<&RawRecord> {
+        if date > self.time {
+            return Err(anyhow!("Can't record what hasn't happened yet!"));
+        }
+
+        let mut x = self.new_record(date, note);
+        x.author = author;
+        let position = self.records.partition_point(|r| r.date <= date);
+        self.records.insert(position, x);
+        self.index.invalidate();
+        Ok(&self.records[position])
+    }
+
+    /// Creates a record at some date, resolving its references.
+    fn new_record(&mut self, date: RawDateTime, note: String) -> RawRecord {
         let chars: Vec<CharacterReference> = CHAR
             .captures_iter(&note)
             .filter_map(|caps| {
                 caps.name("name").map(|cap| {
-                    let character = self.lookup_character(cap.as_str()).expect("match");
+                    let character = self
+                        .lookup_character(cap.as_str())
+                        .unwrap_or_else(|| self.create_character(cap.as_str()));
                     CharacterReference {
                         id: character.id,
                         string: cap.as_str().to_owned(),
@@ -124,14 +254,157 @@ impl World {
         // println!("{:?}", chars);
         // println!("{:?}", locs);
 
-        let x = RawRecord::new(self.time, note, chars, locs);
-        self.records.push(x);
-        self.time = self
-            .time
-            .into_datetime(&self.calendar)
-            .add_seconds(1)
-            .into();
-        self.records.last().unwrap()
+        RawRecord::new(date, note, chars, locs)
+    }
+
+    /// Finds the references in a note to characters and locations
+    /// that don't exist, but are only a typo away from ones that do.
+    /// Each is paired with the reference it was probably meant to be.
+    pub fn suggest_references(&self, note: &str) -> Vec<(String, String)> {
+        let chars = CHAR
+            .captures_iter(note)
+            .filter_map(|caps| caps.name("name"))
+            .filter(|cap| self.lookup_character(cap.as_str()).is_none())
+            .filter_map(|cap| {
+                let known = self.characters.iter().map(|c| c.identifier.as_str());
+                closest(cap.as_str(), known)
+                    .map(|known| (format!("@{}", cap.as_str()), format!("@{}", known)))
+            });
+
+        let locs = LOC
+            .captures_iter(note)
+            .filter_map(|caps| caps.name("name"))
+            .filter(|cap| self.lookup_location(cap.as_str()).is_none())
+            .filter_map(|cap| {
+                let known = self.locations.iter().map(|l| l.identifier.as_str());
+                closest(cap.as_str(), known)
+                    .map(|known| (format!("#{}", cap.as_str()), format!("#{}", known)))
+            });
+
+        chars.chain(locs).unique().collect()
+    }
+
+    /// Moves a character to a location, creating the location
+    /// if it doesn't exist yet, and records the journey.
+    ///
+    /// Returns None if the character is already there.
+    pub fn move_character(
+        &mut self,
+        character: &str,
+        location: &str,
+    ) -> Result<Option<&RawRecord>> {
+        if self.lookup_character(character).is_none() {
+            return Err(anyhow!("There is no character called @{}.", character));
+        }
+
+        let loc = self
+            .lookup_location(location)
+            .unwrap_or_else(|| self.create_location(location));
+
+        let traveller = self
+            .lookup_character_mut(character)
+            .expect("We just checked it exists");
+        if traveller.location() == Some(loc.id) {
+            return Ok(None);
+        }
+        traveller.move_to(loc.id);
+
+        Ok(Some(self.add_record(format!(
+            "@{} traveled to #{}",
+            character, location
+        ))))
+    }
+
+    /// Archives a character, hiding them from listings while
+    /// keeping them around for the records that mention them.
+    pub fn archive_character(&mut self, id: CharacterId) -> Result<()> {
+        self.set_archived(id, true)
+    }
+
+    /// Restores an archived character.
+    pub fn unarchive_character(&mut self, id: CharacterId) -> Result<()> {
+        self.set_archived(id, false)
+    }
+
+    fn set_archived(&mut self, id: CharacterId, archived: bool) -> Result<()> {
+        let character = self
+            .characters
+            .iter_mut()
+            .find(|c| c.id == id)
+            .ok_or_else(|| anyhow!("There is no character with id {}.", id.0))?;
+        character.set_archived(archived);
+        Ok(())
+    }
+
+    /// Gets the characters in combat in the order they act, from the
+    /// highest initiative to the lowest. Ties are broken by name.
+    pub fn combat_order(&self) -> Vec<&Character> {
+        let mut order: Vec<_> = self
+            .characters
+            .iter()
+            .filter(|c| c.initiative().is_some())
+            .collect();
+        order.sort_by(|a, b| {
+            b.initiative()
+                .cmp(&a.initiative())
+                .then_with(|| a.name().cmp(b.name()))
+        });
+        order
+    }
+
+    /// Takes everyone out of combat.
+    pub fn end_combat(&mut self) {
+        for character in &mut self.characters {
+            character.set_initiative(None);
+        }
+    }
+
+    /// Gets all the records that mention a character.
+    pub fn records_for_character(&self, id: CharacterId) -> Vec<&RawRecord> {
+        self.index
+            .character(&self.records, id)
+            .into_iter()
+            .map(|i| &self.records[i])
+            .collect()
+    }
+
+    /// Gets all the records that mention a location.
+    pub fn records_for_location(&self, id: LocationId) -> Vec<&RawRecord> {
+        self.index
+            .location(&self.records, id)
+            .into_iter()
+            .map(|i| &self.records[i])
+            .collect()
+    }
+
+    /// Counts the records that mention each location, most
+    /// mentioned first. Locations no record mentions are left out.
+    pub fn record_density(&self) -> Vec<(LocationId, usize)> {
+        self.locations
+            .iter()
+            .map(|l| (l, self.index.location(&self.records, l.id).len()))
+            .filter(|(_, count)| *count > 0)
+            .sorted_by(|(a, x), (b, y)| y.cmp(x).then_with(|| a.identifier.cmp(&b.identifier)))
+            .map(|(l, count)| (l.id, count))
+            .collect()
+    }
+
+    /// Finds all the records whose note matches a query, in
+    /// date order. The query is either a plain substring or,
+    /// if `regex` is set, a regular expression.
+    pub fn search_records(&self, query: &str, regex: bool) -> Result<Vec<&RawRecord>> {
+        let matcher = if regex {
+            Regex::new(query)?
+        } else {
+            Regex::new(&regex::escape(query))?
+        };
+
+        Ok(self
+            .records
+            .iter()
+            .filter(|r| matcher.is_match(&r.note))
+            .sorted_by_key(|r| r.date)
+            .collect())
     }
 
     pub fn records_between(&mut self, d1: RawDateTime, d2: RawDateTime) -> Vec<RawRecord> {
@@ -144,7 +417,110 @@ impl World {
             .collect()
     }
 
+    /// Saves a dice expression under a name,
+    /// replacing any macro with the same name.
+    pub fn save_dice_macro(&mut self, name: String, expr: String) {
+        self.dice_macros.retain(|(n, _)| *n != name);
+        self.dice_macros.push((name, expr));
+    }
+
+    /// Gets a saved dice expression by name.
+    pub fn dice_macro(&self, name: &str) -> Option<&str> {
+        self.dice_macros
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, e)| e.as_str())
+    }
+
+    /// Saves a record template under a name,
+    /// replacing any template with the same name.
+    pub fn save_record_template(&mut self, name: String, template: String) {
+        self.record_templates.retain(|(n, _)| *n != name);
+        self.record_templates.push((name, template));
+    }
+
+    /// Gets a saved record template by name.
+    pub fn record_template(&self, name: &str) -> Option<&str> {
+        self.record_templates
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, t)| t.as_str())
+    }
+
+    /// Fixes the weather on some day, replacing
+    /// any weather already set for that day.
+    pub fn set_weather(&mut self, date: RawDate, weather: String) {
+        self.weather_overrides.retain(|(d, _)| *d != date);
+        self.weather_overrides.push((date, weather));
+    }
+
+    /// Gets the weather that has been set for some day, if any.
+    pub fn weather_on(&self, date: RawDate) -> Option<&str> {
+        self.weather_overrides
+            .iter()
+            .find(|(d, _)| *d == date)
+            .map(|(_, w)| w.as_str())
+    }
+
+    /// Compares this world with a later version of it, such as
+    /// a backup with the world as it is now. This fails if time
+    /// moved too far to count in years of the newer calendar.
+    pub fn diff(&self, other: &World) -> Result<WorldDiff> {
+        let before = self.time.into_datetime(&self.calendar).total_seconds();
+        let after = other.time.into_datetime(&other.calendar).total_seconds();
+        let elapsed = after - before;
+
+        let duration = Duration::checked_from_seconds(elapsed.unsigned_abs(), &other.calendar)
+            .ok_or_else(|| anyhow!("Time moved too far between the worlds to count."))?;
+
+        Ok(WorldDiff {
+            records: Changes::between(&self.records, &other.records, |r| r.id, |r| r.note.clone()),
+            characters: Changes::between(
+                &self.characters,
+                &other.characters,
+                |c| c.id,
+                |c| format!("@{}", c.identifier),
+            ),
+            locations: Changes::between(
+                &self.locations,
+                &other.locations,
+                |l| l.id,
+                |l| format!("#{}", l.identifier),
+            ),
+            bodies: Changes::between(&self.bodies, &other.bodies, |b| b.id.0, |b| b.name.clone()),
+            elapsed,
+            duration,
+        })
+    }
+
+    /// Gets how much time has passed since the campaign began. This
+    /// fails if it is too long to count in years of the calendar.
+    pub fn elapsed(&self) -> Result<Duration> {
+        let start = self
+            .campaign_start
+            .or_else(|| self.records.iter().map(|r| r.date).min())
+            .unwrap_or(self.time)
+            .into_datetime(&self.calendar);
+        let now = self.time.into_datetime(&self.calendar);
+
+        let seconds = now
+            .seconds_since(&start)
+            .expect("Both use the world's calendar")
+            .max(0)
+            .unsigned_abs();
+        Duration::checked_from_seconds(seconds, &self.calendar)
+            .ok_or_else(|| anyhow!("The campaign has run for too long to count."))
+    }
+
     pub fn update_time(&mut self, expr: &str) -> Result<()> {
+        self.advance_with_events(expr).map(|_| ())
+    }
+
+    /// Steps time forward, returning what changed on the way in the
+    /// order it happened: each month that began, each season and
+    /// moon phase that was entered, and the scheduled events that
+    /// were recorded.
+    pub fn advance_with_events(&mut self, expr: &str) -> Result<Vec<WorldEvent>> {
         let cal_time = self.time.into_datetime(&self.calendar);
         let new_time = self.calendar.parse(expr, Some(cal_time))?;
 
@@ -152,8 +528,164 @@ impl World {
             return Err(anyhow!("Can't go back in time!"));
         }
 
+        let mut events = self.month_starts_between(cal_time, new_time);
+        events.extend(self.season_starts_between(cal_time, new_time));
+        events.extend(self.phase_starts_between(cal_time, new_time));
+
+        let occurrences = self.scheduled_between(cal_time, new_time);
+
         self.time = new_time.into();
-        Ok(())
+        for (date, note) in occurrences {
+            if !self
+                .records
+                .iter()
+                .any(|r| r.date == date && r.note == note)
+            {
+                let record = self.new_record(date, note.clone());
+                self.records.push(record);
+                self.index.invalidate();
+                events.push((date, WorldEvent::ScheduledRecord(date, note)));
+            }
+        }
+
+        Ok(events
+            .into_iter()
+            .sorted_by_key(|(date, _)| *date)
+            .map(|(_, event)| event)
+            .collect())
+    }
+
+    /// Gets the start of every month after `from`, up to and including `to`.
+    fn month_starts_between(&self, from: DateTime, to: DateTime) -> Vec<(RawDateTime, WorldEvent)> {
+        let mut start = DateTime::from_date(from.year(), from.month(), 1, &self.calendar)
+            .expect("the first of the month exists");
+        let mut events = vec![];
+        loop {
+            start = start.add_months(1);
+            if start > to {
+                return events;
+            }
+            events.push((
+                start.into(),
+                WorldEvent::MonthRollover(start.year(), start.month()),
+            ));
+        }
+    }
+
+    /// Gets the start of every season on the home planet after `from`,
+    /// up to and including `to`.
+    fn season_starts_between(
+        &self,
+        from: DateTime,
+        to: DateTime,
+    ) -> Vec<(RawDateTime, WorldEvent)> {
+        let home = match self.get_planet(self.home_planet) {
+            Some(home) if self.season_at(from).is_some() => home,
+            _ => return vec![],
+        };
+        let orbit = home.orbit.expect("only orbiting planets have seasons");
+        let period = i64::from(orbit.period.0);
+        let (from, to) = (from.total_seconds(), to.total_seconds());
+
+        let mut events = vec![];
+        let mut cycle = from - from.rem_euclid(period);
+        while cycle <= to {
+            for season in Season::ALL.iter() {
+                let start = cycle + i64::from(orbit.time_at_true_anomaly(season.start_angle()).0);
+                if from < start && start <= to {
+                    let date = DateTime::from_seconds(start, &self.calendar).into();
+                    events.push((date, WorldEvent::SeasonChanged(*season)));
+                }
+            }
+            cycle += period;
+        }
+        events
+    }
+
+    /// Gets the start of every phase of the moons of the home planet
+    /// after `from`, up to and including `to`.
+    fn phase_starts_between(&self, from: DateTime, to: DateTime) -> Vec<(RawDateTime, WorldEvent)> {
+        let mut events = vec![];
+        for (id, _) in self.phases_at(from) {
+            let orbit = match self.get_planet(id).and_then(|moon| moon.orbit) {
+                Some(orbit) => orbit,
+                None => continue,
+            };
+
+            // whichever phase starts first is the next one the moon enters,
+            // whether it is waxing or waning.
+            let mut time = from;
+            while let Some((next, phase)) = (0..8)
+                .filter_map(|i: u8| Phase::try_from(i).ok())
+                .filter_map(|phase| Some((orbit.next_phase(self, time, phase)?, phase)))
+                .min_by_key(|(next, _)| next.total_seconds())
+                .filter(|(next, _)| *next <= to)
+            {
+                events.push((next.into(), WorldEvent::PhaseChanged(id, phase)));
+                time = next;
+            }
+        }
+        events
+    }
+
+    /// Gets the season on the home planet at some time,
+    /// if it experiences seasons.
+    fn season_at(&self, time: DateTime) -> Option<Season> {
+        let home = self.get_planet(self.home_planet)?;
+        home.season(time.seconds_modulo(home.orbit?.period))
+    }
+
+    /// Gets the phases of the moons of the home planet at some time.
+    fn phases_at(&self, time: DateTime) -> Vec<(PlanetId, Phase)> {
+        self.get_planet(self.home_planet)
+            .map(|home| home.children.as_slice())
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|id| {
+                let phase = self.get_planet(*id)?.orbit?.get_phase(self, time, None)?;
+                Some((*id, phase))
+            })
+            .collect()
+    }
+
+    /// Gets the occurrences of every schedule after `from`, up to and
+    /// including `to`, along with their notes in the order they happen.
+    fn scheduled_between(&self, from: DateTime, to: DateTime) -> Vec<(RawDateTime, String)> {
+        self.schedules
+            .iter()
+            .flat_map(|(schedule, note)| {
+                schedule
+                    .occurrences_between(&self.calendar, from, to)
+                    .into_iter()
+                    .map(move |date| (date.into(), note.clone()))
+            })
+            .sorted_by_key(|(date, _)| *date)
+            .collect()
+    }
+
+    /// Gets everything that will happen between now and some later time,
+    /// both the scheduled events and any records written for the future.
+    pub fn upcoming(&self, until: DateTime) -> Vec<(RawDateTime, String)> {
+        let now = self.time.into_datetime(&self.calendar);
+        let until_raw = RawDateTime::from(until);
+        let records = self
+            .records
+            .iter()
+            .filter(|r| r.date > self.time && r.date <= until_raw)
+            .map(|r| (r.date, r.note.clone()));
+
+        self.scheduled_between(now, until)
+            .into_iter()
+            .chain(records)
+            .sorted()
+            .dedup()
+            .collect()
+    }
+
+    /// Adds a recurring event, which is recorded whenever
+    /// time steps past one of its occurrences.
+    pub fn add_schedule(&mut self, schedule: Schedule, note: String) {
+        self.schedules.push((schedule, note));
     }
 
     pub fn jump_time(&mut self, expr: &str) -> Result<()> {
@@ -178,11 +710,268 @@ impl World {
         }
     }
 
+    /// Switches the world to a new calendar, checking every stored
+    /// date against it. Dates that don't exist in the new calendar
+    /// are either clamped to the nearest valid date, or cause the
+    /// change to be refused, leaving the world untouched.
+    pub fn set_calendar(
+        &mut self,
+        calendar: Calendar,
+        clamp: bool,
+    ) -> Result<CalendarChangeReport> {
+        let mut report = CalendarChangeReport::default();
+        let mut check = |label: String, date: &RawDateTime| -> RawDateTime {
+            if is_valid(&calendar, date) {
+                return *date;
+            }
+            let clamped = clamp_date(&calendar, date);
+            report.invalid.push(InvalidatedDate {
+                label,
+                before: *date,
+                after: clamped,
+            });
+            clamped
+        };
+
+        let time = check("the current time".to_string(), &self.time);
+        let canonical_time = self
+            .canonical_time
+            .map(|t| check("the canonical time".to_string(), &t));
+        let campaign_start = self
+            .campaign_start
+            .map(|t| check("the start of the campaign".to_string(), &t));
+        let records: Vec<_> = self
+            .records
+            .iter()
+            .map(|r| check(format!("the record \"{}\"", r.note), &r.date))
+            .collect();
+        let schedules: Vec<_> = self
+            .schedules
+            .iter()
+            .map(|(s, note)| check(format!("the schedule \"{}\"", note), &s.start))
+            .collect();
+        let weather: Vec<_> = self
+            .weather_overrides
+            .iter()
+            .map(|(date, weather)| {
+                let date = on_day(*date);
+                check(format!("the weather \"{}\"", weather), &date).date
+            })
+            .collect();
+        let notes: Vec<Vec<_>> = self
+            .characters
+            .iter()
+            .map(|c| {
+                c.notes()
+                    .iter()
+                    .map(|(date, _)| check(format!("a note about @{}", c.identifier), date))
+                    .collect()
+            })
+            .collect();
+
+        if !clamp && !report.invalid.is_empty() {
+            return Err(anyhow!(
+                "These dates do not exist in the new calendar:\n{}",
+                report.invalid.iter().map(ToString::to_string).join("\n")
+            ));
+        }
+
+        self.calendar = calendar;
+        self.time = time;
+        self.canonical_time = canonical_time;
+        self.campaign_start = campaign_start;
+        for (record, date) in self.records.iter_mut().zip(records) {
+            record.date = date;
+        }
+        self.records.sort_by_key(|r| r.date);
+        self.index.invalidate();
+        for ((schedule, _), date) in self.schedules.iter_mut().zip(schedules) {
+            schedule.start = date;
+        }
+        let overrides = std::mem::take(&mut self.weather_overrides);
+        for ((_, weather), date) in overrides.into_iter().zip(weather) {
+            self.set_weather(date, weather);
+        }
+        for (character, dates) in self.characters.iter_mut().zip(notes) {
+            character.set_note_dates(dates);
+        }
+
+        Ok(report)
+    }
+
     /// Validates the world.
     pub fn validate(&self) -> Result<bool> {
-        self.get_planet(self.home_planet)
-            .map(|p| p.validate_calendar(&self.calendar).map_err(Into::into))
-            .unwrap_or_else(|| Err(anyhow!("Home planet doest not exist.")))
+        match self.issues().into_iter().next() {
+            Some(issue) => Err(issue.into()),
+            None => Ok(true),
+        }
+    }
+
+    /// Collects every inconsistency in the world, such as a home
+    /// planet that disagrees with the calendar, an orbit around a
+    /// body that doesn't exist, or a date the calendar doesn't have.
+    pub fn issues(&self) -> Vec<WorldError> {
+        let mut issues: Vec<WorldError> = match self.get_planet(self.home_planet) {
+            Some(home) => home
+                .calendar_issues(&self.calendar)
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+            None => vec![WorldError::MissingHomePlanet],
+        };
+
+        issues.extend(
+            self.bodies
+                .iter()
+                .filter(|b| matches!(b.orbit, Some(o) if self.get_planet(o.parent).is_none()))
+                .map(|b| WorldError::MissingParent(b.name.clone())),
+        );
+
+        let mut cycles: Vec<Vec<PlanetId>> = vec![];
+        for body in &self.bodies {
+            if let Some(cycle) = self.orbit_cycle(body.id) {
+                if !cycles.iter().any(|c| c.contains(&cycle[0])) {
+                    cycles.push(cycle);
+                }
+            }
+        }
+        issues.extend(cycles.into_iter().map(|cycle| {
+            let names = cycle
+                .into_iter()
+                .filter_map(|id| self.get_planet(id))
+                .map(|b| b.name.as_str())
+                .join(", ");
+            WorldError::OrbitCycle(names)
+        }));
+
+        let times = [
+            ("the current time".to_string(), Some(self.time)),
+            ("the canonical time".to_string(), self.canonical_time),
+            ("the start of the campaign".to_string(), self.campaign_start),
+        ];
+        let records = self
+            .records
+            .iter()
+            .map(|r| (format!("the record \"{}\"", r.note), Some(r.date)));
+        let schedules = self
+            .schedules
+            .iter()
+            .map(|(s, note)| (format!("the schedule \"{}\"", note), Some(s.start)));
+        let weather = self
+            .weather_overrides
+            .iter()
+            .map(|(date, weather)| (format!("the weather \"{}\"", weather), Some(on_day(*date))));
+        let notes = self.characters.iter().flat_map(|c| {
+            c.notes()
+                .iter()
+                .map(move |(date, _)| (format!("a note about @{}", c.identifier), Some(*date)))
+        });
+        issues.extend(
+            times
+                .into_iter()
+                .chain(records)
+                .chain(schedules)
+                .chain(weather)
+                .chain(notes)
+                .filter_map(|(label, date)| Some((label, date?)))
+                .filter(|(_, date)| !is_valid(&self.calendar, date))
+                .map(|(label, date)| WorldError::InvalidDate(label, date)),
+        );
+
+        issues
+    }
+}
+
+/// Possible inconsistencies in a world.
+#[derive(Error, Debug)]
+pub enum WorldError {
+    #[error("the home planet does not exist")]
+    MissingHomePlanet,
+    #[error("{0} is orbiting a body that does not exist")]
+    MissingParent(String),
+    #[error("the orbits of {0} form a cycle")]
+    OrbitCycle(String),
+    #[error(transparent)]
+    InconsistentCalendar(#[from] ValidationError),
+    #[error("{0} is at {1}, which does not exist in the calendar")]
+    InvalidDate(String, RawDateTime),
+}
+
+/// Something that happened while time moved forward.
+#[derive(Debug, Clone, PartialEq)]
+pub enum WorldEvent {
+    /// A new month began, given by its year and month.
+    MonthRollover(i64, u32),
+    /// The home planet entered a new season.
+    SeasonChanged(Season),
+    /// A moon of the home planet entered a new phase.
+    PhaseChanged(PlanetId, Phase),
+    /// A scheduled event happened and was recorded.
+    ScheduledRecord(RawDateTime, String),
+}
+
+/// The dates that were out of bounds when switching calendars.
+#[derive(Debug, Default)]
+pub struct CalendarChangeReport {
+    pub invalid: Vec<InvalidatedDate>,
+}
+
+/// A date that does not exist in a new calendar,
+/// along with the nearest one that does.
+#[derive(Debug)]
+pub struct InvalidatedDate {
+    pub label: String,
+    pub before: RawDateTime,
+    pub after: RawDateTime,
+}
+
+impl Display for InvalidatedDate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "- {} at {}", self.label, self.before)
+    }
+}
+
+/// Gets the start of a day, so that days can be
+/// checked and clamped in the same way as times.
+fn on_day(date: RawDate) -> RawDateTime {
+    RawDateTime {
+        date,
+        time: RawTime::default(),
+    }
+}
+
+fn is_valid(calendar: &Calendar, date: &RawDateTime) -> bool {
+    let date = date.into_datetime(calendar);
+    calendar.validate_date(&date.date).is_ok() && calendar.validate_time(&date.time).is_ok()
+}
+
+/// Moves each component of a date into the bounds of a calendar.
+fn clamp_date(calendar: &Calendar, date: &RawDateTime) -> RawDateTime {
+    let month = date
+        .date
+        .month
+        .min(calendar.months_in_year().saturating_sub(1));
+    let days = calendar.months().get(month as usize).map_or(0, |m| m.days);
+    RawDateTime {
+        date: RawDate::from_index(
+            date.date.year,
+            month,
+            date.date.day.min(days.saturating_sub(1)),
+        ),
+        time: RawTime {
+            hour: date
+                .time
+                .hour
+                .min(calendar.hours_in_day().saturating_sub(1)),
+            minute: date
+                .time
+                .minute
+                .min(calendar.minutes_in_hour().saturating_sub(1)),
+            second: date
+                .time
+                .second
+                .min(calendar.seconds_in_minute().saturating_sub(1)),
+        },
     }
 }
 
@@ -195,11 +984,15 @@ impl PlanetStore for World {
         self.bodies.iter_mut().find(|p| p.id == id)
     }
 
+    fn find_planets(&self, name: &str) -> Vec<&CelestialBody> {
+        self.bodies.iter().filter(|p| p.name == name).collect()
+    }
+
     fn create_planet(
         &mut self,
         name: String,
-        temperature: i32,
-        rotational_period: u32,
+        temperature: Kelvin,
+        rotational_period: Seconds,
         color: Color,
     ) -> &CelestialBody {
         let planet = CelestialBody::new(name, temperature, rotational_period, color);
@@ -224,12 +1017,23 @@ impl CharacterStore for World {
             .map(ToOwned::to_owned)
     }
 
-    fn create_character(&self) -> Character {
-        unimplemented!()
+    fn lookup_character_mut(&mut self, search: &str) -> Option<&mut Character> {
+        self.characters.iter_mut().find(|c| c.identifier == search)
     }
 
-    fn list_characters(&self) -> Vec<Character> {
-        self.characters.clone()
+    fn create_character(&mut self, identifier: &str) -> Character {
+        let character =
+            Character::new(identifier.to_string(), None, None, None).expect("valid identifier");
+        self.characters.push(character.clone());
+        character
+    }
+
+    fn list_characters(&self, include_archived: bool) -> Vec<Character> {
+        self.characters
+            .iter()
+            .filter(|c| include_archived || !c.archived())
+            .cloned()
+            .collect()
     }
 }
 
@@ -252,7 +1056,7 @@ impl LocationStore for World {
         let loc = Location {
             id: LocationId(Uuid::new_v4()),
             identifier: identifier.to_string(),
-            planet: PlanetId("68f83dbc-1894-4d2f-b3a2-ec9fe59f8071".parse().unwrap()),
+            planet: self.home_planet,
         };
         self.locations.push(loc.clone());
         loc
@@ -265,24 +1069,501 @@ impl LocationStore for World {
 
 impl Default for World {
     fn default() -> Self {
+        Self::from_calendar(Calendar::default())
+    }
+}
+
+impl World {
+    /// Creates a world with an earth-like home planet, orbited
+    /// by a moon and orbiting a sun, following some calendar.
+    /// The home planet turns once a calendar day and orbits
+    /// once a calendar year, so the world is consistent.
+    pub fn from_calendar(calendar: Calendar) -> Self {
+        let day = Days(1).to_seconds(&calendar);
+        let month = Days(28).to_seconds(&calendar);
+        let year = Days(calendar.days_in_year());
+
         let mut world = Self::new(
             "World".to_string(),
-            Default::default(),
-            CelestialBody::new("Earth".to_string(), 290, 86400, Color::Green),
+            calendar,
+            CelestialBody::new("Earth".to_string(), Kelvin(290), day, Color::Green),
         );
 
         let home = world.home_planet;
 
+        // the moon is tidally locked, so its day is as long as its orbit
         let moon = world
-            .create_planet("Moon".to_string(), 240, 2419200, Color::White)
+            .create_planet("Moon".to_string(), Kelvin(240), month, Color::White)
             .id;
-        world.add_orbit(home, moon, 1);
+        world.add_orbit(home, moon, Days(1));
 
         let sun = world
-            .create_planet("Sun".to_string(), 5800, 2419200, Color::Yellow)
+            .create_planet("Sun".to_string(), Kelvin(5800), month, Color::Yellow)
             .id;
-        world.add_orbit(sun, home, 365);
+        world.add_orbit(sun, home, year);
+
+        let sizes = [
+            (home, EARTH_MASS, EARTH_RADIUS),
+            (moon, 7.342e22, 1.737e6),
+            (sun, 1.989e30, 6.957e8),
+        ];
+        for (id, mass, radius) in sizes {
+            let body = world.get_planet_mut(id).expect("We just created it");
+            body.mass = Some(mass);
+            body.radius = Some(radius);
+        }
+
+        world
+    }
+}
+
+/// Finds the name closest to some name, if any are only a few typos
+/// away. Longer names are allowed more typos before they are too far.
+fn closest<'a>(name: &str, names: impl Iterator<Item = &'a str>) -> Option<&'a str> {
+    let allowed = (name.chars().count() / 4).max(1);
+    names
+        .map(|n| (typos(name, n), n))
+        .filter(|(d, _)| *d <= allowed)
+        .min_by_key(|(d, _)| *d)
+        .map(|(_, n)| n)
+}
+
+/// Counts the insertions, deletions, substitutions, and swaps of
+/// adjacent letters it takes to turn one name into another.
+fn typos(a: &str, b: &str) -> usize {
+    let (a, b): (Vec<_>, Vec<_>) = (a.chars().collect(), b.chars().collect());
+    let mut rows = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in rows.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in rows[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut d = (rows[i - 1][j] + 1)
+                .min(rows[i][j - 1] + 1)
+                .min(rows[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d = d.min(rows[i - 2][j - 2] + 1);
+            }
+            rows[i][j] = d;
+        }
+    }
+
+    rows[a.len()][b.len()]
+}
+
+#[cfg(test)]
+mod test {
+    use holding_color::Color;
+    use holding_kronos::units::Seconds;
+    use holding_kronos::{
+        calendar::{Calendar, Day, Month, Week, Year},
+        datetime::{DateTime, RawDate, RawDateTime, RawTime, Schedule},
+    };
+    use holding_solar::{Kelvin, PlanetStore};
+    use itertools::Itertools;
+    use test_case::test_case;
+
+    use super::{typos, World, WorldError, WorldEvent};
+    use crate::{
+        character::{CharacterStore, LocationStore},
+        record::RawRecord,
+        testing::add_character,
+    };
+
+    fn fixture() -> World {
+        let mut world = World::default();
+        world.add_record("The party arrived at #waterdeep".to_string());
+        world.add_record("A dragon was sighted over the hills".to_string());
+        world.add_record("The party left #waterdeep at dawn".to_string());
+        world
+    }
+
+    #[test]
+    pub fn searches_substring() {
+        let world = fixture();
+        let results = world.search_records("party", false).expect("valid query");
+
+        assert_eq!(results.len(), 2);
+        assert!(results[0].date < results[1].date);
+        assert_eq!(results[0].note, "The party arrived at #waterdeep");
+    }
+
+    #[test]
+    pub fn searches_regex() {
+        let world = fixture();
+        let results = world
+            .search_records(r"drag(on|oon)", true)
+            .expect("valid query");
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].note, "A dragon was sighted over the hills");
+    }
+
+    #[test]
+    pub fn substring_is_not_a_regex() {
+        let world = fixture();
+        assert!(world
+            .search_records("(", false)
+            .expect("valid query")
+            .is_empty());
+        assert!(world.search_records("(", true).is_err());
+    }
+
+    #[test]
+    pub fn records_scheduled_events() {
+        let mut world = World::default();
+        let start = world.time;
+        let weekly = world.calendar.parse_duration("1w").expect("valid duration");
+        world.add_schedule(
+            Schedule::new(start, weekly),
+            "The market opened in #waterdeep".to_string(),
+        );
+
+        world.update_time("3w").expect("can step");
+        assert_eq!(world.records.len(), 3);
+
+        world.time = start;
+        world.update_time("20d").expect("can step");
+        world.update_time("1d").expect("can step");
+        assert_eq!(world.records.len(), 3);
+
+        world.update_time("6d").expect("can step");
+        assert_eq!(world.records.len(), 3);
+        world.update_time("1d").expect("can step");
+        assert_eq!(world.records.len(), 4);
+        assert!(world
+            .records
+            .iter()
+            .tuple_windows()
+            .all(|(a, b)| a.date < b.date));
+    }
+
+    #[test]
+    pub fn reports_season_changes() {
+        let mut world = World::default();
+        let home = world.home_planet;
+        world.get_planet_mut(home).expect("home exists").axial_tilt = 0.41;
+        let start = world.time;
+        world.add_schedule(
+            Schedule::new(start, world.calendar.parse_duration("1w").expect("valid")),
+            "The market opened".to_string(),
+        );
+
+        let events = world.advance_with_events("1s").expect("can step");
+        assert!(events.is_empty(), "{:?}", events);
+
+        let events = world.advance_with_events("6mo").expect("can step");
+        let now = world.time.into_datetime(&world.calendar);
+        let season = world.season_at(now).expect("tilted planets have seasons");
+        assert_ne!(
+            Some(season),
+            world.season_at(start.into_datetime(&world.calendar))
+        );
+        assert!(events.contains(&WorldEvent::SeasonChanged(season)));
+        assert!(events.contains(&WorldEvent::MonthRollover(1, 7)));
+        assert_eq!(
+            events
+                .iter()
+                .filter(|e| matches!(e, WorldEvent::MonthRollover(_, _)))
+                .count(),
+            6
+        );
+        assert_eq!(
+            events
+                .iter()
+                .filter(|e| matches!(e, WorldEvent::ScheduledRecord(_, _)))
+                .count(),
+            world.records.len()
+        );
+    }
+
+    #[test]
+    pub fn reports_every_boundary_crossed() {
+        let mut world = World::default();
+        let home = world.home_planet;
+        world.get_planet_mut(home).expect("home exists").axial_tilt = 0.41;
+        let moon = world
+            .get_planet(home)
+            .expect("home exists")
+            .children
+            .iter()
+            .filter_map(|c| world.get_planet(*c))
+            .find_map(|c| c.orbit)
+            .expect("the default world has a moon");
+        let synodic = moon.synodic_period(&world).expect("has phases");
 
+        let count = |events: &[WorldEvent], f: fn(&WorldEvent) -> bool| {
+            events.iter().filter(|e| f(e)).count()
+        };
+
+        let events = world
+            .advance_with_events(&format!("{}s", synodic.0))
+            .expect("can step");
+        assert_eq!(
+            count(&events, |e| matches!(e, WorldEvent::PhaseChanged(_, _))),
+            8
+        );
+
+        let events = world.advance_with_events("1y").expect("can step");
+        assert_eq!(
+            count(&events, |e| matches!(e, WorldEvent::SeasonChanged(_))),
+            4
+        );
+        assert_eq!(
+            count(&events, |e| matches!(e, WorldEvent::MonthRollover(_, _))),
+            12
+        );
+    }
+
+    #[test]
+    pub fn indexes_records() {
+        let mut world = World::default();
+        for name in &["gandalf", "frodo", "sam"] {
+            add_character(&mut world, name);
+        }
+        for i in 0..10_000 {
+            let note = match i % 4 {
+                0 => "@gandalf and @frodo met at #bree",
+                1 => "@sam cooked at #shire",
+                2 => "@frodo returned to #shire",
+                _ => "Nothing happened",
+            };
+            world.add_record(note.to_string());
+        }
+
+        let frodo = world.lookup_character("frodo").expect("exists").id;
+        let shire = world.lookup_location("shire").expect("exists").id;
+
+        let scan = |f: &dyn Fn(&RawRecord) -> bool| -> Vec<_> {
+            world
+                .records
+                .iter()
+                .filter(|r| f(r))
+                .map(|r| r.id)
+                .collect()
+        };
+        let ids = |records: Vec<&RawRecord>| -> Vec<_> { records.iter().map(|r| r.id).collect() };
+
+        assert_eq!(
+            ids(world.records_for_character(frodo)),
+            scan(&|r| r.chars.iter().any(|c| c.id == frodo))
+        );
+        assert_eq!(
+            ids(world.records_for_location(shire)),
+            scan(&|r| r.locs.iter().any(|l| l.id == shire))
+        );
+        assert_eq!(world.records_for_character(frodo).len(), 5_000);
+        assert_eq!(world.index.builds(), 1);
+
+        world.add_record("@frodo left the #shire".to_string());
+        assert_eq!(world.records_for_location(shire).len(), 5_001);
+        assert_eq!(world.records_for_character(frodo).len(), 5_001);
+        assert_eq!(world.index.builds(), 2);
+    }
+
+    /// The default calendar, but with every month 28 days long.
+    fn short_months() -> Calendar {
+        let year = Year::new(
+            Year::default()
+                .iter()
+                .map(|m| Month {
+                    name: m.name.clone(),
+                    days: 28,
+                })
+                .collect(),
+        );
+        Calendar::new(year, Week::default(), Day::default())
+    }
+
+    /// A time on the nth day of the first month of a world's year.
+    fn day_of_first_month(world: &World, day: u32, hour: u32) -> RawDateTime {
+        RawDateTime {
+            date: RawDate::from_index(world.time.date.year, 0, day),
+            time: RawTime {
+                hour,
+                minute: 0,
+                second: 0,
+            },
+        }
+    }
+
+    #[test]
+    pub fn switching_calendar_catches_invalid_dates() {
+        let mut world = World::default();
+        world.update_time("30d").expect("can step");
+        world.add_record("The party arrived at #waterdeep".to_string());
+        let shorter = short_months();
+
+        assert!(world.set_calendar(shorter.clone(), false).is_err());
+        assert_eq!(world.calendar, Calendar::default());
+
+        let report = world.set_calendar(shorter, true).expect("can clamp");
+        assert_eq!(report.invalid.len(), 2);
+        assert_eq!(report.invalid[0].before.date.day, 30);
+        assert_eq!(world.time.date.day, 27);
+        assert_eq!(world.records[0].date.date.day, 27);
+    }
+
+    #[test]
+    pub fn new_locations_are_on_the_home_planet() {
+        let mut world = World::default();
+        add_character(&mut world, "gandalf");
+        world.move_character("gandalf", "moria").expect("can move");
+
+        let moria = world
+            .lookup_location("moria")
+            .expect("location was created");
+        assert_eq!(moria.planet, world.home_planet);
+    }
+
+    #[test]
+    pub fn switching_calendar_clamps_every_date() {
+        let mut world = World::default();
+        let late = day_of_first_month(&world, 29, 0);
+        let weekly = world.calendar.parse_duration("1w").expect("valid duration");
+        world.add_schedule(Schedule::new(late, weekly), "Market day".to_string());
+        world.set_weather(day_of_first_month(&world, 28, 0).date, "Rain".to_string());
+        world.set_weather(late.date, "Snow".to_string());
+        add_character(&mut world, "gandalf").add_note(late, "Arrived late".to_string());
+
+        assert!(world.set_calendar(short_months(), false).is_err());
+        let report = world.set_calendar(short_months(), true).expect("can clamp");
+        assert_eq!(report.invalid.len(), 4);
+
+        let last = day_of_first_month(&world, 27, 0);
+        assert_eq!(world.schedules[0].0.start, last);
+        assert_eq!(world.weather_overrides.len(), 1);
+        assert_eq!(world.weather_on(last.date), Some("Snow"));
+        assert_eq!(world.characters[0].notes()[0].0, last);
+        assert!(!world
+            .issues()
+            .iter()
+            .any(|i| matches!(i, WorldError::InvalidDate(..))));
+    }
+
+    #[test]
+    pub fn clamping_keeps_records_in_order() {
+        let mut world = World::default();
+        world.update_time("60d").expect("can step");
+        let earlier = day_of_first_month(&world, 27, 12);
+        let later = day_of_first_month(&world, 29, 6);
+        world
+            .add_record_at("The party arrived".to_string(), None, earlier)
+            .expect("in the past");
         world
+            .add_record_at("The party left".to_string(), None, later)
+            .expect("in the past");
+
+        world.set_calendar(short_months(), true).expect("can clamp");
+        assert_eq!(world.records[0].note, "The party left");
+        assert!(world.records[0].date < world.records[1].date);
+
+        let date = day_of_first_month(&world, 27, 9);
+        let record = world
+            .add_record_at("The party rested".to_string(), None, date)
+            .expect("in the past");
+        assert_eq!(record.note, "The party rested");
+        assert_eq!(world.records[1].note, "The party rested");
+    }
+
+    #[test]
+    pub fn finds_invalid_dates_everywhere() {
+        let mut world = World::default();
+        let late = day_of_first_month(&world, 29, 0);
+        let weekly = world.calendar.parse_duration("1w").expect("valid duration");
+        world.add_schedule(Schedule::new(late, weekly), "Market day".to_string());
+        world.set_weather(late.date, "Snow".to_string());
+        add_character(&mut world, "gandalf").add_note(late, "Arrived late".to_string());
+        world.calendar = short_months();
+
+        let issues = world
+            .issues()
+            .into_iter()
+            .filter(|i| matches!(i, WorldError::InvalidDate(..)))
+            .map(|i| i.to_string())
+            .collect_vec();
+        assert_eq!(issues.len(), 3);
+        assert!(issues[0].starts_with("the schedule \"Market day\""));
+        assert!(issues[1].starts_with("the weather \"Snow\""));
+        assert!(issues[2].starts_with("a note about @gandalf"));
+    }
+
+    #[test]
+    pub fn counts_long_campaigns() {
+        let mut world = World::default();
+        world.time = DateTime::from_seconds(86400 * 365 * 200, &world.calendar).into();
+        assert_eq!(world.elapsed().expect("the years fit").to_string(), "200y");
+    }
+
+    #[test]
+    pub fn diffs_mutated_copy() {
+        let before = fixture();
+        let mut after = before.clone();
+        assert!(before.diff(&after).expect("can compare").is_empty());
+
+        after.records.remove(1);
+        after.records[0].note = "The party arrived at #baldursgate".to_string();
+        after.add_record("The party rested".to_string());
+        add_character(&mut after, "gandalf");
+        after.create_planet(
+            "Nibiru".to_string(),
+            Kelvin(100),
+            Seconds(86400),
+            Color::Blue,
+        );
+        after.update_time("2d").expect("can step");
+
+        let diff = before.diff(&after).expect("can compare");
+        assert_eq!(diff.records.added, vec!["The party rested"]);
+        assert_eq!(
+            diff.records.removed,
+            vec!["A dragon was sighted over the hills"]
+        );
+        assert_eq!(
+            diff.records.edited,
+            vec!["The party arrived at #baldursgate"]
+        );
+        assert_eq!(diff.characters.added, vec!["@gandalf"]);
+        assert_eq!(diff.bodies.added, vec!["Nibiru"]);
+        assert!(diff.locations.is_empty());
+        assert_eq!(diff.duration.days, 2);
+        assert!(diff.elapsed > 0);
+
+        let report = diff.to_string();
+        assert!(report.contains("+ @gandalf"));
+        assert!(report.contains("- A dragon was sighted over the hills"));
+
+        let start = before.time.into_datetime(&before.calendar).total_seconds();
+        after.time = DateTime::from_seconds(start + 86400 * 365 * 200, &after.calendar).into();
+        let diff = before.diff(&after).expect("the years fit");
+        assert_eq!(diff.duration.to_string(), "200y");
+    }
+
+    #[test_case("gandalf", "gandalf", 0 ; "same")]
+    #[test_case("gandlaf", "gandalf", 1 ; "swapped")]
+    #[test_case("gandlf", "gandalf", 1 ; "missing")]
+    #[test_case("frodo", "gandalf", 6 ; "unrelated")]
+    pub fn counts_typos(a: &str, b: &str, expected: usize) {
+        assert_eq!(typos(a, b), expected);
+    }
+
+    #[test]
+    pub fn creates_unknown_characters() {
+        let mut world = World::default();
+        world.add_record("@gandalf arrived at #hobbiton".to_string());
+
+        assert!(world.lookup_character("gandalf").is_some());
+        assert_eq!(
+            world.suggest_references("@gandlaf left #hobbitn, @frodo stayed"),
+            vec![
+                ("@gandlaf".to_string(), "@gandalf".to_string()),
+                ("#hobbitn".to_string(), "#hobbiton".to_string()),
+            ]
+        );
     }
 }